@@ -0,0 +1,439 @@
+use {
+    bevy::prelude::*,
+    serde::{de, Deserialize, Deserializer, Serialize, Serializer},
+    std::{
+        cmp::Ordering,
+        fmt,
+        iter::Sum,
+        ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign},
+    },
+};
+
+/// Mantissas at or above this move digits into the exponent.
+const MANTISSA_LIMIT: f64 = 1e15;
+
+/// Exponent gaps past this make the smaller operand vanish next to the larger one.
+const MAX_PRECISION_GAP: i64 = 30;
+
+/// Suffixes for thousands, millions and billions. Anything larger is written
+/// in scientific notation.
+const SUFFIXES: [&str; 4] = ["", "K", "M", "B"];
+
+/// Currency amount of `mantissa * 10^exponent`, used for balances, rewards and prices.
+///
+/// Values below `1e15` are stored as a plain `f64` with a zero exponent, so they
+/// are exactly as precise as before. Larger values keep about 15 significant
+/// digits and never overflow.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Default)]
+pub struct BigNumber {
+    mantissa: f64,
+    exponent: i64,
+}
+
+impl BigNumber {
+    pub const ZERO: Self = Self {
+        mantissa: 0.0,
+        exponent: 0,
+    };
+
+    pub const ONE: Self = Self {
+        mantissa: 1.0,
+        exponent: 0,
+    };
+
+    /// Builds `mantissa * 10^exponent`. A mantissa that is not finite counts as zero.
+    pub fn new(mantissa: f64, exponent: i64) -> Self {
+        if mantissa == 0.0 || !mantissa.is_finite() {
+            return Self::ZERO;
+        }
+
+        let (mut mantissa, mut exponent) = (mantissa, exponent);
+        if exponent < 0 {
+            mantissa *= pow10(exponent);
+            exponent = 0;
+        }
+        if mantissa.abs() >= MANTISSA_LIMIT {
+            let shift = (mantissa.abs().log10().floor() as i64 - 14).max(1);
+            mantissa /= pow10(shift);
+            exponent += shift;
+        } else if exponent > 0 && mantissa.abs() < MANTISSA_LIMIT / 10.0 {
+            let shift = (14 - mantissa.abs().log10().floor() as i64).clamp(1, exponent);
+            mantissa *= pow10(shift);
+            exponent -= shift;
+        }
+
+        // `log10` can be a digit off right at a power of ten
+        if mantissa.abs() >= MANTISSA_LIMIT {
+            mantissa /= 10.0;
+            exponent += 1;
+        } else if exponent > 0 && mantissa.abs() < MANTISSA_LIMIT / 10.0 {
+            mantissa *= 10.0;
+            exponent -= 1;
+        }
+
+        if mantissa == 0.0 {
+            return Self::ZERO;
+        }
+        Self { mantissa, exponent }
+    }
+
+    /// `base ^ exponent`, computed in log space once it leaves the `f64` range.
+    /// `base` must be positive.
+    pub fn pow(base: f64, exponent: f64) -> Self {
+        let log = exponent * base.log10();
+        if log < 15.0 {
+            return Self::from(base.powf(exponent));
+        }
+        let shift = log.floor() - 14.0;
+        Self::new(10f64.powf(log - shift), shift as i64)
+    }
+
+    /// Closest `f64`, infinite when the value is out of its range.
+    pub fn to_f64(&self) -> f64 {
+        self.mantissa * pow10(self.exponent)
+    }
+
+    /// Base 10 logarithm, `-inf` for zero.
+    pub fn log10(&self) -> f64 {
+        self.mantissa.abs().log10() + self.exponent as f64
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0.0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.mantissa < 0.0
+    }
+
+    pub fn abs(&self) -> Self {
+        Self {
+            mantissa: self.mantissa.abs(),
+            exponent: self.exponent,
+        }
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        if other > self {
+            other
+        } else {
+            self
+        }
+    }
+
+    pub fn min(self, other: Self) -> Self {
+        if other < self {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+/// `10^exponent`, saturating to infinity or zero outside the `f64` range.
+fn pow10(exponent: i64) -> f64 {
+    10f64.powi(exponent.clamp(-400, 400) as i32)
+}
+
+impl From<f64> for BigNumber {
+    fn from(value: f64) -> Self {
+        Self::new(value, 0)
+    }
+}
+
+impl From<f32> for BigNumber {
+    fn from(value: f32) -> Self {
+        Self::new(value as f64, 0)
+    }
+}
+
+impl From<u32> for BigNumber {
+    fn from(value: u32) -> Self {
+        Self::new(value as f64, 0)
+    }
+}
+
+impl Add for BigNumber {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let (high, low) = if self.exponent >= rhs.exponent {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        let gap = high.exponent - low.exponent;
+        if gap > MAX_PRECISION_GAP {
+            return high;
+        }
+        Self::new(high.mantissa + low.mantissa / pow10(gap), high.exponent)
+    }
+}
+
+impl Sub for BigNumber {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.add(-rhs)
+    }
+}
+
+impl Neg for BigNumber {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            mantissa: -self.mantissa,
+            exponent: self.exponent,
+        }
+    }
+}
+
+impl Mul for BigNumber {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(self.mantissa * rhs.mantissa, self.exponent + rhs.exponent)
+    }
+}
+
+impl Mul<f64> for BigNumber {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self {
+        Self::new(self.mantissa * rhs, self.exponent)
+    }
+}
+
+impl Div for BigNumber {
+    type Output = Self;
+
+    /// # Panics
+    /// When dividing by zero, like integer division.
+    fn div(self, rhs: Self) -> Self {
+        assert!(!rhs.is_zero(), "attempt to divide a BigNumber by zero");
+        Self::new(self.mantissa / rhs.mantissa, self.exponent - rhs.exponent)
+    }
+}
+
+impl Div<f64> for BigNumber {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self {
+        self / Self::from(rhs)
+    }
+}
+
+impl AddAssign for BigNumber {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for BigNumber {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign<f64> for BigNumber {
+    fn mul_assign(&mut self, rhs: f64) {
+        *self = *self * rhs;
+    }
+}
+
+impl Sum for BigNumber {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl PartialOrd for BigNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (*self - *other).mantissa.partial_cmp(&0.0)
+    }
+}
+
+impl PartialEq<f64> for BigNumber {
+    fn eq(&self, other: &f64) -> bool {
+        *self == Self::from(*other)
+    }
+}
+
+impl PartialOrd<f64> for BigNumber {
+    fn partial_cmp(&self, other: &f64) -> Option<Ordering> {
+        self.partial_cmp(&Self::from(*other))
+    }
+}
+
+/// Idle game style: `12.5`, `1.23K`, `45.6M`, `4.5e12`.
+impl fmt::Display for BigNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_negative() {
+            write!(f, "-")?;
+        }
+        let value = self.abs();
+
+        if value < 1000.0 {
+            return write!(f, "{}", trim_zeros(format!("{:.2}", value.to_f64())));
+        }
+
+        // Round to three significant digits first so 999.9K becomes 1M
+        let mut digits = value.log10().floor() as i64;
+        let mut leading = 10f64.powf(value.log10() - digits as f64);
+        leading = (leading * 100.0).round() / 100.0;
+        if leading >= 10.0 {
+            leading /= 10.0;
+            digits += 1;
+        }
+
+        let group = (digits / 3) as usize;
+        if group < SUFFIXES.len() {
+            let scaled = leading * pow10(digits % 3);
+            let decimals = 2 - (digits % 3) as usize;
+            let text = trim_zeros(format!("{:.*}", decimals, scaled));
+            write!(f, "{}{}", text, SUFFIXES[group])
+        } else {
+            let text = trim_zeros(format!("{:.2}", leading));
+            write!(f, "{}e{}", text, digits)
+        }
+    }
+}
+
+fn trim_zeros(text: String) -> String {
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        text
+    }
+}
+
+/// Written as a plain number while it fits one, as `"<mantissa>e<exponent>"` beyond that.
+impl Serialize for BigNumber {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.exponent == 0 {
+            serializer.serialize_f64(self.mantissa)
+        } else {
+            serializer.serialize_str(&format!("{}e{}", self.mantissa, self.exponent))
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BigNumber {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(BigNumberVisitor)
+    }
+}
+
+struct BigNumberVisitor;
+
+impl de::Visitor<'_> for BigNumberVisitor {
+    type Value = BigNumber;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a number or a \"<mantissa>e<exponent>\" string")
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<BigNumber, E> {
+        Ok(BigNumber::from(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<BigNumber, E> {
+        Ok(BigNumber::from(value as f64))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<BigNumber, E> {
+        Ok(BigNumber::from(value as f64))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<BigNumber, E> {
+        let (mantissa, exponent) = value
+            .rsplit_once(['e', 'E'])
+            .unwrap_or((value, "0"));
+        match (mantissa.parse::<f64>(), exponent.parse::<i64>()) {
+            (Ok(mantissa), Ok(exponent)) => Ok(BigNumber::new(mantissa, exponent)),
+            _ => Err(E::invalid_value(de::Unexpected::Str(value), &self)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_values_stay_exact() {
+        let a = BigNumber::from(1000.0);
+        let b = BigNumber::from(950.0);
+        assert_eq!(a - b, 50.0);
+        assert_eq!(BigNumber::from(10.0) + BigNumber::from(5.5), 15.5);
+        assert_eq!(BigNumber::from(200.0) * 1.5, 300.0);
+    }
+
+    #[test]
+    fn test_single_shards_count_past_f32_precision() {
+        let mut wallet = BigNumber::from(999_999_999_000.0);
+        wallet += BigNumber::ONE;
+        assert_eq!(wallet, 999_999_999_001.0);
+    }
+
+    #[test]
+    fn test_values_beyond_f64_range() {
+        let huge = BigNumber::pow(10.0, 400.0);
+        assert!(huge.to_f64().is_infinite());
+        assert!((huge.log10() - 400.0).abs() < 1e-9);
+
+        let double = huge * 2.0;
+        assert!(double > huge);
+        assert!(((double / huge).to_f64() - 2.0).abs() < 1e-9);
+        assert_eq!(huge - huge, 0.0);
+    }
+
+    #[test]
+    fn test_comparison() {
+        let small = BigNumber::from(5.0);
+        let large = BigNumber::pow(2.0, 100.0);
+        assert!(small < large);
+        assert!(-large < small);
+        assert!(large >= BigNumber::pow(2.0, 99.0));
+        assert_eq!(small.max(large), large);
+    }
+
+    #[test]
+    fn test_exponential_precision() {
+        // 19 digits, far past what f32 keeps
+        let value = BigNumber::pow(2.0, 60.0);
+        let expected = 1_152_921_504_606_846_976.0;
+        assert!(((value.to_f64() - expected) / expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_display() {
+        let text = |value: f64| BigNumber::from(value).to_string();
+        assert_eq!(text(0.0), "0");
+        assert_eq!(text(12.5), "12.5");
+        assert_eq!(text(999.0), "999");
+        assert_eq!(text(1234.0), "1.23K");
+        assert_eq!(text(45_600_000.0), "45.6M");
+        assert_eq!(text(100_000.0), "100K");
+        assert_eq!(text(999_999.0), "1M");
+        assert_eq!(text(7_000_000_000.0), "7B");
+        assert_eq!(text(4.5e12), "4.5e12");
+        assert_eq!(text(-1234.0), "-1.23K");
+        assert_eq!(BigNumber::pow(10.0, 400.0).to_string(), "1e400");
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        for value in [BigNumber::from(1234.5), BigNumber::pow(3.0, 500.0)] {
+            let text = ron::to_string(&value).unwrap();
+            let parsed: BigNumber = ron::from_str(&text).unwrap();
+            assert!((parsed.log10() - value.log10()).abs() < 1e-12, "{text}");
+        }
+
+        // Plain numbers from older files still load
+        let parsed: BigNumber = ron::from_str("1234.0").unwrap();
+        assert_eq!(parsed, 1234.0);
+    }
+}
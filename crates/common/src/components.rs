@@ -1,13 +1,24 @@
 use {
-    crate::{ConditionalUpgrade, GrowthStrategy},
+    crate::{Currencies, GrowthStrategy, Prerequisite, PriceStrategy},
     bevy::prelude::*,
     std::collections::HashMap,
 };
 
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
+#[require(PortalId, PortalSpawnTracker)]
 pub struct PortalRoot; // Marker for querying
 
+/// Identifies a portal across save files.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct PortalId(pub u32);
+
+/// Spawn index handed to the next monster of this portal.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct PortalSpawnTracker(pub u32);
+
 #[derive(Component, Reflect, Default, Debug)]
 #[reflect(Component)]
 pub struct PortalLevel {
@@ -18,8 +29,8 @@ pub struct PortalLevel {
 #[derive(Component, Reflect, Default, Debug)]
 #[reflect(Component)]
 pub struct UpgradeCost {
-    pub strategy: GrowthStrategy,
-    pub current_price: f32,
+    pub strategy: PriceStrategy,
+    pub current_price: Currencies,
 }
 
 #[derive(Component, Reflect, Default)]
@@ -29,6 +40,11 @@ pub struct PortalSpawner {
     pub interval_strategy: GrowthStrategy,
 }
 
+/// Distance between the top edge of the window and the portal.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct PortalTopOffset(pub f32);
+
 #[derive(Component, Reflect, Default, Debug)]
 #[reflect(Component)]
 pub struct BaseMonsterHealth(pub GrowthStrategy);
@@ -49,6 +65,16 @@ pub struct BaseMonsterLifetime(pub GrowthStrategy);
 #[reflect(Component)]
 pub struct BaseMonsterArmor(pub GrowthStrategy);
 
+/// Attack of the monsters a portal spawns, each part scaled by the active level.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct BaseMonsterAttack {
+    pub damage: GrowthStrategy,
+    pub range: GrowthStrategy,
+    /// Seconds between two attacks.
+    pub cooldown: GrowthStrategy,
+}
+
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
 pub struct ScavengerPenalty(pub f32);
@@ -63,6 +89,20 @@ pub struct UpgradeSlot {
 #[reflect(Component)]
 pub struct PortalUpgrades(pub HashMap<String, Entity>);
 
+/// Marks an upgrade slot whose prerequisites are not met yet.
 #[derive(Component, Reflect, Default, Clone)]
 #[reflect(Component)]
 pub struct LockedFeature;
+
+/// What an upgrade slot needs before it can be bought.
+#[derive(Component, Reflect, Default, Debug, Clone)]
+#[reflect(Component)]
+pub struct UpgradePrerequisites(pub Vec<Prerequisite>);
+
+impl UpgradePrerequisites {
+    pub fn all_met(&self, portal_level: u32, upgrade_level: impl Fn(&str) -> Option<f32>) -> bool {
+        self.0
+            .iter()
+            .all(|prerequisite| prerequisite.is_met(portal_level, &upgrade_level))
+    }
+}
@@ -0,0 +1,355 @@
+use {
+    crate::{BigNumber, GrowthStrategy},
+    bevy::prelude::*,
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::BTreeMap,
+        fmt,
+        ops::{AddAssign, Mul, SubAssign},
+    },
+};
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    Reflect,
+    Serialize,
+    Deserialize,
+)]
+pub enum Currency {
+    #[default]
+    VoidShards,
+    /// Dropped by elite monsters.
+    Essence,
+    /// Awarded by a prestige, the only currency a prestige keeps.
+    PrestigePoints,
+}
+
+impl Currency {
+    pub const ALL: [Self; 3] = [Self::VoidShards, Self::Essence, Self::PrestigePoints];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::VoidShards => "void shards",
+            Self::Essence => "essence",
+            Self::PrestigePoints => "prestige points",
+        }
+    }
+}
+
+/// Amounts of several currencies at once: a balance, a price or a reward.
+///
+/// Missing currencies count as zero and zero amounts are never stored, so two
+/// bundles holding the same amounts always compare equal.
+#[derive(Debug, Clone, Default, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Default)]
+#[serde(transparent)]
+pub struct Currencies(BTreeMap<Currency, BigNumber>);
+
+impl Currencies {
+    pub fn single(currency: Currency, amount: impl Into<BigNumber>) -> Self {
+        Self::default().with(currency, amount)
+    }
+
+    pub fn with(mut self, currency: Currency, amount: impl Into<BigNumber>) -> Self {
+        self.set(currency, amount.into());
+        self
+    }
+
+    pub fn get(&self, currency: Currency) -> BigNumber {
+        self.0.get(&currency).copied().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, currency: Currency, amount: BigNumber) {
+        if amount.is_zero() {
+            self.0.remove(&currency);
+        } else {
+            self.0.insert(currency, amount);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Currency, BigNumber)> + '_ {
+        self.0.iter().map(|(currency, amount)| (*currency, *amount))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Whether every amount of `price` is matched by this bundle.
+    pub fn covers(&self, price: &Currencies) -> bool {
+        price
+            .iter()
+            .all(|(currency, amount)| self.get(currency) >= amount)
+    }
+}
+
+impl From<BigNumber> for Currencies {
+    /// A bare amount is in void shards.
+    fn from(amount: BigNumber) -> Self {
+        Self::single(Currency::VoidShards, amount)
+    }
+}
+
+impl From<f64> for Currencies {
+    fn from(amount: f64) -> Self {
+        BigNumber::from(amount).into()
+    }
+}
+
+impl AddAssign<&Currencies> for Currencies {
+    fn add_assign(&mut self, rhs: &Currencies) {
+        for (currency, amount) in rhs.iter() {
+            self.set(currency, self.get(currency) + amount);
+        }
+    }
+}
+
+impl SubAssign<&Currencies> for Currencies {
+    fn sub_assign(&mut self, rhs: &Currencies) {
+        for (currency, amount) in rhs.iter() {
+            self.set(currency, self.get(currency) - amount);
+        }
+    }
+}
+
+impl Mul<f64> for Currencies {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self {
+        self.iter()
+            .fold(Self::default(), |acc, (currency, amount)| {
+                acc.with(currency, amount * rhs)
+            })
+    }
+}
+
+impl fmt::Display for Currencies {
+    /// Void shards are written bare, other currencies with their label:
+    /// `120 + 3 essence`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        for (index, (currency, amount)) in self.iter().enumerate() {
+            if index > 0 {
+                write!(f, " + ")?;
+            }
+            match currency {
+                Currency::VoidShards => write!(f, "{}", amount)?,
+                _ => write!(f, "{} {}", amount, currency.label())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How a price grows with the level, paid in one currency or a bundle of them.
+///
+/// In configs a bare `GrowthStrategy` is paid in void shards, `In(Essence, ...)`
+/// names another currency and `Bundle({...})` charges several at once.
+#[derive(Debug, Clone, Default, PartialEq, Reflect, Deserialize)]
+#[serde(from = "PriceConfig")]
+pub struct PriceStrategy(pub BTreeMap<Currency, GrowthStrategy>);
+
+impl PriceStrategy {
+    pub fn single(currency: Currency, strategy: GrowthStrategy) -> Self {
+        Self(BTreeMap::from([(currency, strategy)]))
+    }
+
+    pub fn with(mut self, currency: Currency, strategy: GrowthStrategy) -> Self {
+        self.0.insert(currency, strategy);
+        self
+    }
+
+    /// Price of the level after `level`.
+    pub fn calculate(&self, level: f32) -> Currencies {
+        self.0
+            .iter()
+            .fold(Currencies::default(), |price, (currency, strategy)| {
+                price.with(*currency, strategy.calculate_big(level))
+            })
+    }
+
+    /// Price of buying `count` levels from `from_level` at once.
+    pub fn cumulative_cost(&self, from_level: f32, count: u32) -> Currencies {
+        self.0
+            .iter()
+            .fold(Currencies::default(), |price, (currency, strategy)| {
+                price.with(*currency, strategy.cumulative_cost(from_level, count))
+            })
+    }
+
+    /// Levels `budget` pays for, limited by the scarcest currency of the bundle.
+    pub fn max_affordable(&self, from_level: f32, budget: &Currencies) -> u32 {
+        self.0
+            .iter()
+            .map(|(currency, strategy)| {
+                strategy.max_affordable(from_level, budget.get(*currency))
+            })
+            .min()
+            .unwrap_or(u32::MAX)
+    }
+}
+
+impl From<GrowthStrategy> for PriceStrategy {
+    /// A bare strategy is paid in void shards.
+    fn from(strategy: GrowthStrategy) -> Self {
+        Self::single(Currency::VoidShards, strategy)
+    }
+}
+
+/// Config form of [`PriceStrategy`]. Mirrors the `GrowthStrategy` variants so
+/// configs written before other currencies existed still load.
+#[derive(Deserialize)]
+enum PriceConfig {
+    Static(f32),
+    Linear {
+        base: f32,
+        coefficient: f32,
+    },
+    Exponential {
+        base: f32,
+        factor: f32,
+    },
+    Incremental {
+        base: f32,
+        step: f32,
+    },
+    Chunked {
+        base: f32,
+        interval: f32,
+        step: f32,
+    },
+    In(Currency, GrowthStrategy),
+    Bundle(BTreeMap<Currency, GrowthStrategy>),
+}
+
+impl From<PriceConfig> for PriceStrategy {
+    fn from(config: PriceConfig) -> Self {
+        match config {
+            PriceConfig::Static(value) => GrowthStrategy::Static(value).into(),
+            PriceConfig::Linear { base, coefficient } => {
+                GrowthStrategy::Linear { base, coefficient }.into()
+            }
+            PriceConfig::Exponential { base, factor } => {
+                GrowthStrategy::Exponential { base, factor }.into()
+            }
+            PriceConfig::Incremental { base, step } => {
+                GrowthStrategy::Incremental { base, step }.into()
+            }
+            PriceConfig::Chunked {
+                base,
+                interval,
+                step,
+            } => GrowthStrategy::Chunked {
+                base,
+                interval,
+                step,
+            }
+            .into(),
+            PriceConfig::In(currency, strategy) => Self::single(currency, strategy),
+            PriceConfig::Bundle(strategies) => Self(strategies),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_amounts_are_not_stored() {
+        let mut balance = Currencies::single(Currency::Essence, 5.0);
+        balance -= &Currencies::single(Currency::Essence, 5.0);
+
+        assert!(balance.is_zero());
+        assert_eq!(balance, Currencies::default());
+        assert_eq!(Currencies::from(0.0), Currencies::default());
+    }
+
+    #[test]
+    fn test_covers_checks_every_currency() {
+        let balance = Currencies::from(100.0).with(Currency::Essence, 2.0);
+        let bundle = Currencies::from(50.0).with(Currency::Essence, 3.0);
+
+        assert!(balance.covers(&Currencies::from(100.0)));
+        assert!(!balance.covers(&bundle));
+        assert!(balance.covers(&Currencies::default()));
+    }
+
+    #[test]
+    fn test_add_and_scale() {
+        let mut balance = Currencies::from(10.0);
+        balance += &(Currencies::from(5.0).with(Currency::Essence, 1.0) * 2.0);
+
+        assert_eq!(balance.get(Currency::VoidShards), 20.0);
+        assert_eq!(balance.get(Currency::Essence), 2.0);
+        assert_eq!(balance.get(Currency::PrestigePoints), 0.0);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Currencies::default().to_string(), "0");
+        assert_eq!(Currencies::from(120.0).to_string(), "120");
+        assert_eq!(
+            Currencies::from(120.0)
+                .with(Currency::Essence, 3.0)
+                .to_string(),
+            "120 + 3 essence"
+        );
+    }
+
+    #[test]
+    fn test_bundle_affordable_levels_follow_scarcest_currency() {
+        let price = PriceStrategy::from(GrowthStrategy::Static(10.0))
+            .with(Currency::Essence, GrowthStrategy::Static(1.0));
+
+        let budget = Currencies::from(100.0).with(Currency::Essence, 3.0);
+        assert_eq!(price.max_affordable(0.0, &budget), 3);
+        assert_eq!(
+            price.cumulative_cost(0.0, 3),
+            Currencies::from(30.0).with(Currency::Essence, 3.0)
+        );
+    }
+
+    #[test]
+    fn test_price_config_forms() {
+        let bare: PriceStrategy = ron::from_str("Linear(base: 10.0, coefficient: 5.0)").unwrap();
+        assert_eq!(
+            bare,
+            PriceStrategy::from(GrowthStrategy::Linear {
+                base: 10.0,
+                coefficient: 5.0,
+            })
+        );
+
+        let single: PriceStrategy = ron::from_str("In(Essence, Static(2.0))").unwrap();
+        assert_eq!(
+            single,
+            PriceStrategy::single(Currency::Essence, GrowthStrategy::Static(2.0))
+        );
+
+        let bundle: PriceStrategy =
+            ron::from_str("Bundle({VoidShards: Static(50.0), Essence: Static(1.0)})").unwrap();
+        assert_eq!(
+            bundle.calculate(0.0),
+            Currencies::from(50.0).with(Currency::Essence, 1.0)
+        );
+    }
+
+    #[test]
+    fn test_currencies_ron_roundtrip() {
+        let balance = Currencies::from(1234.0).with(Currency::PrestigePoints, 3.0);
+        let text = ron::to_string(&balance).unwrap();
+        assert_eq!(ron::from_str::<Currencies>(&text).unwrap(), balance);
+    }
+}
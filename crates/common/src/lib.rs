@@ -2,6 +2,12 @@
 
 use bevy::prelude::*;
 
+pub mod big_number;
+pub use big_number::*;
+
+pub mod currency;
+pub use currency::*;
+
 pub mod messages;
 pub use messages::*;
 
@@ -14,6 +20,9 @@ pub use components::*;
 pub mod requirements;
 pub use requirements::*;
 
+pub mod spatial;
+pub use spatial::*;
+
 pub struct CommonPlugin;
 
 impl Plugin for CommonPlugin {
@@ -25,44 +34,103 @@ impl Plugin for CommonPlugin {
             .add_message::<RequestUpgrade>()
             .add_message::<ChangeActiveLevel>()
             .add_message::<MonsterScavenged>()
+            .add_message::<GrantCurrency>()
+            .add_message::<SetPortalLevel>()
+            .add_message::<KillAllMonsters>()
+            .add_message::<SetTimeScale>()
             .add_message::<DamageMessage>()
             .add_message::<MeleeHitMessage>()
+            .add_message::<WeaponMasteryLeveledUp>()
+            .add_message::<EquipItem>()
+            .add_message::<UnequipItem>()
             .add_message::<ProjectileCollisionMessage>()
-            .add_message::<SpawnFloatingText>();
+            .add_message::<SpawnFloatingText>()
+            .add_message::<OfflineProgressReport>();
 
         // Components & Types
         app
             // components.rs
             .register_type::<PortalRoot>()
+            .register_type::<PortalId>()
+            .register_type::<PortalSpawnTracker>()
             .register_type::<PortalLevel>()
             .register_type::<UpgradeCost>()
             .register_type::<PortalSpawner>()
+            .register_type::<PortalTopOffset>()
             .register_type::<BaseMonsterHealth>()
             .register_type::<BaseMonsterReward>()
             .register_type::<BaseMonsterSpeed>()
             .register_type::<BaseMonsterLifetime>()
             .register_type::<BaseMonsterArmor>()
+            .register_type::<BaseMonsterAttack>()
             .register_type::<ScavengerPenalty>()
             .register_type::<UpgradeSlot>()
             .register_type::<PortalUpgrades>()
             .register_type::<LockedFeature>()
+            .register_type::<UpgradePrerequisites>()
             // lib.rs
             .register_type::<Reward>()
             .register_type::<ScavengeModifier>()
             .register_type::<MarkedForCleanUp>()
+            .register_type::<PrestigeMultipliers>()
+            // spatial.rs
+            .register_type::<Collider>()
+            // big_number.rs
+            .register_type::<BigNumber>()
+            // currency.rs
+            .register_type::<Currency>()
+            .register_type::<Currencies>()
+            .register_type::<PriceStrategy>()
             // stats.rs
             .register_type::<GrowthStrategy>()
             .register_type::<Condition>()
+            .register_type::<Prerequisite>()
             .register_type::<ConditionalUpgrade>()
             .register_type::<UpgradeableStat>()
-            .register_type::<MeleeDamageContext>()
-            .register_type::<ProjectileDamageContext>();
+            .register_type::<PurchaseAmount>()
+            .register_type::<DamageType>()
+            .register_type::<WeaponKind>()
+            .register_type::<EquipmentSlot>();
+
+        // Damage pipeline
+        app.configure_sets(
+            Update,
+            (
+                DamageStep::Resolve,
+                DamageStep::Bonus,
+                DamageStep::Crit,
+                DamageStep::Mitigation,
+                DamageStep::Vulnerability,
+                DamageStep::Apply,
+            )
+                .chain()
+                .in_set(VoidGameStage::Effect),
+        );
     }
 }
 
 #[derive(Component, Debug, Clone, Reflect)]
 #[reflect(Component)]
-pub struct Reward(pub f32);
+pub struct Reward(pub Currencies);
+
+/// Permanent bonuses bought with prestige points, kept across portal resets.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource, Default)]
+pub struct PrestigeMultipliers {
+    /// Applied to every void shard payout (kills and scavenging).
+    pub void_shards: f32,
+    /// Applied to the health of newly hydrated monsters.
+    pub monster_health: f32,
+}
+
+impl Default for PrestigeMultipliers {
+    fn default() -> Self {
+        Self {
+            void_shards: 1.0,
+            monster_health: 1.0,
+        }
+    }
+}
 
 #[derive(Component, Debug, Clone, Reflect, Default)]
 #[reflect(Component, Default)]
@@ -95,3 +163,24 @@ pub enum VoidGameStage {
     /// Frame-end: Maintenance stage (despawn dead, process rewards, etc.).
     FrameEnd,
 }
+
+/// Ordered steps every [`DamageMessage`] goes through inside [`VoidGameStage::Effect`].
+///
+/// Damage sources write messages in `Resolve`. Modifiers read them with a
+/// `MessageMutator` in the step matching what they do, and a single system
+/// writes `Health` in `Apply`.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, SystemSet)]
+pub enum DamageStep {
+    /// Hits are turned into `DamageMessage`s carrying the base damage.
+    Resolve,
+    /// Flat and multiplicative bonuses of the attacker (mastery, buffs).
+    Bonus,
+    /// Critical hits, rolled on the bonus-adjusted damage.
+    Crit,
+    /// Armor and resistances of the target.
+    Mitigation,
+    /// Extra damage the target takes (debuffs, weaknesses).
+    Vulnerability,
+    /// Final amount is subtracted from `Health`.
+    Apply,
+}
@@ -1,8 +1,14 @@
-use bevy::prelude::*;
+use {
+    crate::{BigNumber, Currencies, PriceStrategy},
+    bevy::prelude::*,
+};
 
 #[derive(Message, Debug, Clone)]
 pub struct SpawnMonsterRequest {
     pub portal_entity: Entity,
+    /// Prefab of the monster config to spawn. `None` picks one among the configs
+    /// allowed at the portal's active level.
+    pub monster: Option<String>,
 }
 
 #[derive(Message, Debug, Clone)]
@@ -11,11 +17,47 @@ pub struct MonsterKilled {
 }
 
 #[derive(Message, Debug, Clone)]
-pub struct UpgradePortal;
+pub struct UpgradePortal {
+    pub portal_entity: Entity,
+    pub amount: PurchaseAmount,
+}
 
 #[derive(Message, Debug, Clone)]
 pub struct RequestUpgrade {
     pub upgrade_entity: Entity,
+    pub amount: PurchaseAmount,
+}
+
+/// How many levels a single upgrade message buys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum PurchaseAmount {
+    /// Exactly this many levels, or nothing if they can't all be paid for.
+    Levels(u32),
+    /// As many levels as the wallet covers in every currency of the price, at least one.
+    MaxAffordable,
+}
+
+impl Default for PurchaseAmount {
+    fn default() -> Self {
+        Self::Levels(1)
+    }
+}
+
+impl PurchaseAmount {
+    /// Keeps free or near-free prices from pushing a level towards overflow.
+    pub const MAX_LEVELS: u32 = 10_000;
+
+    /// Levels this amount stands for when buying from `from_level` with `budget`.
+    /// `MaxAffordable` never resolves to less than one level, so callers can still
+    /// show the price of the next level when nothing is affordable.
+    pub fn levels(&self, strategy: &PriceStrategy, from_level: f32, budget: &Currencies) -> u32 {
+        match *self {
+            Self::Levels(count) => count,
+            Self::MaxAffordable => strategy
+                .max_affordable(from_level, budget)
+                .clamp(1, Self::MAX_LEVELS),
+        }
+    }
 }
 
 #[derive(Message, Debug, Clone)]
@@ -26,53 +68,124 @@ pub struct ChangeActiveLevel {
 
 #[derive(Message, Debug, Clone)]
 pub struct MonsterScavenged {
-    pub amount: f32,
+    pub amount: Currencies,
     pub location: Vec3,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+/// Adds `amount` to the wallet without a source, e.g. from the dev console.
+#[derive(Message, Debug, Clone)]
+pub struct GrantCurrency {
+    pub amount: Currencies,
+}
+
+/// Moves a portal straight to `level`, as both its active and highest unlocked
+/// level, without paying for it.
+#[derive(Message, Debug, Clone)]
+pub struct SetPortalLevel {
+    pub portal_entity: Entity,
+    pub level: u32,
+}
+
+/// Drops every living monster to 0 health. They die the regular way, rewards included.
+#[derive(Message, Debug, Clone, Default)]
+pub struct KillAllMonsters;
+
+/// Speed of virtual time, 1.0 being real time.
+#[derive(Message, Debug, Clone)]
+pub struct SetTimeScale {
+    pub scale: f32,
+}
+
+/// Earnings credited for the time the game was closed, sent once after loading a save.
+#[derive(Message, Debug, Clone)]
+pub struct OfflineProgressReport {
+    /// Seconds since the save was written.
+    pub away_secs: u64,
+    /// Seconds that were actually paid out, after the offline cap.
+    pub counted_secs: u64,
+    pub monsters_killed: u32,
+    pub void_shards: BigNumber,
+}
+
+/// Also used as a component on weapons; weapons without it deal `Physical` damage.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Component, Default)]
 pub enum DamageType {
+    #[default]
     Physical,
     Magic,
     Void,
 }
 
+/// A single hit travelling through the [`DamageStep`](crate::DamageStep) pipeline.
+/// Modifiers change `amount` in place, the apply step subtracts it from `Health`.
 #[derive(Message, Debug, Clone)]
 pub struct DamageMessage {
+    /// Unit that dealt the damage.
     pub source: Entity,
     pub target: Entity,
+    /// Weapon the hit came from, if any.
+    pub weapon: Option<Entity>,
     pub amount: f32,
     pub damage_type: DamageType,
+    /// Attacker's mastery multiplier for the weapon class, taken when the
+    /// attack was made. Applied in `DamageStep::Bonus`.
+    pub mastery_bonus: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum WeaponKind {
+    Melee,
+    Ranged,
 }
 
 #[derive(Message, Debug, Clone)]
-pub struct MeleeHitMessage {
-    pub attacker: Entity,
-    pub target: Entity,
+pub struct WeaponMasteryLeveledUp {
+    pub entity: Entity,
+    pub kind: WeaponKind,
+    /// Level reached. One message is sent per level gained.
+    pub level: u32,
+    pub location: Vec3,
+}
+
+/// Named places a player NPC can wear an item in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum EquipmentSlot {
+    MainHand,
+    OffHand,
+    Armor,
 }
 
+/// Spawns the item scene at `prefab` and equips it on `npc`, replacing
+/// whatever was in the slot.
 #[derive(Message, Debug, Clone)]
-pub struct ProjectileCollisionMessage {
-    pub projectile: Entity,
-    pub source: Entity,
-    pub target: Entity,
+pub struct EquipItem {
+    pub npc: Entity,
+    pub slot: EquipmentSlot,
+    /// Item scene, relative to the assets folder.
+    pub prefab: String,
 }
 
-#[derive(Debug, Clone, Reflect)]
-pub struct MeleeDamageContext {
-    pub source: Entity,
+/// Removes and despawns the item in `slot` of `npc`.
+#[derive(Message, Debug, Clone)]
+pub struct UnequipItem {
+    pub npc: Entity,
+    pub slot: EquipmentSlot,
+}
+
+#[derive(Message, Debug, Clone)]
+pub struct MeleeHitMessage {
+    pub attacker: Entity,
     pub target: Entity,
-    pub current_value: f32,
 }
 
-#[derive(Debug, Clone, Reflect)]
-pub struct ProjectileDamageContext {
-    // who fired the projectile
-    pub weapons: Entity,
-    // projectile
+#[derive(Message, Debug, Clone)]
+pub struct ProjectileCollisionMessage {
+    pub projectile: Entity,
     pub source: Entity,
     pub target: Entity,
-    pub current_value: f32,
+    /// Where the projectile was when it hit.
+    pub location: Vec3,
 }
 
 #[derive(Message, Debug, Clone)]
@@ -104,9 +217,23 @@ impl SpawnFloatingText {
         }
     }
 
-    pub fn void_shards_reward(amount: f32, location: Vec3) -> Self {
+    pub fn mastery_level_up(kind: WeaponKind, level: u32, location: Vec3) -> Self {
+        let name = match kind {
+            WeaponKind::Melee => "Melee",
+            WeaponKind::Ranged => "Ranged",
+        };
+
+        Self {
+            text: format!("{} mastery {}!", name, level),
+            location,
+            color: bevy::color::palettes::basic::YELLOW.into(),
+            size: 22.0,
+        }
+    }
+
+    pub fn reward(amount: &Currencies, location: Vec3) -> Self {
         Self {
-            text: format!("+{:.0}", amount),
+            text: format!("+{}", amount),
             location,
             color: Color::srgb(0.5, 0.0, 0.5),
             size: 20.0,
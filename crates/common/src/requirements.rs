@@ -1,4 +1,4 @@
-use {bevy::prelude::*, serde::Deserialize};
+use {bevy::prelude::*, serde::Deserialize, std::fmt};
 
 #[derive(Debug, Clone, Reflect, Deserialize, PartialEq, Default)]
 pub enum Condition {
@@ -26,12 +26,55 @@ impl Condition {
             Self::MinLevel(min) => current_level >= *min,
             Self::LevelRange(min, max) => current_level >= *min && current_level <= *max,
             Self::Periodic { interval, offset } => {
-                current_level >= *offset && (current_level - offset) % interval == 0
+                current_level >= *offset && (current_level - offset).is_multiple_of(*interval)
             }
         }
     }
 }
 
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Always => write!(f, "any level"),
+            Self::MinLevel(min) => write!(f, "level {min}+"),
+            Self::LevelRange(min, max) => write!(f, "level {min}-{max}"),
+            Self::Periodic { interval, offset } => {
+                write!(f, "every {interval} levels from level {offset}")
+            }
+        }
+    }
+}
+
+/// Something that has to hold before an upgrade can be bought.
+#[derive(Debug, Clone, Reflect, Deserialize, PartialEq)]
+pub enum Prerequisite {
+    /// The highest unlocked level of the portal must satisfy the condition.
+    PortalLevel(Condition),
+    /// Another upgrade of the same portal must reach `level`.
+    Upgrade { name: String, level: u32 },
+}
+
+impl Prerequisite {
+    /// `upgrade_level` looks up the level of a sibling upgrade by name.
+    pub fn is_met(&self, portal_level: u32, upgrade_level: impl Fn(&str) -> Option<f32>) -> bool {
+        match self {
+            Self::PortalLevel(condition) => condition.is_satisfied(portal_level),
+            Self::Upgrade { name, level } => {
+                upgrade_level(name).is_some_and(|current| current >= *level as f32)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Prerequisite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PortalLevel(condition) => write!(f, "Portal {condition}"),
+            Self::Upgrade { name, level } => write!(f, "{name} level {level}"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Reflect, Deserialize, PartialEq, Default)]
 pub struct Requirement<T> {
     pub condition: Condition,
@@ -74,6 +117,43 @@ mod tests {
         assert!(cond.is_satisfied(10));
     }
 
+    #[test]
+    fn test_prerequisite_is_met() {
+        let levels = |name: &str| (name == "Capacity").then_some(3.0);
+
+        assert!(Prerequisite::PortalLevel(Condition::MinLevel(2)).is_met(2, levels));
+        assert!(!Prerequisite::PortalLevel(Condition::MinLevel(2)).is_met(1, levels));
+
+        let capacity = |level| Prerequisite::Upgrade {
+            name: "Capacity".to_string(),
+            level,
+        };
+        assert!(capacity(3).is_met(0, levels));
+        assert!(!capacity(4).is_met(0, levels));
+
+        let missing = Prerequisite::Upgrade {
+            name: "Lifetime".to_string(),
+            level: 0,
+        };
+        assert!(!missing.is_met(0, levels));
+    }
+
+    #[test]
+    fn test_prerequisite_display() {
+        assert_eq!(
+            Prerequisite::PortalLevel(Condition::MinLevel(5)).to_string(),
+            "Portal level 5+"
+        );
+        assert_eq!(
+            Prerequisite::Upgrade {
+                name: "Capacity".to_string(),
+                level: 3
+            }
+            .to_string(),
+            "Capacity level 3"
+        );
+    }
+
     #[test]
     fn test_requirement_wrap() {
         let req = Requirement::new(Condition::MinLevel(5), 100);
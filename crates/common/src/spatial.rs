@@ -0,0 +1,336 @@
+use {bevy::prelude::*, std::collections::HashMap};
+
+/// Hit circle of an entity, for bodies that don't match their sprite.
+/// Without one the circle fits the sprite, see [`Collider::radius_of`].
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct Collider {
+    pub radius: f32,
+}
+
+impl Collider {
+    /// Radius of the hit circle: the collider's when there is one, half the
+    /// longest side of a sized sprite otherwise, `0.0` (a point) as last resort.
+    pub fn radius_of(collider: Option<&Collider>, sprite: Option<&Sprite>) -> f32 {
+        match (collider, sprite.and_then(|sprite| sprite.custom_size)) {
+            (Some(collider), _) => collider.radius,
+            (None, Some(size)) => size.max_element() / 2.0,
+            (None, None) => 0.0,
+        }
+    }
+}
+
+/// Hit circles of every monster, rebuilt by the monsters plugin in
+/// `VoidGameStage::FrameStart`. Monsters spawned later in the frame show up
+/// on the next one.
+#[derive(Resource, Debug, Default, Deref, DerefMut)]
+pub struct MonsterIndex(pub SpatialGrid);
+
+/// An entity stored in a [`SpatialGrid`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpatialEntry {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub radius: f32,
+}
+
+/// Uniform grid of circles, so overlap checks only look at nearby cells
+/// instead of every entity.
+#[derive(Debug, Clone)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<SpatialEntry>>,
+    len: usize,
+    /// Largest radius inserted, an entry can overlap cells its center isn't in.
+    max_radius: f32,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        Self::new(64.0)
+    }
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size: cell_size.max(f32::EPSILON),
+            cells: HashMap::new(),
+            len: 0,
+            max_radius: 0.0,
+        }
+    }
+
+    /// Empties the grid but keeps its allocations for the next rebuild.
+    pub fn clear(&mut self) {
+        for entries in self.cells.values_mut() {
+            entries.clear();
+        }
+        self.len = 0;
+        self.max_radius = 0.0;
+    }
+
+    pub fn insert(&mut self, entity: Entity, position: Vec2, radius: f32) {
+        self.len += 1;
+        self.max_radius = self.max_radius.max(radius);
+        self.cells
+            .entry(self.cell_of(position))
+            .or_default()
+            .push(SpatialEntry {
+                entity,
+                position,
+                radius,
+            });
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Entries whose circle overlaps the circle at `center`, in no particular order.
+    pub fn query_radius(
+        &self,
+        center: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = &SpatialEntry> + '_ {
+        self.entries_around(center, radius + self.max_radius)
+            .filter(move |entry| entry.position.distance(center) <= radius + entry.radius)
+    }
+
+    /// Entries whose center is in the cone at `origin` opening `angle` radians
+    /// (full width) around `facing`, at most `reach` away. Entries standing on
+    /// the origin are always inside, and so is everything in reach when
+    /// `facing` is zero.
+    pub fn query_cone(
+        &self,
+        origin: Vec2,
+        facing: Vec2,
+        reach: f32,
+        angle: f32,
+    ) -> impl Iterator<Item = &SpatialEntry> + '_ {
+        self.entries_around(origin, reach).filter(move |entry| {
+            let offset = entry.position - origin;
+            offset.length() <= reach
+                && (offset.length_squared() <= f32::EPSILON
+                    || facing == Vec2::ZERO
+                    || facing.angle_to(offset).abs() <= angle / 2.0)
+        })
+    }
+
+    /// The `count` entries whose center is closest to `center`, closest first.
+    ///
+    /// Walks rings of cells outwards and stops once nothing further out can
+    /// beat the entries found so far.
+    pub fn nearest(&self, center: Vec2, count: usize) -> Vec<&SpatialEntry> {
+        let distance = |entry: &SpatialEntry| entry.position.distance(center);
+        let origin = self.cell_of(center);
+        let mut found: Vec<&SpatialEntry> = Vec::new();
+        let mut seen = 0;
+
+        let mut ring = 0;
+        while count > 0 && seen < self.len {
+            for cell in ring_cells(origin, ring) {
+                if let Some(entries) = self.cells.get(&cell) {
+                    seen += entries.len();
+                    found.extend(entries);
+                }
+            }
+
+            if found.len() >= count {
+                found.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+                found.truncate(count);
+                // Cells of the next ring are at least this far from any point of `origin`
+                if distance(found[count - 1]) <= ring as f32 * self.cell_size {
+                    return found;
+                }
+            }
+            ring += 1;
+        }
+
+        found.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+        found.truncate(count);
+        found
+    }
+
+    /// Entries of every cell touching the square of half side `reach` around `center`.
+    fn entries_around(
+        &self,
+        center: Vec2,
+        reach: f32,
+    ) -> impl Iterator<Item = &SpatialEntry> + '_ {
+        let min = self.cell_of(center - Vec2::splat(reach));
+        let max = self.cell_of(center + Vec2::splat(reach));
+
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+    }
+
+    fn cell_of(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+}
+
+/// Cells exactly `ring` cells away from `origin` (Chebyshev distance).
+fn ring_cells(origin: IVec2, ring: i32) -> impl Iterator<Item = IVec2> {
+    (-ring..=ring)
+        .flat_map(move |x| (-ring..=ring).map(move |y| IVec2::new(x, y)))
+        .filter(move |offset| offset.x.abs() == ring || offset.y.abs() == ring)
+        .map(move |offset| origin + offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn entity(index: u32) -> Entity {
+        Entity::from_raw_u32(index).unwrap()
+    }
+
+    fn sorted<'a>(entries: impl Iterator<Item = &'a SpatialEntry>) -> Vec<Entity> {
+        let mut entities: Vec<_> = entries.map(|entry| entry.entity).collect();
+        entities.sort();
+        entities
+    }
+
+    /// Spread over negative and positive cells, some sharing a cell.
+    fn scattered() -> (SpatialGrid, Vec<SpatialEntry>) {
+        let mut grid = SpatialGrid::new(25.0);
+        let mut entries = Vec::new();
+        for index in 0..200 {
+            let position = Vec2::new(
+                ((index * 37) % 400) as f32 - 200.0,
+                ((index * 91) % 300) as f32 - 150.0,
+            );
+            let radius = (index % 4) as f32 * 5.0;
+            grid.insert(entity(index), position, radius);
+            entries.push(SpatialEntry {
+                entity: entity(index),
+                position,
+                radius,
+            });
+        }
+        (grid, entries)
+    }
+
+    #[test]
+    fn test_radius_query_matches_brute_force() {
+        let (grid, entries) = scattered();
+        assert_eq!(grid.len(), 200);
+
+        for (center, radius) in [
+            (Vec2::ZERO, 10.0),
+            (Vec2::new(-130.0, 40.0), 60.0),
+            (Vec2::new(199.0, -149.0), 3.0),
+            (Vec2::new(1000.0, 1000.0), 50.0),
+        ] {
+            let mut expected: Vec<_> = entries
+                .iter()
+                .filter(|entry| entry.position.distance(center) <= radius + entry.radius)
+                .map(|entry| entry.entity)
+                .collect();
+            expected.sort();
+            assert_eq!(sorted(grid.query_radius(center, radius)), expected);
+        }
+    }
+
+    #[test]
+    fn test_cone_query_matches_brute_force() {
+        let (grid, entries) = scattered();
+
+        for (origin, facing, reach, angle) in [
+            (Vec2::ZERO, Vec2::X, 120.0, FRAC_PI_2),
+            (Vec2::new(50.0, -60.0), Vec2::new(-1.0, 1.0), 200.0, 0.3),
+            (Vec2::new(-100.0, 0.0), Vec2::ZERO, 40.0, 0.0),
+        ] {
+            let mut expected: Vec<_> = entries
+                .iter()
+                .filter(|entry| {
+                    let offset = entry.position - origin;
+                    offset.length() <= reach
+                        && (facing == Vec2::ZERO || facing.angle_to(offset).abs() <= angle / 2.0)
+                })
+                .map(|entry| entry.entity)
+                .collect();
+            expected.sort();
+            assert!(!expected.is_empty());
+            assert_eq!(
+                sorted(grid.query_cone(origin, facing, reach, angle)),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_nearest_matches_brute_force() {
+        let (grid, mut entries) = scattered();
+
+        for (center, count) in [
+            (Vec2::ZERO, 1),
+            (Vec2::new(180.0, 140.0), 7),
+            (Vec2::new(-900.0, 30.0), 3),
+            (Vec2::new(10.0, 10.0), 500),
+        ] {
+            entries.sort_by(|a, b| {
+                a.position
+                    .distance(center)
+                    .total_cmp(&b.position.distance(center))
+            });
+            let expected: Vec<_> = entries
+                .iter()
+                .take(count)
+                .map(|entry| entry.position.distance(center))
+                .collect();
+            let nearest: Vec<_> = grid
+                .nearest(center, count)
+                .iter()
+                .map(|entry| entry.position.distance(center))
+                .collect();
+            // Compared by distance, entries at the same distance may come in any order
+            assert_eq!(nearest, expected);
+        }
+        assert!(grid.nearest(Vec2::ZERO, 0).is_empty());
+        assert!(SpatialGrid::default().nearest(Vec2::ZERO, 3).is_empty());
+    }
+
+    #[test]
+    fn test_large_entry_reaches_across_cells() {
+        let mut grid = SpatialGrid::new(10.0);
+        grid.insert(entity(1), Vec2::new(55.0, 5.0), 40.0);
+
+        let hits: Vec<_> = grid.query_radius(Vec2::new(18.0, 5.0), 0.0).collect();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].entity, entity(1));
+    }
+
+    #[test]
+    fn test_clear_empties_grid() {
+        let mut grid = SpatialGrid::default();
+        grid.insert(entity(1), Vec2::ZERO, 5.0);
+        grid.clear();
+
+        assert!(grid.is_empty());
+        assert_eq!(grid.query_radius(Vec2::ZERO, 100.0).count(), 0);
+    }
+
+    #[test]
+    fn test_collider_radius() {
+        let sprite = Sprite {
+            custom_size: Some(Vec2::new(32.0, 24.0)),
+            ..default()
+        };
+        assert_eq!(Collider::radius_of(None, Some(&sprite)), 16.0);
+        assert_eq!(
+            Collider::radius_of(Some(&Collider { radius: 5.0 }), Some(&sprite)),
+            5.0
+        );
+        assert_eq!(Collider::radius_of(None, Some(&Sprite::default())), 0.0);
+        assert_eq!(Collider::radius_of(None, None), 0.0);
+    }
+}
@@ -1,4 +1,8 @@
-use {crate::requirements::Requirement, bevy::prelude::*, serde::Deserialize};
+use {
+    crate::{requirements::Requirement, BigNumber, Currencies, PriceStrategy},
+    bevy::prelude::*,
+    serde::Deserialize,
+};
 
 #[derive(Debug, Clone, Reflect, Deserialize, PartialEq)]
 pub enum GrowthStrategy {
@@ -38,6 +42,149 @@ impl GrowthStrategy {
             } => base + (level / interval).floor() * step,
         }
     }
+
+    /// Same as [`calculate`](Self::calculate) without the `f32` limits, for prices.
+    /// `Exponential` keeps growing past `f32::MAX` instead of turning infinite.
+    pub fn calculate_big(&self, level: f32) -> BigNumber {
+        match self {
+            Self::Exponential { base, factor } => {
+                BigNumber::pow(*factor as f64, level as f64) * *base as f64
+            }
+            _ => self.calculate(level).into(),
+        }
+    }
+
+    /// Total of `count` consecutive values starting at `from_level`, i.e. the price
+    /// of buying `count` levels at once. `Chunked` assumes a whole-number interval.
+    pub fn cumulative_cost(&self, from_level: f32, count: u32) -> BigNumber {
+        let n = count as f64;
+        let from_level = from_level as f64;
+        if count == 0 {
+            return BigNumber::ZERO;
+        }
+        match self {
+            Self::Static(val) => (*val as f64 * n).into(),
+            Self::Linear {
+                base,
+                coefficient: step,
+            }
+            | Self::Incremental { base, step } => {
+                let first = *base as f64 + from_level * *step as f64;
+                arithmetic_sum(first, *step as f64, n).into()
+            }
+            Self::Exponential { factor, .. } => {
+                let first = self.calculate_big(from_level as f32);
+                if *factor == 1.0 {
+                    first * n
+                } else {
+                    let factor = *factor as f64;
+                    (BigNumber::pow(factor, n) - BigNumber::ONE) * first / (factor - 1.0)
+                }
+            }
+            Self::Chunked {
+                base,
+                interval,
+                step,
+            } => {
+                let interval = *interval as f64;
+                let floor_sum = chunk_floor_sum(from_level + n, interval)
+                    - chunk_floor_sum(from_level, interval);
+                (*base as f64 * n + *step as f64 * floor_sum).into()
+            }
+        }
+    }
+
+    /// Most levels that can be bought from `from_level` without spending more than
+    /// `budget`. Saturates at `u32::MAX` when prices never add up to the budget.
+    pub fn max_affordable(&self, from_level: f32, budget: BigNumber) -> u32 {
+        let first_big = self.calculate_big(from_level);
+        if budget < first_big {
+            return 0;
+        }
+        if first_big <= BigNumber::ZERO {
+            return u32::MAX;
+        }
+
+        let first = first_big.to_f64();
+        let funds = budget.to_f64();
+        let estimate = match self {
+            Self::Static(_) => funds / first,
+            Self::Linear {
+                coefficient: step, ..
+            }
+            | Self::Incremental { step, .. } => arithmetic_max(first, *step as f64, funds),
+            Self::Exponential { factor, .. } => {
+                if *factor == 1.0 {
+                    (budget / first_big).to_f64()
+                } else {
+                    // first * (factor^n - 1) / (factor - 1) <= budget
+                    let factor = *factor as f64;
+                    let x = budget * (factor - 1.0) / first_big + BigNumber::ONE;
+                    if x <= BigNumber::ZERO {
+                        f64::INFINITY
+                    } else {
+                        x.log10() / factor.log10()
+                    }
+                }
+            }
+            Self::Chunked { interval, step, .. } => {
+                // Finish the current chunk, then whole chunks form an arithmetic
+                // series, then whatever fits of the next one.
+                let (interval, step) = (*interval as f64, *step as f64);
+                let head = interval - (from_level as f64).rem_euclid(interval);
+                if funds < head * first {
+                    funds / first
+                } else {
+                    let left = funds - head * first;
+                    let next = first + step;
+                    let chunks = arithmetic_max(interval * next, interval * step, left).floor();
+                    let left = left - arithmetic_sum(interval * next, interval * step, chunks);
+                    let tail = (left / (next + chunks * step)).floor().min(interval - 1.0);
+                    head + chunks * interval + tail.max(0.0)
+                }
+            }
+        };
+        if !estimate.is_finite() || estimate >= u32::MAX as f64 {
+            return u32::MAX;
+        }
+
+        // The estimate can be a level off due to float rounding
+        let mut levels = estimate.max(0.0).floor() as u32;
+        if levels > 0 && self.cumulative_cost(from_level, levels) > budget {
+            levels -= 1;
+        } else if self.cumulative_cost(from_level, levels + 1) <= budget {
+            levels += 1;
+        }
+        levels
+    }
+}
+
+/// `first + (first + step) + ...` over `n` terms.
+fn arithmetic_sum(first: f64, step: f64, n: f64) -> f64 {
+    n * first + step * n * (n - 1.0) / 2.0
+}
+
+/// Largest real `n` with `arithmetic_sum(first, step, n) <= budget`, `first` being positive.
+fn arithmetic_max(first: f64, step: f64, budget: f64) -> f64 {
+    if step == 0.0 {
+        return budget / first;
+    }
+    // step/2 * n^2 + (first - step/2) * n - budget = 0
+    let b = first - step / 2.0;
+    let discriminant = b * b + 2.0 * step * budget;
+    if discriminant < 0.0 {
+        // Falling prices that never add up to the budget
+        return f64::INFINITY;
+    }
+    // With a negative step this is the first crossing, the earlier root
+    (discriminant.sqrt() - b) / step
+}
+
+/// Sum of `floor(level / interval)` over the levels `0..end`.
+fn chunk_floor_sum(end: f64, interval: f64) -> f64 {
+    let chunks = (end / interval).floor();
+    let rest = end - chunks * interval;
+    interval * chunks * (chunks - 1.0) / 2.0 + chunks * rest
 }
 
 pub type ConditionalUpgrade = Requirement<GrowthStrategy>;
@@ -55,11 +202,11 @@ pub struct UpgradeableStat {
 
     // Cached current state
     pub value: f32,
-    pub price: f32,
+    pub price: Currencies,
 
     // Logic containers
     pub value_strategy: GrowthStrategy,
-    pub price_strategy: GrowthStrategy,
+    pub price_strategy: PriceStrategy,
 }
 
 impl Default for UpgradeableStat {
@@ -67,31 +214,40 @@ impl Default for UpgradeableStat {
         Self {
             level: 0.0,
             value: 0.0,
-            price: 0.0,
+            price: Currencies::default(),
             value_strategy: GrowthStrategy::default(),
-            price_strategy: GrowthStrategy::default(),
+            price_strategy: PriceStrategy::default(),
         }
     }
 }
 
 impl UpgradeableStat {
-    pub fn new(value_strategy: GrowthStrategy, price_strategy: GrowthStrategy) -> Self {
+    pub fn new(value_strategy: GrowthStrategy, price_strategy: impl Into<PriceStrategy>) -> Self {
         let mut stat = Self {
             level: 0.0,
             value: 0.0,
-            price: 0.0,
+            price: Currencies::default(),
             value_strategy,
-            price_strategy,
+            price_strategy: price_strategy.into(),
         };
         stat.recalculate();
         stat
     }
 
     pub fn upgrade(&mut self) {
-        self.level += 1.0;
+        self.upgrade_by(1);
+    }
+
+    pub fn upgrade_by(&mut self, levels: u32) {
+        self.level += levels as f32;
         self.recalculate();
     }
 
+    /// Price of buying `levels` levels from the current one.
+    pub fn price_of(&self, levels: u32) -> Currencies {
+        self.price_strategy.cumulative_cost(self.level, levels)
+    }
+
     pub fn set_level(&mut self, level: f32) {
         self.level = level;
         self.recalculate();
@@ -164,13 +320,13 @@ mod tests {
         // Level 0
         assert_eq!(stat.level, 0.0);
         assert_eq!(stat.value, 10.0);
-        assert_eq!(stat.price, 100.0);
+        assert_eq!(stat.price, 100.0.into());
 
         // Upgrade to Level 1
         stat.upgrade();
         assert_eq!(stat.level, 1.0);
         assert_eq!(stat.value, 11.0);
-        assert_eq!(stat.price, 150.0);
+        assert_eq!(stat.price, 150.0.into());
     }
 
     #[test]
@@ -216,4 +372,112 @@ mod tests {
         // 30 -> 15
         assert_eq!(strategy.calculate(30.0), 15.0);
     }
+
+    fn strategies() -> Vec<GrowthStrategy> {
+        vec![
+            GrowthStrategy::Static(7.0),
+            GrowthStrategy::Linear {
+                base: 100.0,
+                coefficient: 50.0,
+            },
+            GrowthStrategy::Exponential {
+                base: 10.0,
+                factor: 1.5,
+            },
+            GrowthStrategy::Incremental {
+                base: 5.0,
+                step: 1.0,
+            },
+            GrowthStrategy::Chunked {
+                base: 20.0,
+                interval: 10.0,
+                step: 5.0,
+            },
+        ]
+    }
+
+    fn looped_cost(strategy: &GrowthStrategy, from_level: f32, count: u32) -> BigNumber {
+        (0..count)
+            .map(|i| strategy.calculate_big(from_level + i as f32))
+            .sum()
+    }
+
+    fn looped_max(strategy: &GrowthStrategy, from_level: f32, budget: BigNumber) -> u32 {
+        let mut levels = 0;
+        let mut spent = BigNumber::ZERO;
+        loop {
+            spent += strategy.calculate_big(from_level + levels as f32);
+            if spent > budget {
+                return levels;
+            }
+            levels += 1;
+        }
+    }
+
+    #[test]
+    fn test_cumulative_cost_matches_loop() {
+        for strategy in strategies() {
+            for from_level in [0.0, 3.0, 9.0, 17.0] {
+                for count in [0, 1, 2, 10, 25] {
+                    let expected = looped_cost(&strategy, from_level, count);
+                    let actual = strategy.cumulative_cost(from_level, count);
+                    assert!(
+                        (actual - expected).abs() <= expected * 1e-4,
+                        "{strategy:?} from {from_level} x{count}: {actual} != {expected}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_affordable_matches_loop() {
+        for strategy in strategies() {
+            for from_level in [0.0, 3.0, 9.0, 17.0] {
+                for budget in [0.0, 6.0, 100.0, 1234.0, 50_000.0] {
+                    let budget = BigNumber::from(budget);
+                    assert_eq!(
+                        strategy.max_affordable(from_level, budget),
+                        looped_max(&strategy, from_level, budget),
+                        "{strategy:?} from {from_level} with {budget}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_affordable_exact_budget() {
+        let strategy = GrowthStrategy::Exponential {
+            base: 1.0,
+            factor: 2.0,
+        };
+        // 1 + 2 + 4 + 8 = 15
+        assert_eq!(strategy.max_affordable(0.0, 15.0.into()), 4);
+        assert_eq!(strategy.max_affordable(0.0, 14.0.into()), 3);
+    }
+
+    #[test]
+    fn test_max_affordable_free_price_saturates() {
+        let strategy = GrowthStrategy::Static(0.0);
+        assert_eq!(strategy.max_affordable(0.0, 10.0.into()), u32::MAX);
+    }
+
+    #[test]
+    fn test_exponential_price_past_f32_range() {
+        let strategy = GrowthStrategy::Exponential {
+            base: 10.0,
+            factor: 2.0,
+        };
+        assert!(strategy.calculate(200.0).is_infinite());
+
+        // 10 * 2^200 ~ 1.6e61
+        let price = strategy.calculate_big(200.0);
+        assert!((price.log10() - 61.206).abs() < 1e-3);
+
+        // The next level alone costs twice as much, so the budget covers exactly one
+        let budget = price * 2.5;
+        assert_eq!(strategy.max_affordable(200.0, budget), 1);
+        assert!(strategy.cumulative_cost(200.0, 2) > budget);
+    }
 }
@@ -0,0 +1,81 @@
+use {bevy::prelude::*, common::Currency};
+
+/// A parsed console line. Commands only write messages the game systems
+/// already handle, the console never edits game state itself.
+#[derive(Message, Debug, Clone, PartialEq)]
+pub enum ConsoleCommand {
+    Help,
+    /// `grant <amount> [shards|essence|prestige]`
+    Grant { currency: Currency, amount: f64 },
+    /// `level <level>`, applied to every portal.
+    SetPortalLevel(u32),
+    /// `spawn <monster>`, named after the file of its prefab.
+    Spawn(String),
+    /// `killall`
+    KillAll,
+    /// `timescale <scale>`
+    TimeScale(f32),
+}
+
+impl ConsoleCommand {
+    pub const HELP: &'static str = "commands: grant <amount> [shards|essence|prestige], \
+        level <level>, spawn <monster>, killall, timescale <scale>";
+
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let Some(name) = words.next() else {
+            return Err("empty command".to_string());
+        };
+        let args: Vec<&str> = words.collect();
+
+        let command = match (name, args.as_slice()) {
+            ("help", []) => Self::Help,
+            ("grant", [amount]) => Self::Grant {
+                currency: Currency::VoidShards,
+                amount: parse_non_negative(amount)?,
+            },
+            ("grant", [amount, currency]) => Self::Grant {
+                currency: parse_currency(currency)?,
+                amount: parse_non_negative(amount)?,
+            },
+            ("level", [level]) => Self::SetPortalLevel(parse_number(level)?),
+            ("spawn", [monster]) => Self::Spawn(monster.to_string()),
+            ("killall", []) => Self::KillAll,
+            ("timescale", [scale]) => Self::TimeScale(parse_non_negative(scale)?),
+            ("help" | "grant" | "level" | "spawn" | "killall" | "timescale", _) => {
+                return Err(format!("wrong arguments for `{name}`, see `help`"));
+            }
+            _ => return Err(format!("unknown command `{name}`, see `help`")),
+        };
+        Ok(command)
+    }
+}
+
+/// Name of the monster a prefab path spawns: `prefabs/monsters/goblin.scn.ron` is `goblin`.
+pub fn monster_name(prefab: &str) -> &str {
+    let file = prefab.rsplit('/').next().unwrap_or(prefab);
+    file.split('.').next().unwrap_or(file)
+}
+
+fn parse_number<T: std::str::FromStr>(text: &str) -> Result<T, String> {
+    text.parse().map_err(|_| format!("`{text}` is not a valid number"))
+}
+
+/// Amounts and scales: finite and at least zero, `inf`, `NaN` and `-5` are all refused.
+fn parse_non_negative<T: std::str::FromStr + Into<f64> + Copy>(text: &str) -> Result<T, String> {
+    let number: T = parse_number(text)?;
+    let value: f64 = number.into();
+    if !value.is_finite() || value < 0.0 {
+        return Err(format!("`{text}` must be a finite number of at least zero"));
+    }
+    Ok(number)
+}
+
+fn parse_currency(text: &str) -> Result<Currency, String> {
+    match text {
+        "shards" | "void_shards" => Ok(Currency::VoidShards),
+        "essence" => Ok(Currency::Essence),
+        "prestige" | "prestige_points" => Ok(Currency::PrestigePoints),
+        _ => Err(format!("unknown currency `{text}`")),
+    }
+}
@@ -0,0 +1,246 @@
+#![allow(clippy::type_complexity)]
+
+use {
+    bevy::{
+        input::{
+            keyboard::{Key, KeyboardInput},
+            ButtonState,
+        },
+        prelude::*,
+    },
+    common::{
+        components::{PortalId, PortalRoot},
+        Currencies, GameState, GrantCurrency, KillAllMonsters, SetPortalLevel, SetTimeScale,
+        SpawnMonsterRequest,
+    },
+    monsters::AvailableEnemies,
+};
+pub use command::*;
+
+mod command;
+
+/// Developer console, only built with the `game_core/dev_console` feature.
+/// Toggled with the backquote key while playing.
+pub struct DevConsolePlugin;
+
+impl Plugin for DevConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DevConsole>()
+            .add_message::<ConsoleCommand>();
+
+        app.add_systems(OnEnter(GameState::Playing), spawn_console_ui)
+            .add_systems(
+                Update,
+                (
+                    read_console_input,
+                    execute_console_commands,
+                    update_console_ui,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), despawn_console_ui);
+
+        info!("Dev console initialized, press ` to open it");
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct DevConsole {
+    pub open: bool,
+    /// Line being typed.
+    pub input: String,
+    /// Submitted lines and their replies, oldest first.
+    pub history: Vec<String>,
+}
+
+impl DevConsole {
+    const HISTORY_LINES: usize = 12;
+
+    pub fn print(&mut self, line: impl Into<String>) {
+        self.history.push(line.into());
+        let overflow = self.history.len().saturating_sub(Self::HISTORY_LINES);
+        self.history.drain(..overflow);
+    }
+}
+
+// Marker components
+#[derive(Component)]
+struct ConsoleUiRoot;
+
+#[derive(Component)]
+struct ConsoleText;
+
+fn spawn_console_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Px(520.0),
+                position_type: PositionType::Absolute,
+                left: Val::Px(10.0),
+                top: Val::Px(60.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                display: Display::None,
+                ..default()
+            },
+            ZIndex(300),
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+            ConsoleUiRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.6, 1.0, 0.6)),
+                ConsoleText,
+            ));
+        });
+}
+
+pub fn read_console_input(
+    mut keys: MessageReader<KeyboardInput>,
+    mut console: ResMut<DevConsole>,
+    mut events: MessageWriter<ConsoleCommand>,
+) {
+    for key in keys.read() {
+        if key.state != ButtonState::Pressed {
+            continue;
+        }
+        if key.key_code == KeyCode::Backquote {
+            console.open = !console.open;
+            continue;
+        }
+        if !console.open {
+            continue;
+        }
+
+        match &key.logical_key {
+            Key::Enter => {
+                let line = std::mem::take(&mut console.input);
+                if line.trim().is_empty() {
+                    continue;
+                }
+                console.print(format!("> {line}"));
+                match ConsoleCommand::parse(&line) {
+                    Ok(command) => {
+                        events.write(command);
+                    }
+                    Err(err) => console.print(err),
+                }
+            }
+            Key::Backspace => {
+                console.input.pop();
+            }
+            Key::Space => console.input.push(' '),
+            Key::Character(text) => console.input.push_str(text),
+            _ => {}
+        }
+    }
+}
+
+/// Turns console commands into the messages the game systems handle.
+pub fn execute_console_commands(
+    mut events: MessageReader<ConsoleCommand>,
+    mut console: ResMut<DevConsole>,
+    portal_query: Query<(Entity, &PortalId), With<PortalRoot>>,
+    available_monsters: Res<AvailableEnemies>,
+    mut grants: MessageWriter<GrantCurrency>,
+    mut levels: MessageWriter<SetPortalLevel>,
+    mut spawns: MessageWriter<SpawnMonsterRequest>,
+    mut kills: MessageWriter<KillAllMonsters>,
+    mut time_scales: MessageWriter<SetTimeScale>,
+) {
+    for command in events.read() {
+        match command {
+            ConsoleCommand::Help => console.print(ConsoleCommand::HELP),
+            ConsoleCommand::Grant { currency, amount } => {
+                grants.write(GrantCurrency {
+                    amount: Currencies::single(*currency, *amount),
+                });
+                console.print(format!("granted {} {}", amount, currency.label()));
+            }
+            ConsoleCommand::SetPortalLevel(level) => {
+                for (portal_entity, _) in &portal_query {
+                    levels.write(SetPortalLevel {
+                        portal_entity,
+                        level: *level,
+                    });
+                }
+                console.print(format!("portals set to level {level}"));
+            }
+            ConsoleCommand::Spawn(name) => {
+                let Some(config) = available_monsters
+                    .0
+                    .iter()
+                    .find(|config| monster_name(&config.prefab) == name)
+                else {
+                    let names: Vec<&str> = available_monsters
+                        .0
+                        .iter()
+                        .map(|config| monster_name(&config.prefab))
+                        .collect();
+                    console.print(format!(
+                        "unknown monster `{name}`, one of: {}",
+                        names.join(", ")
+                    ));
+                    continue;
+                };
+                // Same portal order as the layout
+                let Some((portal_entity, _)) = portal_query.iter().min_by_key(|(_, id)| id.0) else {
+                    console.print("no portal to spawn from");
+                    continue;
+                };
+                spawns.write(SpawnMonsterRequest {
+                    portal_entity,
+                    monster: Some(config.prefab.clone()),
+                });
+                console.print(format!("spawned {name}"));
+            }
+            ConsoleCommand::KillAll => {
+                kills.write(KillAllMonsters);
+                console.print("killed all monsters");
+            }
+            ConsoleCommand::TimeScale(scale) => {
+                time_scales.write(SetTimeScale { scale: *scale });
+                console.print(format!("time scale set to {scale}"));
+            }
+        }
+    }
+}
+
+fn update_console_ui(
+    console: Res<DevConsole>,
+    mut root_query: Query<&mut Node, With<ConsoleUiRoot>>,
+    mut text_query: Query<&mut Text, With<ConsoleText>>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+
+    let display = if console.open {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for mut node in &mut root_query {
+        node.display = display;
+    }
+
+    let mut lines = console.history.clone();
+    lines.push(format!("> {}_", console.input));
+    for mut text in &mut text_query {
+        **text = lines.join("\n");
+    }
+}
+
+fn despawn_console_ui(mut commands: Commands, query: Query<Entity, With<ConsoleUiRoot>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,168 @@
+use {
+    crate::*,
+    common::Currency,
+    monsters::{MonsterConfig, MovementPattern},
+};
+
+fn monster_config(prefab: &str) -> MonsterConfig {
+    MonsterConfig {
+        prefab: prefab.to_string(),
+        spawn_weight: 1.0,
+        min_portal_level: 0,
+        health_coef: 1.0,
+        lifetime_coef: 1.0,
+        speed_coef: 1.0,
+        reward_coef: 1.0,
+        movement: MovementPattern::default(),
+        return_to_portal: None,
+    }
+}
+
+fn setup_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<DevConsole>()
+        .insert_resource(AvailableEnemies(vec![
+            monster_config("prefabs/monsters/goblin.scn.ron"),
+            monster_config("prefabs/monsters/goblin_elite.scn.ron"),
+        ]))
+        .add_message::<ConsoleCommand>()
+        .add_message::<GrantCurrency>()
+        .add_message::<SetPortalLevel>()
+        .add_message::<SpawnMonsterRequest>()
+        .add_message::<KillAllMonsters>()
+        .add_message::<SetTimeScale>();
+    app.add_systems(Update, execute_console_commands);
+    app
+}
+
+fn drain<M: Message>(app: &mut App) -> Vec<M> {
+    app.world_mut().resource_mut::<Messages<M>>().drain().collect()
+}
+
+#[test]
+fn test_parse_commands() {
+    assert_eq!(
+        ConsoleCommand::parse("grant 500"),
+        Ok(ConsoleCommand::Grant {
+            currency: Currency::VoidShards,
+            amount: 500.0,
+        })
+    );
+    assert_eq!(
+        ConsoleCommand::parse("  grant 2.5  essence "),
+        Ok(ConsoleCommand::Grant {
+            currency: Currency::Essence,
+            amount: 2.5,
+        })
+    );
+    assert_eq!(
+        ConsoleCommand::parse("level 12"),
+        Ok(ConsoleCommand::SetPortalLevel(12))
+    );
+    assert_eq!(
+        ConsoleCommand::parse("spawn goblin_elite"),
+        Ok(ConsoleCommand::Spawn("goblin_elite".to_string()))
+    );
+    assert_eq!(ConsoleCommand::parse("killall"), Ok(ConsoleCommand::KillAll));
+    assert_eq!(
+        ConsoleCommand::parse("timescale 4"),
+        Ok(ConsoleCommand::TimeScale(4.0))
+    );
+}
+
+#[test]
+fn test_parse_rejects_bad_input() {
+    assert!(ConsoleCommand::parse("").is_err());
+    assert!(ConsoleCommand::parse("fly").is_err());
+    assert!(ConsoleCommand::parse("grant lots").is_err());
+    assert!(ConsoleCommand::parse("grant 5 gold").is_err());
+    assert!(ConsoleCommand::parse("level -1").is_err());
+    assert!(ConsoleCommand::parse("killall now").is_err());
+    assert!(ConsoleCommand::parse("timescale -2").is_err());
+    assert!(ConsoleCommand::parse("timescale inf").is_err());
+    assert!(ConsoleCommand::parse("timescale NaN").is_err());
+    assert!(ConsoleCommand::parse("timescale 1e39").is_err());
+    assert!(ConsoleCommand::parse("grant -500").is_err());
+    assert!(ConsoleCommand::parse("grant NaN essence").is_err());
+    assert!(ConsoleCommand::parse("grant inf").is_err());
+}
+
+#[test]
+fn test_monster_name_from_prefab() {
+    assert_eq!(monster_name("prefabs/monsters/goblin.scn.ron"), "goblin");
+    assert_eq!(monster_name("ogre"), "ogre");
+}
+
+#[test]
+fn test_commands_become_game_messages() {
+    let mut app = setup_app();
+    // Spawned out of id order, monsters come out of the lowest id
+    let portals = [
+        app.world_mut().spawn((PortalRoot, PortalId(1))).id(),
+        app.world_mut().spawn((PortalRoot, PortalId(0))).id(),
+    ];
+
+    for command in [
+        ConsoleCommand::Grant {
+            currency: Currency::Essence,
+            amount: 3.0,
+        },
+        ConsoleCommand::SetPortalLevel(7),
+        ConsoleCommand::Spawn("goblin_elite".to_string()),
+        ConsoleCommand::KillAll,
+        ConsoleCommand::TimeScale(0.5),
+    ] {
+        app.world_mut().write_message(command);
+    }
+    app.update();
+
+    let grants = drain::<GrantCurrency>(&mut app);
+    assert_eq!(grants.len(), 1);
+    assert_eq!(grants[0].amount, Currencies::single(Currency::Essence, 3.0));
+
+    let levels = drain::<SetPortalLevel>(&mut app);
+    assert_eq!(levels.len(), portals.len());
+    assert!(levels.iter().all(|event| event.level == 7));
+
+    let spawns = drain::<SpawnMonsterRequest>(&mut app);
+    assert_eq!(spawns.len(), 1);
+    assert_eq!(spawns[0].portal_entity, portals[1]);
+    assert_eq!(
+        spawns[0].monster.as_deref(),
+        Some("prefabs/monsters/goblin_elite.scn.ron")
+    );
+
+    assert_eq!(drain::<KillAllMonsters>(&mut app).len(), 1);
+    let scales = drain::<SetTimeScale>(&mut app);
+    assert_eq!(scales.len(), 1);
+    assert_eq!(scales[0].scale, 0.5);
+}
+
+#[test]
+fn test_unknown_monster_is_reported() {
+    let mut app = setup_app();
+    app.world_mut().spawn(PortalRoot);
+
+    app.world_mut()
+        .write_message(ConsoleCommand::Spawn("dragon".to_string()));
+    app.update();
+
+    assert!(drain::<SpawnMonsterRequest>(&mut app).is_empty());
+    let console = app.world().resource::<DevConsole>();
+    assert_eq!(
+        console.history.last().map(String::as_str),
+        Some("unknown monster `dragon`, one of: goblin, goblin_elite")
+    );
+}
+
+#[test]
+fn test_history_keeps_latest_lines() {
+    let mut console = DevConsole::default();
+    for index in 0..20 {
+        console.print(index.to_string());
+    }
+
+    assert_eq!(console.history.len(), DevConsole::HISTORY_LINES);
+    assert_eq!(console.history.first().map(String::as_str), Some("8"));
+}
@@ -4,15 +4,15 @@
 use {
     assets::VoidAssetsPlugin,
     bevy::{asset::LoadedFolder, prelude::*},
-    common::{
-        CommonPlugin, GameState, MonsterKilled, RequestUpgrade, UpgradePortal, VoidGameStage,
-    },
+    common::{CommonPlugin, GameState, SetTimeScale, VoidGameStage},
     items::ItemsPlugin,
     monster_factory::MonsterFactoryPlugin,
     monsters::{AvailableEnemies, MonsterConfig, MonsterPlugin},
-    player_npcs::PlayerNpcsPlugin,
+    player_npcs::{PlayerNpcConfigHandle, PlayerNpcsPlugin},
     player_npcs_ui::PlayerNpcsUiPlugin,
-    portal::PortalPlugin,
+    portal::{NextPortalId, PortalBuilder, PortalConfig, PortalPlugin},
+    prestige::PrestigePlugin,
+    save::SavePlugin,
     ui::VoidUiPlugin,
     vfx::VfxPlugin,
     wallet::VoidWalletPlugin,
@@ -22,7 +22,7 @@ pub struct VoidPortalPlugin;
 
 #[derive(Resource, Default)]
 struct GameConfigHandles {
-    portal_scene: Handle<DynamicScene>,
+    portal_config: Handle<PortalConfig>,
     monsters_folder: Handle<LoadedFolder>,
 }
 
@@ -38,12 +38,17 @@ impl Plugin for VoidPortalPlugin {
             PlayerNpcsPlugin,
             PlayerNpcsUiPlugin,
             PortalPlugin,
+            PrestigePlugin,
+            SavePlugin,
             VfxPlugin,
             VoidAssetsPlugin,
             VoidUiPlugin,
             VoidWalletPlugin,
         ));
 
+        #[cfg(feature = "dev_console")]
+        app.add_plugins(dev_console::DevConsolePlugin);
+
         app.init_resource::<GameConfigHandles>();
 
         app.configure_sets(
@@ -62,7 +67,10 @@ impl Plugin for VoidPortalPlugin {
         app.add_systems(Startup, (setup_camera, start_loading));
         app.add_systems(
             Update,
-            check_assets_ready.run_if(in_state(GameState::Loading)),
+            (
+                check_assets_ready.run_if(in_state(GameState::Loading)),
+                apply_time_scale,
+            ),
         );
 
         info!("Void Portal Core initialized");
@@ -79,7 +87,7 @@ fn start_loading(
     asset_server: Res<AssetServer>,
     mut handles: ResMut<GameConfigHandles>,
 ) {
-    handles.portal_scene = asset_server.load("prefabs/portal.scn.ron");
+    handles.portal_config = asset_server.load("configs/main.portal.ron");
     handles.monsters_folder = asset_server.load_folder("configs/monsters");
 
     commands.spawn((
@@ -100,25 +108,24 @@ struct LoadingText;
 fn check_assets_ready(
     mut commands: Commands,
     handles: Res<GameConfigHandles>,
-    // We don't need to check for PortalConfig asset anymore, just that the scene handle is "ready" (which load returns immediately,
-    // but to follow the pattern we might want to check load state.
-    // However, for DynamicScene, usually we just spawn it.
-    // We DO need to wait for monsters folder to be loaded to populate AvailableEnemies.
+    // We need to wait for monsters folder to be loaded to populate AvailableEnemies.
     loaded_folders: Res<Assets<LoadedFolder>>,
     monster_config_asset: Res<Assets<MonsterConfig>>,
+    portal_config_asset: Res<Assets<PortalConfig>>,
+    player_npc_config: Res<PlayerNpcConfigHandle>,
+    asset_server: Res<AssetServer>,
     mut available_monsters: ResMut<AvailableEnemies>,
+    mut portal_ids: ResMut<NextPortalId>,
     mut next_state: ResMut<NextState<GameState>>,
     loading_text_query: Query<Entity, With<LoadingText>>,
-    asset_server: Res<AssetServer>,
-    mut scene_spawner: ResMut<SceneSpawner>,
 ) {
     // Check if monsters are loaded
     if let Some(monsters_folder) = loaded_folders.get(&handles.monsters_folder) {
-        // Also check if portal scene is loaded?
-        // Not strictly required to access its content here (since we just spawn it),
-        // but good for ensuring smooth transition.
-
-        if !asset_server.is_loaded_with_dependencies(&handles.portal_scene) {
+        let Some(portal_config) = portal_config_asset.get(&handles.portal_config) else {
+            return;
+        };
+        // Soldiers spawn on entering Playing and need their config applied
+        if !asset_server.is_loaded_with_dependencies(&player_npc_config.0) {
             return;
         }
 
@@ -136,8 +143,11 @@ fn check_assets_ready(
             info!("Loaded {} monster configs", available_monsters.0.len());
         }
 
-        // Spawn the Portal Scene
-        scene_spawner.spawn_dynamic(handles.portal_scene.clone());
+        // Spawn the Portal
+        PortalBuilder::new(portal_config)
+            .with_id(portal_ids.take())
+            .with_source(handles.portal_config.clone())
+            .spawn(&mut commands);
 
         for entity in loading_text_query.iter() {
             commands.entity(entity).despawn();
@@ -148,5 +158,14 @@ fn check_assets_ready(
     }
 }
 
+/// Scales virtual time, so every system driven by `Time` speeds up or slows down alike.
+/// Non-finite scales are ignored, `Time<Virtual>` panics on them.
+fn apply_time_scale(mut events: MessageReader<SetTimeScale>, mut time: ResMut<Time<Virtual>>) {
+    if let Some(event) = events.read().filter(|event| event.scale.is_finite()).last() {
+        time.set_relative_speed(event.scale.max(0.0));
+        info!("Time scale set to {}", event.scale);
+    }
+}
+
 #[cfg(test)]
 mod tests_integration;
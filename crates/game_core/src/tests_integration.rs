@@ -3,15 +3,16 @@ use {
     common::{
         components::{PortalLevel, PortalRoot, PortalSpawner, UpgradeCost, UpgradeSlot},
         BaseMonsterHealth, BaseMonsterLifetime, BaseMonsterReward, BaseMonsterSpeed,
-        GrowthStrategy, MonsterKilled, Reward, SpawnMonsterRequest, UpgradeableStat,
+        GrowthStrategy, MonsterIndex, MonsterKilled, MonsterScavenged, ProjectileCollisionMessage,
+        Reward, SpawnMonsterRequest, UpgradeableStat,
     },
     monster_factory::SpawnMonsterEvent,
     monsters::{
-        manage_monster_lifecycle, move_monsters, update_monster_health_ui, AvailableEnemies,
-        Health, Lifetime, Monster, MonsterConfig, Speed,
+        index_monsters, manage_monster_lifecycle, move_monsters, update_monster_health_ui,
+        AvailableEnemies, Health, Lifetime, Monster, MonsterConfig, MovementPattern, Speed,
     },
     player_npcs::{move_projectiles, projectile_collision},
-    portal::{portal_spawn_logic, portal_tick_logic, PortalSpawnTracker},
+    portal::{portal_spawn_logic, portal_tick_logic},
 };
 
 fn setup_app() -> App {
@@ -19,8 +20,11 @@ fn setup_app() -> App {
     app.add_plugins(MinimalPlugins.build().disable::<TimePlugin>());
     app.insert_resource(Time::<()>::default());
     app.add_message::<MonsterKilled>();
+    app.add_message::<MonsterScavenged>();
+    app.add_message::<ProjectileCollisionMessage>();
     app.add_message::<SpawnMonsterRequest>();
     app.add_message::<SpawnMonsterEvent>();
+    app.init_resource::<MonsterIndex>();
 
     // Mock Window
     app.world_mut().spawn((
@@ -32,14 +36,17 @@ fn setup_app() -> App {
     ));
 
     app.insert_resource(AvailableEnemies(vec![MonsterConfig {
+        prefab: "prefabs/monsters/goblin.scn.ron".to_string(),
+        spawn_weight: 1.0,
+        min_portal_level: 0,
         health_coef: 1.0,
         lifetime_coef: 1.0,
         speed_coef: 1.0,
         reward_coef: 1.0,
+        movement: MovementPattern::default(),
+        return_to_portal: None,
     }]));
 
-    app.init_resource::<PortalSpawnTracker>();
-
     // Add Systems
     app.add_systems(
         Update,
@@ -49,7 +56,7 @@ fn setup_app() -> App {
             (manage_monster_lifecycle),
             update_monster_health_ui,
             move_projectiles,
-            projectile_collision,
+            (index_monsters, projectile_collision).chain(),
         ),
     );
 
@@ -58,6 +65,7 @@ fn setup_app() -> App {
         .world_mut()
         .spawn((
             PortalRoot,
+            Transform::default(),
             PortalLevel {
                 active: 0,
                 max_unlocked: 0,
@@ -66,8 +74,9 @@ fn setup_app() -> App {
                 strategy: GrowthStrategy::Linear {
                     base: 100.0,
                     coefficient: 50.0,
-                },
-                current_price: 100.0,
+                }
+                .into(),
+                current_price: 100.0.into(),
             },
             PortalSpawner {
                 timer: Timer::from_seconds(1.0, TimerMode::Repeating),
@@ -102,9 +111,9 @@ fn setup_app() -> App {
             UpgradeableStat {
                 level: 0.0,
                 value: 10.0, // Enough capacity
-                price: 100.0,
+                price: 100.0.into(),
                 value_strategy: GrowthStrategy::Static(10.0),
-                price_strategy: GrowthStrategy::Static(100.0),
+                price_strategy: GrowthStrategy::Static(100.0).into(),
             },
         ))
         .id();
@@ -174,9 +183,12 @@ fn test_monster_movement() {
                 current: 100.0,
                 max: 100.0,
             },
-            Reward(10.0),
+            Reward(10.0.into()),
             Speed(100.0),
-            Lifetime::default(), // Required for lifecycle system
+            // Required for lifecycle system, long enough to outlive the test
+            Lifetime {
+                timer: Timer::from_seconds(10.0, TimerMode::Once),
+            },
         ))
         .id();
 
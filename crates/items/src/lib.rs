@@ -12,6 +12,9 @@ impl Plugin for ItemsPlugin {
             .register_type::<AttackRange>()
             .register_type::<AttackSpeed>()
             .register_type::<ProjectileStats>()
+            .register_type::<Homing>()
+            .register_type::<Pierce>()
+            .register_type::<Chain>()
             .register_type::<AreaOfEffect>();
     }
 }
@@ -47,10 +50,18 @@ pub struct BaseDamage(pub f32);
 #[reflect(Component)]
 pub struct AttackRange(pub f32);
 
+/// Attacks per second. The weapon cooldown is `1 / AttackSpeed` seconds.
 #[derive(Component, Reflect, Default, Debug)]
 #[reflect(Component)]
 pub struct AttackSpeed(pub f32);
 
+impl AttackSpeed {
+    /// Cooldown between two attacks, `None` for weapons that never attack.
+    pub fn cooldown_secs(&self) -> Option<f32> {
+        (self.0 > 0.0).then(|| 1.0 / self.0)
+    }
+}
+
 #[derive(Component, Reflect, Default, Debug)]
 #[reflect(Component)]
 pub struct ProjectileStats {
@@ -58,6 +69,33 @@ pub struct ProjectileStats {
     pub lifetime: f32,
 }
 
+/// Projectiles steer toward their target, turning at most `turn_rate`
+/// radians per second.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct Homing {
+    pub turn_rate: f32,
+}
+
+/// Extra monsters a projectile passes through before it stops.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct Pierce(pub u32);
+
+/// Once it can't pierce anymore, a projectile jumps to the nearest monster
+/// within `range` it hasn't hit yet, up to `bounces` times.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct Chain {
+    pub bounces: u32,
+    pub range: f32,
+}
+
+/// Hits every monster inside a shape instead of a single target.
+///
+/// Melee weapons swing an arc of `angle` radians (full width) centered on
+/// their target, reaching `radius` or the weapon range, whichever is longer.
+/// Projectiles splash everything within `radius` of the impact point.
 #[derive(Component, Reflect, Default, Debug)]
 #[reflect(Component)]
 pub struct AreaOfEffect {
@@ -1,4 +1,4 @@
-use bevy::prelude::*;
+use {bevy::prelude::*, monsters::MovementPattern};
 
 /// Added to the entity at spawn time to carry reference to creator.
 /// Removed after hydration is complete.
@@ -8,6 +8,8 @@ pub struct MonsterBuilder {
     pub portal_entity: Entity,
     pub spawn_index: u32,
     pub target_position: Vec2,
+    pub movement: MovementPattern,
+    pub return_to_portal: Option<f32>,
 }
 
 // --- Coefficient Proxies (Loaded from Scene) ---
@@ -35,3 +37,50 @@ pub struct RewardCoef {
 pub struct LifetimeCoef {
     pub val: f32,
 }
+
+/// Flat essence paid on kill on top of the void shard reward. Only elite
+/// monster prefabs carry it.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct EssenceReward {
+    pub amount: f32,
+}
+
+/// Multipliers on the portal's `BaseMonsterAttack`.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct AttackCoef {
+    pub damage: f32,
+    pub range: f32,
+    pub cooldown: f32,
+}
+
+impl Default for AttackCoef {
+    fn default() -> Self {
+        Self {
+            damage: 1.0,
+            range: 1.0,
+            cooldown: 1.0,
+        }
+    }
+}
+
+/// Share of the portal's `BaseMonsterArmor` this monster gets per damage type.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct ArmorCoef {
+    pub physical: f32,
+    pub magic: f32,
+    pub void: f32,
+}
+
+impl Default for ArmorCoef {
+    fn default() -> Self {
+        // Plain armor: stops blades, barely slows spells, does nothing against the void
+        Self {
+            physical: 1.0,
+            magic: 0.5,
+            void: 0.0,
+        }
+    }
+}
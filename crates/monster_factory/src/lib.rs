@@ -14,6 +14,9 @@ impl Plugin for MonsterFactoryPlugin {
             .register_type::<SpeedCoef>()
             .register_type::<RewardCoef>()
             .register_type::<LifetimeCoef>()
+            .register_type::<ArmorCoef>()
+            .register_type::<AttackCoef>()
+            .register_type::<EssenceReward>()
             .register_type::<MonsterBuilder>();
 
         app.add_message::<SpawnMonsterEvent>();
@@ -7,9 +7,13 @@ use {
             BaseMonsterHealth, BaseMonsterLifetime, BaseMonsterReward, BaseMonsterSpeed,
             PortalLevel, PortalRoot, ScavengerPenalty,
         },
-        BaseMonsterArmor, Reward, ScavengeModifier, UpgradeSlot, UpgradeableStat,
+        BaseMonsterArmor, BaseMonsterAttack, Currencies, Currency, PrestigeMultipliers, Reward,
+        ScavengeModifier, UpgradeSlot, UpgradeableStat,
+    },
+    monsters::{
+        Armor, Health, Lifetime, Monster, MonsterAttack, MonsterPrefab, MovementPattern,
+        ReturnToPortal, SpawnIndex, SpawnedBy, Speed,
     },
-    monsters::{Health, Lifetime, Monster, SpawnIndex, Speed},
     std::collections::HashMap,
 };
 
@@ -20,11 +24,14 @@ pub struct SpawnMonsterEvent {
     pub portal_entity: Entity,
     pub spawn_index: u32,
     pub target_position: Vec2,
+    pub movement: MovementPattern,
+    /// `MonsterConfig::return_to_portal`.
+    pub return_to_portal: Option<f32>,
 }
 
-/// Resource to track pending spawns from the SceneSpawner
+/// Resource to track pending spawns from the SceneSpawner, with the prefab each came from
 #[derive(Resource, Default)]
-pub struct PendingMonsterSpawns(HashMap<bevy::scene::InstanceId, MonsterBuilder>);
+pub struct PendingMonsterSpawns(HashMap<bevy::scene::InstanceId, (MonsterBuilder, String)>);
 
 /// 1. The Listener: Starts the spawn process
 pub fn spawn_monster_listener(
@@ -40,8 +47,12 @@ pub fn spawn_monster_listener(
             portal_entity: event.portal_entity,
             spawn_index: event.spawn_index,
             target_position: event.target_position,
+            movement: event.movement,
+            return_to_portal: event.return_to_portal,
         };
-        pending_spawns.0.insert(instance_id, builder);
+        pending_spawns
+            .0
+            .insert(instance_id, (builder, event.asset_path.clone()));
     }
 }
 
@@ -54,7 +65,7 @@ pub fn attach_monster_context(
 ) {
     let mut to_remove = Vec::new();
 
-    for (instance_id, builder) in pending_spawns.0.iter() {
+    for (instance_id, (builder, prefab)) in pending_spawns.0.iter() {
         if scene_spawner.instance_is_ready(*instance_id) {
             let entities: Vec<Entity> =
                 scene_spawner.iter_instance_entities(*instance_id).collect();
@@ -71,7 +82,7 @@ pub fn attach_monster_context(
             if let Some(entity) = entities.first() {
                 commands
                     .entity(*entity)
-                    .insert(*builder)
+                    .insert((*builder, MonsterPrefab(prefab.clone())))
                     .insert(Transform::from_translation(spawn_translation));
             }
 
@@ -100,6 +111,9 @@ pub fn hydrate_monster_stats(
         Option<&SpeedCoef>,
         Option<&RewardCoef>,
         Option<&LifetimeCoef>,
+        Option<&ArmorCoef>,
+        Option<&AttackCoef>,
+        Option<&EssenceReward>,
     )>,
     // Query components from the Portal (Source of Truth)
     portal_query: Query<
@@ -112,13 +126,28 @@ pub fn hydrate_monster_stats(
             &Children,
             Option<&ScavengerPenalty>,
             &BaseMonsterArmor,
+            Option<&BaseMonsterAttack>,
         ),
         With<PortalRoot>,
     >,
     // Query generic stats for the "Lifetime" upgrade
     upgrade_stat_query: Query<(&UpgradeSlot, &UpgradeableStat)>,
+    multipliers: Option<Res<PrestigeMultipliers>>,
 ) {
-    for (entity, builder, hp_coef, speed_coef, reward_coef, lifetime_coef) in monster_query.iter() {
+    let prestige = multipliers.as_deref().copied().unwrap_or_default();
+
+    for (
+        entity,
+        builder,
+        hp_coef,
+        speed_coef,
+        reward_coef,
+        lifetime_coef,
+        armor_coef,
+        attack_coef,
+        essence,
+    ) in monster_query.iter()
+    {
         let mut entity_cmds = commands.entity(entity);
 
         // 1. Fetch Portal Data
@@ -131,6 +160,7 @@ pub fn hydrate_monster_stats(
             children,
             scav_penalty_opt,
             base_armor,
+            base_attack,
         )) = portal_query.get(builder.portal_entity)
         else {
             warn!(
@@ -154,7 +184,7 @@ pub fn hydrate_monster_stats(
         // 3. Calculate Base Stats
         let base_health = health_scaling.0.calculate(level.active as f32);
         let base_speed = speed_scaling.0.calculate(level.active as f32);
-        let base_reward = reward_scaling.0.calculate(level.active as f32);
+        let base_reward = reward_scaling.0.calculate_big(level.active as f32);
         let base_lifetime = lifetime_scaling.0.calculate(level.active as f32);
         let base_armor = base_armor.calculate(level.active as f32);
 
@@ -167,7 +197,7 @@ pub fn hydrate_monster_stats(
             base_health * coef.val
         } else {
             base_health
-        };
+        } * prestige.monster_health;
         entity_cmds.insert(Health {
             current: final_hp,
             max: final_hp,
@@ -185,12 +215,16 @@ pub fn hydrate_monster_stats(
 
         // Reward
         let final_reward = if let Some(coef) = reward_coef {
-            base_reward * coef.val
+            base_reward * coef.val as f64
         } else {
             base_reward
         };
-        entity_cmds.insert(Reward(final_reward));
+        let essence = essence.map(|essence| essence.amount).unwrap_or_default();
+        entity_cmds.insert(Reward(
+            Currencies::from(final_reward).with(Currency::Essence, essence),
+        ));
         entity_cmds.remove::<RewardCoef>();
+        entity_cmds.remove::<EssenceReward>();
 
         // Lifetime
         let final_lifetime = if let Some(coef) = lifetime_coef {
@@ -204,15 +238,50 @@ pub fn hydrate_monster_stats(
         });
         entity_cmds.remove::<LifetimeCoef>();
 
+        // Armor
+        let default_armor_coef = ArmorCoef::default();
+        let armor_coef = armor_coef.unwrap_or(&default_armor_coef);
+        entity_cmds.insert(Armor {
+            physical: base_armor * armor_coef.physical,
+            magic: base_armor * armor_coef.magic,
+            void: base_armor * armor_coef.void,
+        });
+        entity_cmds.remove::<ArmorCoef>();
+
+        // Attack, portals without one spawn harmless monsters
+        if let Some(base_attack) = base_attack {
+            let default_attack_coef = AttackCoef::default();
+            let attack_coef = attack_coef.unwrap_or(&default_attack_coef);
+            let level = level.active as f32;
+            entity_cmds.insert(MonsterAttack {
+                damage: base_attack.damage.calculate(level) * attack_coef.damage,
+                range: base_attack.range.calculate(level) * attack_coef.range,
+                timer: Timer::from_seconds(
+                    base_attack.cooldown.calculate(level) * attack_coef.cooldown,
+                    TimerMode::Once,
+                ),
+            });
+        }
+        entity_cmds.remove::<AttackCoef>();
+
         // Scavenge Modifier
         entity_cmds.insert(ScavengeModifier(scavenger_penalty));
 
-        // Spawn Index & Monster Marker
-        entity_cmds.insert(SpawnIndex(builder.spawn_index));
+        // Spawn Index, Source Portal & Monster Marker
+        entity_cmds.insert((
+            SpawnIndex(builder.spawn_index),
+            SpawnedBy(builder.portal_entity),
+        ));
         entity_cmds.insert(Monster {
             target_position: builder.target_position,
         });
 
+        // Movement
+        entity_cmds.insert(builder.movement);
+        if let Some(seconds_left) = builder.return_to_portal {
+            entity_cmds.insert(ReturnToPortal { seconds_left });
+        }
+
         // Cleanup
         entity_cmds.remove::<MonsterBuilder>();
     }
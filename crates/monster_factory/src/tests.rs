@@ -7,9 +7,12 @@ use {
             BaseMonsterHealth, BaseMonsterLifetime, BaseMonsterReward, BaseMonsterSpeed,
             PortalLevel, PortalRoot, PortalUpgrades, ScavengerPenalty,
         },
-        GrowthStrategy, Reward, ScavengeModifier,
+        BaseMonsterArmor, BaseMonsterAttack, Currencies, Currency, GrowthStrategy, Reward,
+        ScavengeModifier,
+    },
+    monsters::{
+        Armor, Health, Lifetime, Monster, MonsterAttack, MovementPattern, ReturnToPortal, Speed,
     },
-    monsters::{Health, Lifetime, Monster, Speed},
     std::collections::HashMap,
 };
 
@@ -23,6 +26,7 @@ fn test_hydrate_monster_stats() {
         .register_type::<SpeedCoef>()
         .register_type::<RewardCoef>()
         .register_type::<LifetimeCoef>()
+        .register_type::<ArmorCoef>()
         .register_type::<MonsterBuilder>();
 
     // Register components for hydration target
@@ -70,6 +74,18 @@ fn test_hydrate_monster_stats() {
                 base: 20.0,
                 coefficient: 0.0,
             }),
+            BaseMonsterArmor(GrowthStrategy::Linear {
+                base: 20.0,
+                coefficient: 0.0,
+            }),
+            BaseMonsterAttack {
+                damage: GrowthStrategy::Linear {
+                    base: 2.0,
+                    coefficient: 1.0,
+                },
+                range: GrowthStrategy::Static(30.0),
+                cooldown: GrowthStrategy::Static(2.0),
+            },
             ScavengerPenalty(0.5),
             PortalUpgrades(HashMap::new()), // No upgrades for simplicity
             bevy::ecs::hierarchy::Children::default(),
@@ -82,6 +98,8 @@ fn test_hydrate_monster_stats() {
         portal_entity,
         spawn_index: 1,
         target_position: target_pos,
+        movement: MovementPattern::Orbit { radius: 80.0 },
+        return_to_portal: Some(3.0),
     };
 
     let entity = app
@@ -91,7 +109,18 @@ fn test_hydrate_monster_stats() {
             HpCoef { val: 1.5 },
             SpeedCoef { val: 2.0 },
             RewardCoef { val: 0.5 },
+            EssenceReward { amount: 2.0 },
             LifetimeCoef { val: 1.0 },
+            ArmorCoef {
+                physical: 1.0,
+                magic: 0.25,
+                void: 0.0,
+            },
+            AttackCoef {
+                damage: 2.0,
+                range: 1.0,
+                cooldown: 0.5,
+            },
             Transform::default(),
             Visibility::default(),
         ))
@@ -110,7 +139,11 @@ fn test_hydrate_monster_stats() {
 
     let reward = app.world().get::<Reward>(entity);
     assert!(reward.is_some(), "Reward component missing");
-    assert_eq!(reward.unwrap().0, 25.0); // 50 * 0.5
+    // 50 * 0.5 void shards, essence is paid as is
+    assert_eq!(
+        reward.unwrap().0,
+        Currencies::from(25.0).with(Currency::Essence, 2.0)
+    );
 
     let lifetime = app.world().get::<Lifetime>(entity);
     assert!(lifetime.is_some(), "Lifetime component missing");
@@ -121,10 +154,38 @@ fn test_hydrate_monster_stats() {
     assert!(scav.is_some(), "ScavengeModifier component missing");
     assert_eq!(scav.unwrap().0, 0.5);
 
+    let armor = app.world().get::<Armor>(entity);
+    assert!(armor.is_some(), "Armor component missing");
+    assert_eq!(
+        *armor.unwrap(),
+        Armor {
+            physical: 20.0,
+            magic: 5.0,
+            void: 0.0,
+        }
+    );
+
+    let attack = app.world().get::<MonsterAttack>(entity);
+    assert!(attack.is_some(), "MonsterAttack component missing");
+    let attack = attack.unwrap();
+    // Damage = (2 + 1*1) * 2 = 6
+    assert_eq!(attack.damage, 6.0);
+    assert_eq!(attack.range, 30.0);
+    assert_eq!(attack.timer.duration().as_secs_f32(), 1.0);
+
     let monster = app.world().get::<Monster>(entity);
     assert!(monster.is_some(), "Monster component missing");
     assert_eq!(monster.unwrap().target_position, target_pos);
 
+    assert_eq!(
+        app.world().get::<MovementPattern>(entity),
+        Some(&MovementPattern::Orbit { radius: 80.0 })
+    );
+    assert_eq!(
+        app.world().get::<ReturnToPortal>(entity),
+        Some(&ReturnToPortal { seconds_left: 3.0 })
+    );
+
     // Ensure builder and coefs are removed
     assert!(
         app.world().get::<MonsterBuilder>(entity).is_none(),
@@ -134,4 +195,12 @@ fn test_hydrate_monster_stats() {
         app.world().get::<HpCoef>(entity).is_none(),
         "HpCoef should be removed"
     );
+    assert!(
+        app.world().get::<ArmorCoef>(entity).is_none(),
+        "ArmorCoef should be removed"
+    );
+    assert!(
+        app.world().get::<AttackCoef>(entity).is_none(),
+        "AttackCoef should be removed"
+    );
 }
@@ -4,10 +4,13 @@ use {
     bevy::prelude::*,
     bevy_common_assets::ron::RonAssetPlugin,
     common::{
-        GameState, MarkedForCleanUp, MonsterKilled, MonsterScavenged, Reward, ScavengeModifier,
-        VoidGameStage,
+        Collider, DamageMessage, DamageStep, DamageType, GameState, KillAllMonsters,
+        MarkedForCleanUp, MonsterIndex, MonsterKilled, MonsterScavenged, Reward,
+        ScavengeModifier, VoidGameStage,
     },
+    rand::Rng,
     serde::Deserialize,
+    std::f32::consts::TAU,
 };
 
 pub struct MonsterPlugin;
@@ -18,18 +21,31 @@ impl Plugin for MonsterPlugin {
 
         app.register_type::<Monster>()
             .register_type::<Health>()
+            .register_type::<Armor>()
+            .register_type::<MonsterAttack>()
+            .register_type::<Hostile>()
             .register_type::<Lifetime>()
             .register_type::<SpawnIndex>()
+            .register_type::<SpawnedBy>()
+            .register_type::<MonsterPrefab>()
+            .register_type::<PortalMonsters>()
             .register_type::<Speed>()
+            .register_type::<MovementPattern>()
+            .register_type::<ReturnToPortal>()
             .register_type::<MonsterConfig>()
             .register_type::<LifetimeText>();
 
-        app.init_resource::<AvailableEnemies>();
+        app.init_resource::<AvailableEnemies>()
+            .init_resource::<MonsterIndex>();
 
         app.add_systems(
             Update,
             (
-                move_monsters.in_set(VoidGameStage::Actions),
+                (reload_monster_configs, kill_all_monsters, index_monsters)
+                    .in_set(VoidGameStage::FrameStart),
+                (move_monsters, monster_attack).in_set(VoidGameStage::Actions),
+                mitigate_damage.in_set(DamageStep::Mitigation),
+                apply_damage.in_set(DamageStep::Apply),
                 (
                     manage_monster_lifecycle,
                     process_marked_cleanup,
@@ -46,10 +62,28 @@ impl Plugin for MonsterPlugin {
 // Configs
 #[derive(Deserialize, Asset, Clone, Debug, Resource, Reflect)]
 pub struct MonsterConfig {
+    /// Scene spawned for this monster, relative to the assets folder.
+    pub prefab: String,
+    /// Relative chance to be picked among the monsters allowed at the portal level.
+    #[serde(default = "default_spawn_weight")]
+    pub spawn_weight: f32,
+    /// Lowest active portal level this monster spawns at.
+    #[serde(default)]
+    pub min_portal_level: u32,
     pub health_coef: f32,
     pub lifetime_coef: f32,
     pub speed_coef: f32,
     pub reward_coef: f32,
+    #[serde(default)]
+    pub movement: MovementPattern,
+    /// Seconds of `Lifetime` left when the monster heads back to its portal,
+    /// whatever its movement. Never goes back when unset.
+    #[serde(default)]
+    pub return_to_portal: Option<f32>,
+}
+
+fn default_spawn_weight() -> f32 {
+    1.0
 }
 
 #[derive(Resource, Default)]
@@ -69,6 +103,45 @@ pub struct Health {
     pub max: f32,
 }
 
+/// Armor rating per damage type. Each point reduces incoming damage with
+/// diminishing returns: 100 armor halves it, 300 quarters it.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct Armor {
+    pub physical: f32,
+    pub magic: f32,
+    pub void: f32,
+}
+
+impl Armor {
+    pub fn rating(&self, damage_type: DamageType) -> f32 {
+        match damage_type {
+            DamageType::Physical => self.physical,
+            DamageType::Magic => self.magic,
+            DamageType::Void => self.void,
+        }
+    }
+
+    pub fn mitigate(&self, amount: f32, damage_type: DamageType) -> f32 {
+        let rating = self.rating(damage_type).max(0.0);
+        amount * 100.0 / (100.0 + rating)
+    }
+}
+
+/// Hits the closest `Hostile` within `range` whenever `timer` is finished.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct MonsterAttack {
+    pub damage: f32,
+    pub range: f32,
+    pub timer: Timer,
+}
+
+/// Units monsters attack when they get in range.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Hostile;
+
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
 pub struct Lifetime {
@@ -79,29 +152,270 @@ pub struct Lifetime {
 #[reflect(Component)]
 pub struct SpawnIndex(pub u32);
 
+/// Portal a monster came out of.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[relationship(relationship_target = PortalMonsters)]
+pub struct SpawnedBy(pub Entity);
+
+/// Prefab a monster was spawned from, ties it back to its `MonsterConfig`.
+#[derive(Component, Reflect, Default, Debug, Clone, PartialEq)]
+#[reflect(Component)]
+pub struct MonsterPrefab(pub String);
+
+/// Monsters spawned by this portal that still exist, kept up to date by Bevy.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+#[relationship_target(relationship = SpawnedBy)]
+pub struct PortalMonsters(Vec<Entity>);
+
+impl PortalMonsters {
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.0.iter().copied()
+    }
+}
+
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
 pub struct Speed(pub f32);
 
+/// How a monster gets around, set from its `MonsterConfig`.
+#[derive(Component, Reflect, Deserialize, Default, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component, Default)]
+pub enum MovementPattern {
+    /// Walks to `Monster.target_position` and stays there.
+    #[default]
+    Straight,
+    /// Picks a new point within `radius` of its portal on each arrival.
+    Wander { radius: f32 },
+    /// Walks to `Monster.target_position` `angle` radians off the straight
+    /// line, switching sides every half `period` seconds.
+    ZigZag { angle: f32, period: f32 },
+    /// Circles its portal at `radius`, counterclockwise.
+    Orbit { radius: f32 },
+    /// Like `Straight`, but runs from `Hostile` units closer than `radius`.
+    Flee { radius: f32 },
+}
+
+/// Heads back to the portal once `seconds_left` or less of `Lifetime` remain,
+/// overriding the `MovementPattern`.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct ReturnToPortal {
+    pub seconds_left: f32,
+}
+
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
 pub struct LifetimeText;
 
 // Systems
+
+/// Picks up edits to monster configs. Spawn weights apply to the next picks,
+/// live monsters of an edited config switch to its movement and return-to-portal
+/// settings right away.
+pub fn reload_monster_configs(
+    mut commands: Commands,
+    mut config_events: MessageReader<AssetEvent<MonsterConfig>>,
+    configs: Res<Assets<MonsterConfig>>,
+    mut available_monsters: ResMut<AvailableEnemies>,
+    mut monster_query: Query<(Entity, &MonsterPrefab, &mut MovementPattern)>,
+) {
+    let modified: Vec<&MonsterConfig> = config_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { id } => configs.get(*id),
+            _ => None,
+        })
+        .collect();
+    if modified.is_empty() {
+        return;
+    }
+
+    available_monsters.0 = configs.iter().map(|(_, config)| config.clone()).collect();
+    info!("Reloaded {} monster configs", available_monsters.0.len());
+
+    for (entity, prefab, mut movement) in &mut monster_query {
+        let Some(config) = modified.iter().find(|config| config.prefab == prefab.0) else {
+            continue;
+        };
+        *movement = config.movement;
+        match config.return_to_portal {
+            Some(seconds_left) => {
+                commands
+                    .entity(entity)
+                    .insert(ReturnToPortal { seconds_left });
+            }
+            None => {
+                commands.entity(entity).remove::<ReturnToPortal>();
+            }
+        }
+    }
+}
+
+/// Reduces every hit by the target's armor against its damage type.
+pub fn mitigate_damage(mut messages: MessageMutator<DamageMessage>, armor_query: Query<&Armor>) {
+    for msg in messages.read() {
+        if let Ok(armor) = armor_query.get(msg.target) {
+            msg.amount = armor.mitigate(msg.amount, msg.damage_type);
+        }
+    }
+}
+
+/// The only place damage is written to `Health`, whatever its source.
+pub fn apply_damage(
+    mut messages: MessageReader<DamageMessage>,
+    mut health_query: Query<&mut Health>,
+) {
+    for msg in messages.read() {
+        if let Ok(mut health) = health_query.get_mut(msg.target) {
+            health.current -= msg.amount.round();
+            debug!(
+                "Unit {:?} took {} {:?} damage from {:?}",
+                msg.target, msg.amount, msg.damage_type, msg.source
+            );
+        }
+    }
+}
+
+/// Moves monsters along their `MovementPattern`, monsters without one walk
+/// straight to their target position.
 pub fn move_monsters(
     time: Res<Time>,
-    mut monster_query: Query<(&mut Transform, &Monster, &Speed), Without<MarkedForCleanUp>>,
+    mut monster_query: Query<
+        (
+            &mut Transform,
+            &mut Monster,
+            &Speed,
+            Option<&MovementPattern>,
+            Option<&ReturnToPortal>,
+            Option<&Lifetime>,
+            Option<&SpawnedBy>,
+        ),
+        Without<MarkedForCleanUp>,
+    >,
+    portal_query: Query<&Transform, Without<Monster>>,
+    hostile_query: Query<&Transform, (With<Hostile>, Without<Monster>)>,
+) {
+    let mut rng = rand::rng();
+
+    for (mut transform, mut monster, speed, pattern, return_to_portal, lifetime, spawned_by) in
+        monster_query.iter_mut()
+    {
+        let position = transform.translation.truncate();
+        let step = speed.0 * time.delta_secs();
+        let portal = spawned_by
+            .and_then(|spawned_by| portal_query.get(spawned_by.0).ok())
+            .map(|portal| portal.translation.truncate());
+
+        let returning = return_to_portal
+            .zip(lifetime)
+            .filter(|(rule, lifetime)| lifetime.timer.remaining_secs() <= rule.seconds_left)
+            .and(portal);
+
+        let offset = if let Some(portal) = returning {
+            step_toward(position, portal, step)
+        } else {
+            match pattern.copied().unwrap_or_default() {
+                MovementPattern::Straight => step_toward(position, monster.target_position, step),
+                MovementPattern::Wander { radius } => {
+                    if position.distance(monster.target_position) <= 1.0 {
+                        // Uniform over the disc around the portal
+                        let center = portal.unwrap_or(position);
+                        let distance = radius * rng.random::<f32>().sqrt();
+                        monster.target_position = center
+                            + Vec2::from_angle(rng.random_range(0.0..TAU)) * distance;
+                    }
+                    step_toward(position, monster.target_position, step)
+                }
+                MovementPattern::ZigZag { angle, period } => {
+                    let to_target = monster.target_position - position;
+                    // The last weave goes straight, so the monster doesn't overshoot forever
+                    if period <= 0.0 || to_target.length() <= speed.0 * period {
+                        step_toward(position, monster.target_position, step)
+                    } else {
+                        let elapsed = lifetime
+                            .map(|lifetime| lifetime.timer.elapsed_secs())
+                            .unwrap_or_else(|| time.elapsed_secs());
+                        let angle = if elapsed % period < period / 2.0 {
+                            angle
+                        } else {
+                            -angle
+                        };
+                        Vec2::from_angle(angle).rotate(to_target.normalize()) * step
+                    }
+                }
+                MovementPattern::Orbit { radius } => {
+                    let center = portal.unwrap_or(monster.target_position);
+                    let from_center = position - center;
+                    let outward = from_center.try_normalize().unwrap_or(Vec2::X);
+                    // Along the circle, steering back onto it when off
+                    let correction = (radius - from_center.length()) / radius.max(1.0);
+                    (outward.perp() + outward * correction).normalize_or_zero() * step
+                }
+                MovementPattern::Flee { radius } => {
+                    let threat = hostile_query
+                        .iter()
+                        .map(|hostile| hostile.translation.truncate())
+                        .filter(|hostile| hostile.distance(position) < radius)
+                        .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)));
+                    match threat {
+                        Some(threat) => {
+                            (position - threat).try_normalize().unwrap_or(Vec2::X) * step
+                        }
+                        None => step_toward(position, monster.target_position, step),
+                    }
+                }
+            }
+        };
+
+        transform.translation += offset.extend(0.0);
+    }
+}
+
+/// Offset moving `step` from `position` toward `target`, without overshooting.
+fn step_toward(position: Vec2, target: Vec2, step: f32) -> Vec2 {
+    let to_target = target - position;
+    if to_target.length() <= 1.0 {
+        return Vec2::ZERO;
+    }
+    to_target.clamp_length_max(step)
+}
+
+/// Monsters hit the closest living hostile in range. Hits go through the
+/// regular damage pipeline, so the target's armor applies.
+pub fn monster_attack(
+    time: Res<Time>,
+    mut monster_query: Query<
+        (Entity, &Transform, &mut MonsterAttack, Option<&DamageType>),
+        (With<Monster>, Without<MarkedForCleanUp>),
+    >,
+    hostile_query: Query<(Entity, &Transform, &Health), With<Hostile>>,
+    mut damage_events: MessageWriter<DamageMessage>,
 ) {
-    for (mut transform, monster, speed) in monster_query.iter_mut() {
-        let direction =
-            (monster.target_position - transform.translation.truncate()).normalize_or_zero();
-        let distance = transform
-            .translation
-            .truncate()
-            .distance(monster.target_position);
-
-        if distance > 1.0 {
-            transform.translation += (direction * speed.0 * time.delta_secs()).extend(0.0);
+    for (monster, monster_tf, mut attack, damage_type) in monster_query.iter_mut() {
+        attack.timer.tick(time.delta());
+        if !attack.timer.is_finished() {
+            continue;
+        }
+
+        let target = hostile_query
+            .iter()
+            .filter(|(_, _, health)| health.current > 0.0)
+            .map(|(entity, tf, _)| (entity, monster_tf.translation.distance(tf.translation)))
+            .filter(|(_, distance)| *distance <= attack.range)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        if let Some((target, _)) = target {
+            damage_events.write(DamageMessage {
+                source: monster,
+                target,
+                weapon: None,
+                amount: attack.damage,
+                damage_type: damage_type.copied().unwrap_or_default(),
+                mastery_bonus: None,
+            });
+            attack.timer.reset();
         }
     }
 }
@@ -149,14 +463,14 @@ pub fn manage_monster_lifecycle(
             if damage_dealt > 0.0 {
                 let percentage = damage_dealt / health.max;
                 let penalty = modifier.map(|m| m.0).unwrap_or(0.0);
-                let amount = reward.0 * percentage * penalty;
+                let amount = reward.0.clone() * (percentage * penalty) as f64;
 
-                if amount > 0.0 {
+                if !amount.is_zero() {
+                    info!(%entity, "Monster scavenged for {}", amount);
                     scavenge_events.write(MonsterScavenged {
                         amount,
                         location: transform.translation,
                     });
-                    info!(%entity, "Monster scavenged for {}", amount);
                 }
             }
 
@@ -166,6 +480,37 @@ pub fn manage_monster_lifecycle(
     }
 }
 
+/// Rebuilds [`MonsterIndex`] from where the monsters stand at the start of the frame.
+pub fn index_monsters(
+    mut index: ResMut<MonsterIndex>,
+    monster_query: Query<(Entity, &Transform, Option<&Collider>, Option<&Sprite>), With<Monster>>,
+) {
+    index.clear();
+    for (entity, transform, collider, sprite) in monster_query.iter() {
+        index.insert(
+            entity,
+            transform.translation.truncate(),
+            Collider::radius_of(collider, sprite),
+        );
+    }
+}
+
+/// Leaves the actual death to [`manage_monster_lifecycle`], so killed monsters
+/// pay out like any other kill.
+pub fn kill_all_monsters(
+    mut events: MessageReader<KillAllMonsters>,
+    mut monster_query: Query<&mut Health, (With<Monster>, Without<MarkedForCleanUp>)>,
+) {
+    if events.read().count() == 0 {
+        return;
+    }
+
+    for mut health in monster_query.iter_mut() {
+        health.current = 0.0;
+    }
+    info!("Killed all monsters");
+}
+
 pub fn process_marked_cleanup(
     mut commands: Commands,
     time: Res<Time>,
@@ -206,5 +551,11 @@ pub fn update_lifetime_text(
     }
 }
 
+#[cfg(test)]
+mod tests_config;
+#[cfg(test)]
+mod tests_damage;
 #[cfg(test)]
 mod tests_lifecycle;
+#[cfg(test)]
+mod tests_movement;
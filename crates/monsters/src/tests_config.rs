@@ -0,0 +1,113 @@
+use {
+    crate::{
+        reload_monster_configs, AvailableEnemies, MonsterConfig, MonsterPrefab, MovementPattern,
+        ReturnToPortal,
+    },
+    bevy::prelude::*,
+};
+
+#[test]
+fn test_modified_monster_config_replaces_available_enemies() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()));
+    app.init_asset::<MonsterConfig>();
+    app.init_resource::<AvailableEnemies>();
+    app.add_systems(Update, reload_monster_configs);
+
+    let config = MonsterConfig {
+        prefab: "prefabs/monsters/goblin.scn.ron".to_string(),
+        spawn_weight: 1.0,
+        min_portal_level: 0,
+        health_coef: 1.0,
+        lifetime_coef: 1.0,
+        speed_coef: 1.0,
+        reward_coef: 1.0,
+        movement: MovementPattern::default(),
+        return_to_portal: None,
+    };
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<MonsterConfig>>()
+        .add(config.clone());
+    app.insert_resource(AvailableEnemies(vec![config]));
+    app.update();
+
+    app.world_mut()
+        .resource_mut::<Assets<MonsterConfig>>()
+        .get_mut(&handle)
+        .unwrap()
+        .spawn_weight = 3.0;
+    // Asset events are sent at the end of the frame
+    app.update();
+    app.update();
+
+    let available = app.world().resource::<AvailableEnemies>();
+    assert_eq!(available.0.len(), 1);
+    assert_eq!(available.0[0].spawn_weight, 3.0);
+}
+
+#[test]
+fn test_modified_monster_config_updates_its_live_monsters() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()));
+    app.init_asset::<MonsterConfig>();
+    app.init_resource::<AvailableEnemies>();
+    app.add_systems(Update, reload_monster_configs);
+
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<MonsterConfig>>()
+        .add(MonsterConfig {
+            prefab: "prefabs/monsters/goblin.scn.ron".to_string(),
+            spawn_weight: 1.0,
+            min_portal_level: 0,
+            health_coef: 1.0,
+            lifetime_coef: 1.0,
+            speed_coef: 1.0,
+            reward_coef: 1.0,
+            movement: MovementPattern::default(),
+            return_to_portal: Some(3.0),
+        });
+    let goblin = app
+        .world_mut()
+        .spawn((
+            MonsterPrefab("prefabs/monsters/goblin.scn.ron".to_string()),
+            MovementPattern::default(),
+            ReturnToPortal { seconds_left: 3.0 },
+        ))
+        .id();
+    let other = app
+        .world_mut()
+        .spawn((
+            MonsterPrefab("prefabs/monsters/elite_goblin.scn.ron".to_string()),
+            MovementPattern::default(),
+            ReturnToPortal { seconds_left: 3.0 },
+        ))
+        .id();
+    app.update();
+
+    {
+        let mut configs = app.world_mut().resource_mut::<Assets<MonsterConfig>>();
+        let config = configs.get_mut(&handle).unwrap();
+        config.movement = MovementPattern::Orbit { radius: 80.0 };
+        config.return_to_portal = None;
+    }
+    // Asset events are sent at the end of the frame
+    app.update();
+    app.update();
+
+    let world = app.world();
+    assert_eq!(
+        *world.get::<MovementPattern>(goblin).unwrap(),
+        MovementPattern::Orbit { radius: 80.0 }
+    );
+    assert!(world.get::<ReturnToPortal>(goblin).is_none());
+    assert_eq!(
+        *world.get::<MovementPattern>(other).unwrap(),
+        MovementPattern::Straight
+    );
+    assert_eq!(
+        *world.get::<ReturnToPortal>(other).unwrap(),
+        ReturnToPortal { seconds_left: 3.0 }
+    );
+}
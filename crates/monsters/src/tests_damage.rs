@@ -0,0 +1,196 @@
+use {
+    crate::{
+        apply_damage, mitigate_damage, monster_attack, Armor, Health, Hostile, Monster,
+        MonsterAttack,
+    },
+    bevy::{prelude::*, time::TimePlugin},
+    common::{CommonPlugin, DamageMessage, DamageStep, DamageType},
+};
+
+fn setup_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins).add_plugins(CommonPlugin);
+    app.add_systems(
+        Update,
+        (
+            mitigate_damage.in_set(DamageStep::Mitigation),
+            apply_damage.in_set(DamageStep::Apply),
+        ),
+    );
+    app
+}
+
+fn spawn_target(app: &mut App, armor: Armor) -> Entity {
+    app.world_mut()
+        .spawn((
+            Health {
+                current: 100.0,
+                max: 100.0,
+            },
+            armor,
+        ))
+        .id()
+}
+
+fn hit(target: Entity, amount: f32, damage_type: DamageType) -> DamageMessage {
+    DamageMessage {
+        source: Entity::PLACEHOLDER,
+        target,
+        weapon: None,
+        amount,
+        damage_type,
+        mastery_bonus: None,
+    }
+}
+
+#[test]
+fn test_armor_mitigation_per_damage_type() {
+    let armor = Armor {
+        physical: 100.0,
+        magic: 300.0,
+        void: 0.0,
+    };
+
+    assert_eq!(armor.mitigate(20.0, DamageType::Physical), 10.0);
+    assert_eq!(armor.mitigate(20.0, DamageType::Magic), 5.0);
+    assert_eq!(armor.mitigate(20.0, DamageType::Void), 20.0);
+
+    // Negative armor never amplifies damage
+    let broken = Armor {
+        physical: -50.0,
+        ..default()
+    };
+    assert_eq!(broken.mitigate(20.0, DamageType::Physical), 20.0);
+}
+
+#[test]
+fn test_damage_is_mitigated_then_applied() {
+    let mut app = setup_app();
+    let armored = spawn_target(
+        &mut app,
+        Armor {
+            physical: 100.0,
+            ..default()
+        },
+    );
+    let bare = app
+        .world_mut()
+        .spawn(Health {
+            current: 100.0,
+            max: 100.0,
+        })
+        .id();
+
+    app.world_mut()
+        .write_message(hit(armored, 20.0, DamageType::Physical));
+    app.world_mut()
+        .write_message(hit(bare, 20.0, DamageType::Physical));
+    app.update();
+
+    assert_eq!(app.world().get::<Health>(armored).unwrap().current, 90.0);
+    assert_eq!(app.world().get::<Health>(bare).unwrap().current, 80.0);
+
+    // Messages are only applied once
+    app.update();
+    assert_eq!(app.world().get::<Health>(armored).unwrap().current, 90.0);
+}
+
+#[test]
+fn test_modifiers_run_in_step_order() {
+    let mut app = setup_app();
+    let target = spawn_target(
+        &mut app,
+        Armor {
+            physical: 100.0,
+            ..default()
+        },
+    );
+
+    // Registered after the armor step but runs before it because of its set
+    app.add_systems(
+        Update,
+        (|mut messages: MessageMutator<DamageMessage>| {
+            for msg in messages.read() {
+                msg.amount += 20.0;
+            }
+        })
+        .in_set(DamageStep::Bonus),
+    );
+
+    app.world_mut()
+        .write_message(hit(target, 20.0, DamageType::Physical));
+    app.update();
+
+    // (20 + 20) halved by armor, not 20 halved + 20
+    assert_eq!(app.world().get::<Health>(target).unwrap().current, 80.0);
+}
+
+#[test]
+fn test_monster_attacks_closest_hostile_in_range() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins.build().disable::<TimePlugin>())
+        .add_plugins(CommonPlugin);
+    app.insert_resource(Time::<()>::default());
+    app.add_systems(
+        Update,
+        (
+            monster_attack,
+            mitigate_damage.in_set(DamageStep::Mitigation),
+            apply_damage.in_set(DamageStep::Apply),
+        )
+            .chain(),
+    );
+
+    app.world_mut().spawn((
+        Monster::default(),
+        Transform::default(),
+        MonsterAttack {
+            damage: 20.0,
+            range: 50.0,
+            timer: Timer::from_seconds(1.0, TimerMode::Once),
+        },
+    ));
+
+    let mut spawn_hostile = |x: f32| {
+        app.world_mut()
+            .spawn((
+                Hostile,
+                Transform::from_xyz(x, 0.0, 0.0),
+                Health {
+                    current: 100.0,
+                    max: 100.0,
+                },
+                Armor {
+                    physical: 100.0,
+                    ..default()
+                },
+            ))
+            .id()
+    };
+    let near = spawn_hostile(30.0);
+    let far = spawn_hostile(45.0);
+    let out_of_range = spawn_hostile(80.0);
+
+    let health = |app: &App, e: Entity| app.world().get::<Health>(e).unwrap().current;
+
+    // Still on cooldown
+    app.update();
+    assert_eq!(health(&app, near), 100.0);
+
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(std::time::Duration::from_secs_f32(1.1));
+    app.update();
+
+    // Halved by armor
+    assert_eq!(health(&app, near), 90.0);
+    assert_eq!(health(&app, far), 100.0);
+    assert_eq!(health(&app, out_of_range), 100.0);
+
+    // Cooldown restarted
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(std::time::Duration::from_secs_f32(0.5));
+    app.update();
+    assert_eq!(health(&app, near), 90.0);
+}
@@ -1,7 +1,13 @@
 use {
-    crate::{manage_monster_lifecycle, process_marked_cleanup, Health, Lifetime, Monster},
+    crate::{
+        index_monsters, kill_all_monsters, manage_monster_lifecycle, process_marked_cleanup,
+        Health, Lifetime, Monster,
+    },
     bevy::{prelude::*, time::TimePlugin},
-    common::{MarkedForCleanUp, MonsterKilled, MonsterScavenged, Reward},
+    common::{
+        Collider, KillAllMonsters, MarkedForCleanUp, MonsterIndex, MonsterKilled,
+        MonsterScavenged, Reward,
+    },
 };
 
 #[test]
@@ -48,7 +54,7 @@ fn test_monster_death_lifecycle() {
             },
             Lifetime::default(),
             Transform::default(),
-            Reward(reward_amount),
+            Reward(reward_amount.into()),
             // Ensure Visibility is present to test Hidden
             Visibility::Visible,
             InheritedVisibility::default(),
@@ -122,3 +128,99 @@ fn test_monster_death_lifecycle() {
         "Entity should be despawned after 1.1s"
     );
 }
+
+#[test]
+fn test_kill_all_monsters_goes_through_death() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins.build().disable::<TimePlugin>());
+    app.insert_resource(Time::<()>::default());
+    app.add_message::<KillAllMonsters>();
+    app.add_message::<MonsterKilled>();
+    app.add_message::<MonsterScavenged>();
+    app.add_systems(Update, (kill_all_monsters, manage_monster_lifecycle).chain());
+
+    let monsters: Vec<Entity> = (0..3)
+        .map(|_| {
+            app.world_mut()
+                .spawn((
+                    Monster {
+                        target_position: Vec2::ZERO,
+                    },
+                    Health {
+                        current: 50.0,
+                        max: 50.0,
+                    },
+                    Lifetime {
+                        timer: Timer::from_seconds(60.0, TimerMode::Once),
+                    },
+                    Transform::default(),
+                    Reward(10.0.into()),
+                ))
+                .id()
+        })
+        .collect();
+
+    app.update();
+    for &monster in &monsters {
+        assert!(app.world().get::<Monster>(monster).is_some());
+    }
+
+    app.world_mut().write_message(KillAllMonsters);
+    app.update();
+
+    let killed: Vec<Entity> = app
+        .world_mut()
+        .resource_mut::<Messages<MonsterKilled>>()
+        .drain()
+        .map(|event| event.entity)
+        .collect();
+    assert_eq!(killed.len(), monsters.len());
+    for &monster in &monsters {
+        assert!(app.world().get::<MarkedForCleanUp>(monster).is_some());
+    }
+}
+
+#[test]
+fn test_index_monsters_follows_monsters() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<MonsterIndex>();
+    app.add_systems(Update, index_monsters);
+
+    let sized = app
+        .world_mut()
+        .spawn((
+            Monster::default(),
+            Transform::from_xyz(100.0, 0.0, 0.0),
+            Sprite {
+                custom_size: Some(Vec2::splat(32.0)),
+                ..default()
+            },
+        ))
+        .id();
+    let small = app
+        .world_mut()
+        .spawn((
+            Monster::default(),
+            Transform::from_xyz(-50.0, 0.0, 0.0),
+            Collider { radius: 2.0 },
+        ))
+        .id();
+    app.world_mut().spawn(Transform::default());
+
+    app.update();
+
+    let index = app.world().resource::<MonsterIndex>();
+    assert_eq!(index.len(), 2);
+    let hits: Vec<_> = index.query_radius(Vec2::new(80.0, 0.0), 5.0).collect();
+    assert_eq!(hits.len(), 1);
+    assert_eq!((hits[0].entity, hits[0].radius), (sized, 16.0));
+
+    // Rebuilt every frame, despawned monsters drop out
+    app.world_mut().despawn(sized);
+    app.update();
+    let index = app.world().resource::<MonsterIndex>();
+    let nearest = index.nearest(Vec2::new(80.0, 0.0), 5);
+    assert_eq!(nearest.len(), 1);
+    assert_eq!(nearest[0].entity, small);
+}
@@ -0,0 +1,225 @@
+use {
+    crate::{
+        move_monsters, Hostile, Lifetime, Monster, MovementPattern, ReturnToPortal, SpawnedBy,
+        Speed,
+    },
+    bevy::{prelude::*, time::TimePlugin},
+    std::{f32::consts::FRAC_PI_4, time::Duration},
+};
+
+fn setup_app(portal_position: Vec2) -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins.build().disable::<TimePlugin>());
+    app.insert_resource(Time::<()>::default());
+    app.add_systems(Update, move_monsters);
+    let portal = app
+        .world_mut()
+        .spawn(Transform::from_translation(portal_position.extend(0.0)))
+        .id();
+    (app, portal)
+}
+
+/// Monster at `position` walking at 100 units per second, with a minute to live.
+fn spawn_monster(
+    app: &mut App,
+    portal: Entity,
+    position: Vec2,
+    target: Vec2,
+    pattern: MovementPattern,
+) -> Entity {
+    app.world_mut()
+        .spawn((
+            Monster {
+                target_position: target,
+            },
+            pattern,
+            Speed(100.0),
+            Lifetime {
+                timer: Timer::from_seconds(60.0, TimerMode::Once),
+            },
+            SpawnedBy(portal),
+            Transform::from_translation(position.extend(0.0)),
+        ))
+        .id()
+}
+
+fn advance(app: &mut App, secs: f32) {
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_secs_f32(secs));
+    app.update();
+}
+
+fn position(app: &App, entity: Entity) -> Vec2 {
+    app.world()
+        .get::<Transform>(entity)
+        .unwrap()
+        .translation
+        .truncate()
+}
+
+fn assert_near(actual: Vec2, expected: Vec2) {
+    assert!(
+        actual.distance(expected) < 1e-3,
+        "expected {expected}, got {actual}"
+    );
+}
+
+fn age(app: &mut App, monster: Entity, secs: f32) {
+    app.world_mut()
+        .get_mut::<Lifetime>(monster)
+        .unwrap()
+        .timer
+        .tick(Duration::from_secs_f32(secs));
+}
+
+#[test]
+fn test_straight_stops_on_target() {
+    let (mut app, portal) = setup_app(Vec2::ZERO);
+    let target = Vec2::new(50.0, 0.0);
+    let monster = spawn_monster(&mut app, portal, Vec2::ZERO, target, MovementPattern::Straight);
+
+    // A full second of walking would carry it 100 units, it stops on the target
+    advance(&mut app, 1.0);
+    assert_near(position(&app, monster), target);
+
+    advance(&mut app, 1.0);
+    assert_near(position(&app, monster), target);
+}
+
+#[test]
+fn test_wander_picks_new_point_on_arrival() {
+    let portal_position = Vec2::new(200.0, 0.0);
+    let (mut app, portal) = setup_app(portal_position);
+    let pattern = MovementPattern::Wander { radius: 50.0 };
+    let monster = spawn_monster(&mut app, portal, portal_position, portal_position, pattern);
+    let target = |app: &App| app.world().get::<Monster>(monster).unwrap().target_position;
+
+    // Already on its target
+    advance(&mut app, 0.0);
+    let first = target(&app);
+    assert!(first.distance(portal_position) <= 50.0);
+
+    // At most 50 units from the portal, one second is enough to get there
+    advance(&mut app, 1.0);
+    assert_near(position(&app, monster), first);
+
+    advance(&mut app, 0.0);
+    let second = target(&app);
+    assert_ne!(second, first);
+    assert!(second.distance(portal_position) <= 50.0);
+}
+
+#[test]
+fn test_zig_zag_switches_sides() {
+    let (mut app, portal) = setup_app(Vec2::ZERO);
+    let pattern = MovementPattern::ZigZag {
+        angle: FRAC_PI_4,
+        period: 2.0,
+    };
+    let target = Vec2::new(1000.0, 0.0);
+    let monster = spawn_monster(&mut app, portal, Vec2::ZERO, target, pattern);
+
+    // First half of the period, off to the left of the line
+    advance(&mut app, 0.5);
+    let left = Vec2::from_angle(FRAC_PI_4) * 50.0;
+    assert_near(position(&app, monster), left);
+
+    // Second half, back to the right
+    age(&mut app, monster, 1.0);
+    advance(&mut app, 0.5);
+    let heading = (target - left).normalize();
+    assert_near(
+        position(&app, monster),
+        left + Vec2::from_angle(-FRAC_PI_4).rotate(heading) * 50.0,
+    );
+    assert!(position(&app, monster).y < left.y);
+
+    // Within one weave of the target, it goes straight
+    app.world_mut()
+        .get_mut::<Transform>(monster)
+        .unwrap()
+        .translation = Vec3::new(900.0, 0.0, 0.0);
+    advance(&mut app, 0.5);
+    assert_near(position(&app, monster), Vec2::new(950.0, 0.0));
+}
+
+#[test]
+fn test_orbit_circles_portal() {
+    let portal_position = Vec2::new(-100.0, 50.0);
+    let (mut app, portal) = setup_app(portal_position);
+    let pattern = MovementPattern::Orbit { radius: 100.0 };
+    let start = portal_position + Vec2::new(100.0, 0.0);
+    let monster = spawn_monster(&mut app, portal, start, Vec2::ZERO, pattern);
+
+    // Counterclockwise along the circle
+    advance(&mut app, 0.1);
+    assert_near(position(&app, monster), start + Vec2::new(0.0, 10.0));
+
+    // A quarter turn later, still on the circle
+    for _ in 0..31 {
+        advance(&mut app, 0.05);
+    }
+    let offset = position(&app, monster) - portal_position;
+    assert!((offset.length() - 100.0).abs() < 2.0, "{offset}");
+    assert!(offset.to_angle() > 1.4, "{offset}");
+
+    // Pulled back onto the circle from outside
+    let outside = spawn_monster(
+        &mut app,
+        portal,
+        portal_position + Vec2::new(0.0, 300.0),
+        Vec2::ZERO,
+        pattern,
+    );
+    advance(&mut app, 0.5);
+    assert!(position(&app, outside).distance(portal_position) < 270.0);
+}
+
+#[test]
+fn test_flee_runs_from_nearby_hostiles() {
+    let (mut app, portal) = setup_app(Vec2::ZERO);
+    let pattern = MovementPattern::Flee { radius: 100.0 };
+    let target = Vec2::new(0.0, -500.0);
+    let monster = spawn_monster(&mut app, portal, Vec2::ZERO, target, pattern);
+    let hostile = app
+        .world_mut()
+        .spawn((Hostile, Transform::from_xyz(50.0, 0.0, 0.0)))
+        .id();
+
+    advance(&mut app, 0.1);
+    assert_near(position(&app, monster), Vec2::new(-10.0, 0.0));
+
+    // Out of range, back on its way
+    app.world_mut()
+        .get_mut::<Transform>(hostile)
+        .unwrap()
+        .translation = Vec3::new(500.0, 0.0, 0.0);
+    advance(&mut app, 0.1);
+    let expected = Vec2::new(-10.0, 0.0) + (target - Vec2::new(-10.0, 0.0)).normalize() * 10.0;
+    assert_near(position(&app, monster), expected);
+}
+
+#[test]
+fn test_returns_to_portal_when_lifetime_runs_out() {
+    let portal_position = Vec2::new(0.0, 100.0);
+    let (mut app, portal) = setup_app(portal_position);
+    let target = Vec2::new(500.0, 0.0);
+    let monster = spawn_monster(&mut app, portal, Vec2::ZERO, target, MovementPattern::Straight);
+    app.world_mut()
+        .entity_mut(monster)
+        .insert(ReturnToPortal { seconds_left: 3.0 });
+
+    age(&mut app, monster, 50.0);
+    advance(&mut app, 0.1);
+    assert_near(position(&app, monster), Vec2::new(10.0, 0.0));
+
+    // Two seconds left, time to head home
+    age(&mut app, monster, 8.0);
+    advance(&mut app, 0.1);
+    let start = Vec2::new(10.0, 0.0);
+    assert_near(
+        position(&app, monster),
+        start + (portal_position - start).normalize() * 10.0,
+    );
+}
@@ -0,0 +1,186 @@
+//! Headless benchmark of the monster queries against a linear scan.
+//!
+//! `cargo run --release -p player_npcs --example monster_index_benchmark -- 5000`
+//!
+//! Spawns the given number of monsters (5000 by default) spread over a large
+//! field, a squad of NPCs retargeting every frame and a cloud of projectiles,
+//! then times `index_monsters`, `player_npc_decision_logic` and
+//! `projectile_collision` against the same queries done with nested loops.
+
+use {
+    bevy::prelude::*,
+    common::{DamageType, MonsterIndex, ProjectileCollisionMessage, WeaponKind},
+    items::Pierce,
+    monsters::{index_monsters, Health, Monster, SpawnIndex, SpawnedBy},
+    player_npcs::{
+        player_npc_decision_logic, projectile_collision, Intent, PlayerNpc, Projectile, Target,
+        TargetClaims, TargetingPolicy,
+    },
+    portal::PortalSpawnTracker,
+    std::time::{Duration, Instant},
+};
+
+const FIELD: f32 = 4000.0;
+const NPCS: usize = 50;
+const PROJECTILES: usize = 500;
+const FRAMES: u32 = 100;
+
+/// Small deterministic generator, runs stay comparable.
+struct Lcg(u64);
+
+impl Lcg {
+    /// Uniform in `[0, 1)`.
+    fn unit(&mut self) -> f32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn point(&mut self) -> Vec3 {
+        Vec3::new((self.unit() - 0.5) * FIELD, (self.unit() - 0.5) * FIELD, 0.0)
+    }
+}
+
+fn retarget_every_frame(mut policy_query: Query<&mut TargetingPolicy>) {
+    for mut policy in policy_query.iter_mut() {
+        policy.set_changed();
+    }
+}
+
+fn setup(monsters: u32) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_message::<ProjectileCollisionMessage>()
+        .init_resource::<MonsterIndex>()
+        .init_resource::<TargetClaims>();
+    app.add_systems(
+        Update,
+        (
+            index_monsters,
+            retarget_every_frame,
+            player_npc_decision_logic,
+            projectile_collision,
+        )
+            .chain(),
+    );
+
+    let mut rng = Lcg(42);
+    let portal = app.world_mut().spawn(PortalSpawnTracker(monsters)).id();
+    for index in 0..monsters {
+        app.world_mut().spawn((
+            Monster::default(),
+            Health {
+                current: 10.0,
+                max: 10.0,
+            },
+            SpawnIndex(index),
+            SpawnedBy(portal),
+            Transform::from_translation(rng.point()),
+            Sprite {
+                custom_size: Some(Vec2::splat(32.0)),
+                ..default()
+            },
+        ));
+    }
+    for _ in 0..NPCS {
+        app.world_mut().spawn((
+            PlayerNpc,
+            TargetingPolicy::Nearest,
+            Target(None),
+            Intent::Idle,
+            Transform::from_translation(rng.point()),
+        ));
+    }
+    for _ in 0..PROJECTILES {
+        let source = app.world_mut().spawn_empty().id();
+        app.world_mut().spawn((
+            Projectile {
+                velocity: Vec3::ZERO,
+                lifetime: Timer::from_seconds(3600.0, TimerMode::Once),
+                source,
+                weapon: source,
+                target: source,
+                hits: Vec::new(),
+                damage: 10.0,
+                damage_type: DamageType::Physical,
+                splash: None,
+                kind: WeaponKind::Ranged,
+                mastery_bonus: 1.0,
+            },
+            // Never spent, so every frame does the same amount of work
+            Pierce(u32::MAX),
+            Transform::from_translation(rng.point()),
+            Sprite {
+                custom_size: Some(Vec2::splat(8.0)),
+                ..default()
+            },
+        ));
+    }
+    app
+}
+
+/// The same nearest monster and hit circle lookups, scanning every monster.
+fn linear_scan(world: &mut World) -> usize {
+    let monsters: Vec<Vec3> = world
+        .query_filtered::<&Transform, With<Monster>>()
+        .iter(world)
+        .map(|transform| transform.translation)
+        .collect();
+    let mut found = 0;
+
+    let mut npc_query = world.query_filtered::<&Transform, With<PlayerNpc>>();
+    for npc in npc_query.iter(world) {
+        let nearest = monsters.iter().min_by(|a, b| {
+            a.distance(npc.translation)
+                .total_cmp(&b.distance(npc.translation))
+        });
+        found += usize::from(nearest.is_some());
+    }
+
+    let mut projectile_query = world.query_filtered::<&Transform, With<Projectile>>();
+    for projectile in projectile_query.iter(world) {
+        found += monsters
+            .iter()
+            .filter(|monster| monster.distance(projectile.translation) < 20.0)
+            .count();
+    }
+    found
+}
+
+fn main() {
+    let monsters = std::env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(5000);
+    let mut app = setup(monsters);
+    // Warm up: first targets, allocations of the index
+    app.update();
+
+    let mut indexed = Duration::ZERO;
+    for _ in 0..FRAMES {
+        let start = Instant::now();
+        app.update();
+        indexed += start.elapsed();
+    }
+
+    let mut linear = Duration::ZERO;
+    let mut found = 0;
+    for _ in 0..FRAMES {
+        let start = Instant::now();
+        found += linear_scan(app.world_mut());
+        linear += start.elapsed();
+    }
+
+    println!("{monsters} monsters, {NPCS} npcs retargeting, {PROJECTILES} projectiles");
+    println!(
+        "monster index: {:>8.3} ms/frame (rebuild, targeting and collision)",
+        indexed.as_secs_f64() * 1000.0 / f64::from(FRAMES)
+    );
+    println!(
+        "linear scan:   {:>8.3} ms/frame (same lookups, {} matches)",
+        linear.as_secs_f64() * 1000.0 / f64::from(FRAMES),
+        found / FRAMES as usize
+    );
+}
@@ -0,0 +1,122 @@
+use {
+    crate::{MovementSpeed, PlayerNpc, Weapon, WeaponCooldown},
+    bevy::prelude::*,
+    items::{
+        AttackRange as ItemAttackRange, AttackSpeed, BaseDamage,
+        ProjectileStats as ItemProjectileStats, Ranged,
+    },
+    serde::Deserialize,
+    std::time::Duration,
+};
+
+pub const PLAYER_NPC_CONFIG_PATH: &str = "configs/player_npcs/main.soldier.ron";
+
+// Components
+
+/// Marks the weapon a player NPC prefab ships with, the only weapon
+/// [`PlayerNpcConfig`] tunes. Equipped items keep their own stats.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy)]
+#[reflect(Component, Default)]
+pub struct DefaultWeapon;
+
+// Configs
+
+/// Balance of the player NPC, applied on top of its prefab when it spawns
+/// and again whenever the file changes.
+#[derive(Deserialize, Asset, Clone, Debug, Reflect)]
+pub struct PlayerNpcConfig {
+    /// Seconds between attacks of weapons without an `AttackSpeed`.
+    pub attack_timer: f32,
+    /// Used by ranged weapons only.
+    pub projectile_speed: f32,
+    /// Used by ranged weapons only.
+    pub projectile_damage: f32,
+    /// Used by ranged weapons only.
+    pub projectile_lifetime: f32,
+    pub attack_range: f32,
+    pub move_speed: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct PlayerNpcConfigHandle(pub Handle<PlayerNpcConfig>);
+
+// Systems
+
+pub fn load_player_npc_config(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(PlayerNpcConfigHandle(
+        asset_server.load(PLAYER_NPC_CONFIG_PATH),
+    ));
+}
+
+/// Applies the config to newly spawned player NPCs, or to all of them once the
+/// config is (re)loaded. Weapons only get it if they are a [`DefaultWeapon`].
+pub fn apply_player_npc_config(
+    mut commands: Commands,
+    mut config_events: MessageReader<AssetEvent<PlayerNpcConfig>>,
+    config_handle: Option<Res<PlayerNpcConfigHandle>>,
+    configs: Res<Assets<PlayerNpcConfig>>,
+    npc_query: Query<(Entity, Ref<PlayerNpc>, Option<&Children>)>,
+    mut weapon_query: Query<
+        (
+            Option<&mut WeaponCooldown>,
+            Option<&mut ItemAttackRange>,
+            Option<&mut ItemProjectileStats>,
+            Option<&mut BaseDamage>,
+            Has<AttackSpeed>,
+            Has<Ranged>,
+        ),
+        (With<Weapon>, With<DefaultWeapon>),
+    >,
+) {
+    let Some(config_handle) = config_handle else {
+        return;
+    };
+    let reloaded = config_events.read().fold(false, |reloaded, event| {
+        reloaded
+            || event.is_loaded_with_dependencies(&config_handle.0)
+            || event.is_modified(&config_handle.0)
+    });
+    let Some(config) = configs.get(&config_handle.0) else {
+        return;
+    };
+
+    for (npc, player_npc, children) in npc_query.iter() {
+        if !reloaded && !player_npc.is_added() {
+            continue;
+        }
+
+        commands
+            .entity(npc)
+            .insert(MovementSpeed(config.move_speed));
+
+        for child in children.into_iter().flat_map(|children| children.iter()) {
+            let Ok((cooldown, range, projectile, damage, has_attack_speed, is_ranged)) =
+                weapon_query.get_mut(child)
+            else {
+                continue;
+            };
+
+            if let Some(mut range) = range {
+                range.0 = config.attack_range;
+            }
+            // `AttackSpeed` keeps driving the cooldown of weapons that have one
+            if let (Some(mut cooldown), false) = (cooldown, has_attack_speed) {
+                cooldown
+                    .timer
+                    .set_duration(Duration::from_secs_f32(config.attack_timer));
+            }
+
+            if is_ranged {
+                if let Some(mut projectile) = projectile {
+                    projectile.speed = config.projectile_speed;
+                    projectile.lifetime = config.projectile_lifetime;
+                }
+                if let Some(mut damage) = damage {
+                    damage.0 = config.projectile_damage;
+                }
+            }
+        }
+
+        debug!(%npc, "Player NPC config applied");
+    }
+}
@@ -0,0 +1,201 @@
+use {
+    crate::{PlayerNpc, Weapon, WeaponCooldown},
+    bevy::{
+        ecs::relationship::Relationship,
+        prelude::*,
+        scene::{DynamicScene, InstanceId},
+    },
+    common::{EquipItem, EquipmentSlot, UnequipItem},
+    items::{Armor as ItemArmor, AttackSpeed, Melee, Ranged},
+    monsters::Armor,
+    std::collections::HashMap,
+};
+
+/// Cooldown used for weapons that do not define an `AttackSpeed`.
+const DEFAULT_COOLDOWN_SECS: f32 = 1.0;
+
+// Components
+
+/// Items currently worn by a player NPC, one per slot.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component, Default)]
+pub struct Equipment {
+    pub main_hand: Option<Entity>,
+    pub off_hand: Option<Entity>,
+    pub armor: Option<Entity>,
+}
+
+impl Equipment {
+    pub fn get(&self, slot: EquipmentSlot) -> Option<Entity> {
+        *self.slot(slot)
+    }
+
+    fn slot(&self, slot: EquipmentSlot) -> &Option<Entity> {
+        match slot {
+            EquipmentSlot::MainHand => &self.main_hand,
+            EquipmentSlot::OffHand => &self.off_hand,
+            EquipmentSlot::Armor => &self.armor,
+        }
+    }
+
+    fn slot_mut(&mut self, slot: EquipmentSlot) -> &mut Option<Entity> {
+        match slot {
+            EquipmentSlot::MainHand => &mut self.main_hand,
+            EquipmentSlot::OffHand => &mut self.off_hand,
+            EquipmentSlot::Armor => &mut self.armor,
+        }
+    }
+}
+
+/// Combined stats of the armor items an NPC wears.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component, Default)]
+pub struct EquipmentStats {
+    pub defense: f32,
+    /// Sum of the items' modifiers, `-0.1` is 10% slower.
+    pub movement_speed_modifier: f32,
+}
+
+impl EquipmentStats {
+    pub fn effective_speed(&self, base_speed: f32) -> f32 {
+        base_speed * (1.0 + self.movement_speed_modifier).max(0.0)
+    }
+}
+
+/// Marks an item entity as worn in a slot of its parent `PlayerNpc`.
+/// Prefabs can set it on items they ship with.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct EquippedIn(pub EquipmentSlot);
+
+/// Item scenes still being spawned, with where they should go.
+#[derive(Resource, Default)]
+pub struct PendingEquips(HashMap<InstanceId, (Entity, EquipmentSlot)>);
+
+// Systems
+
+pub fn unequip_items(
+    mut commands: Commands,
+    mut messages: MessageReader<UnequipItem>,
+    mut npc_query: Query<&mut Equipment, With<PlayerNpc>>,
+) {
+    for msg in messages.read() {
+        let Ok(mut equipment) = npc_query.get_mut(msg.npc) else {
+            warn!(npc = %msg.npc, "Cannot unequip, entity is not a player NPC");
+            continue;
+        };
+
+        if let Some(item) = equipment.slot_mut(msg.slot).take() {
+            commands.entity(item).despawn();
+            info!(npc = %msg.npc, slot = ?msg.slot, %item, "Item unequipped");
+        }
+    }
+}
+
+/// Starts spawning the requested item scenes. The previous item stays
+/// equipped until the new one is ready.
+pub fn equip_items(
+    mut messages: MessageReader<EquipItem>,
+    mut scene_spawner: ResMut<SceneSpawner>,
+    asset_server: Res<AssetServer>,
+    mut pending_equips: ResMut<PendingEquips>,
+) {
+    for msg in messages.read() {
+        let scene_handle = asset_server.load::<DynamicScene>(&msg.prefab);
+        let instance_id = scene_spawner.spawn_dynamic(scene_handle);
+        pending_equips.0.insert(instance_id, (msg.npc, msg.slot));
+    }
+}
+
+/// Parents spawned item scenes to their NPC and adds the runtime weapon components.
+pub fn attach_equipped_items(
+    mut commands: Commands,
+    scene_spawner: Res<SceneSpawner>,
+    mut pending_equips: ResMut<PendingEquips>,
+    npc_query: Query<(), With<PlayerNpc>>,
+    item_query: Query<(Option<&AttackSpeed>, Has<Melee>, Has<Ranged>)>,
+) {
+    pending_equips.0.retain(|instance_id, (npc, slot)| {
+        if !scene_spawner.instance_is_ready(*instance_id) {
+            return true;
+        }
+
+        // Item prefabs have a single root entity
+        let Some(item) = scene_spawner.iter_instance_entities(*instance_id).next() else {
+            return false;
+        };
+
+        if npc_query.get(*npc).is_err() {
+            warn!(%npc, "Player NPC gone before its item was ready");
+            commands.entity(item).despawn();
+            return false;
+        }
+
+        let mut item_cmds = commands.entity(item);
+        item_cmds.insert((ChildOf(*npc), EquippedIn(*slot), Visibility::Inherited));
+
+        if let Ok((attack_speed, is_melee, is_ranged)) = item_query.get(item) {
+            if is_melee || is_ranged {
+                let secs = attack_speed
+                    .and_then(AttackSpeed::cooldown_secs)
+                    .unwrap_or(DEFAULT_COOLDOWN_SECS);
+                item_cmds.insert((
+                    Weapon,
+                    WeaponCooldown {
+                        timer: Timer::from_seconds(secs, TimerMode::Once),
+                    },
+                ));
+            }
+        }
+
+        false
+    });
+}
+
+/// Records newly equipped items on their NPC, despawning the item they replace.
+pub fn register_equipped_items(
+    mut commands: Commands,
+    item_query: Query<(Entity, &EquippedIn, &ChildOf), Added<EquippedIn>>,
+    mut npc_query: Query<&mut Equipment, With<PlayerNpc>>,
+) {
+    for (item, equipped_in, parent) in item_query.iter() {
+        let Ok(mut equipment) = npc_query.get_mut(parent.get()) else {
+            continue;
+        };
+
+        let slot = equipment.slot_mut(equipped_in.0);
+        if let Some(previous) = slot.replace(item) {
+            if previous != item {
+                commands.entity(previous).despawn();
+            }
+        }
+        info!(npc = %parent.get(), slot = ?equipped_in.0, %item, "Item equipped");
+    }
+}
+
+/// Recomputes armor totals whenever the equipment changes. Defense protects
+/// against physical damage through the same `Armor` monsters use.
+pub fn update_equipment_stats(
+    mut commands: Commands,
+    mut npc_query: Query<(Entity, &Equipment, &mut EquipmentStats), Changed<Equipment>>,
+    armor_query: Query<&ItemArmor>,
+) {
+    for (npc, equipment, mut stats) in npc_query.iter_mut() {
+        let mut totals = EquipmentStats::default();
+        for item in [equipment.main_hand, equipment.off_hand, equipment.armor]
+            .into_iter()
+            .flatten()
+        {
+            if let Ok(armor) = armor_query.get(item) {
+                totals.defense += armor.defense;
+                totals.movement_speed_modifier += armor.movement_speed_modifier;
+            }
+        }
+
+        *stats = totals;
+        commands.entity(npc).insert(Armor {
+            physical: totals.defense,
+            ..default()
+        });
+    }
+}
@@ -1,62 +1,122 @@
 #![allow(clippy::type_complexity)]
 
 use {
-    bevy::{ecs::relationship::Relationship, prelude::*, scene::DynamicScene},
+    bevy::{ecs::relationship::Relationship, prelude::*},
+    bevy_common_assets::ron::RonAssetPlugin,
     common::{
-        DamageMessage, GameState, MarkedForCleanUp, MeleeDamageContext, MeleeHitMessage,
-        ProjectileCollisionMessage, ProjectileDamageContext, VoidGameStage,
+        Collider, Currency, DamageMessage, DamageStep, DamageType, GameState, MarkedForCleanUp,
+        MeleeHitMessage, MonsterIndex, ProjectileCollisionMessage, Reward, VoidGameStage,
+        WeaponKind, WeaponMasteryLeveledUp,
     },
     items::{
-        AttackRange as ItemAttackRange, BaseDamage, Melee, ProjectileStats as ItemProjectileStats,
-        Ranged,
+        AreaOfEffect, AttackRange as ItemAttackRange, AttackSpeed, BaseDamage, Chain, Homing,
+        Melee, Pierce, ProjectileStats as ItemProjectileStats, Ranged,
     },
-    monsters::{Monster, SpawnIndex},
+    monsters::{Health, Hostile, Lifetime, Monster, SpawnIndex, SpawnedBy},
     portal::PortalSpawnTracker,
     std::time::Duration,
 };
 
+mod config;
+mod equipment;
+mod respawn;
+mod targeting;
+
+pub use {config::*, equipment::*, respawn::*, targeting::*};
+
 pub struct PlayerNpcsPlugin;
 
 impl Plugin for PlayerNpcsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_message::<DamageMessage>();
+        app.add_plugins(RonAssetPlugin::<PlayerNpcConfig>::new(&["soldier.ron"]));
+
         app.register_type::<PlayerNpc>()
+            .register_type::<PlayerNpcId>()
+            .register_type::<PlayerNpcPrefab>()
+            .register_type::<DefaultWeapon>()
             .register_type::<MovementSpeed>()
             .register_type::<Target>()
+            .register_type::<TargetingPolicy>()
             .register_type::<Weapon>()
             .register_type::<WeaponCooldown>()
             .register_type::<Projectile>()
             .register_type::<MasteryTrack>()
             .register_type::<WeaponExpertise>()
             .register_type::<WeaponExpertiseXp>()
-            .register_type::<CooldownText>();
-
+            .register_type::<CooldownText>()
+            .register_type::<Equipment>()
+            .register_type::<EquipmentStats>()
+            .register_type::<EquippedIn>();
+
+        app.init_resource::<PendingEquips>()
+            .init_resource::<PlayerNpcRespawn>()
+            .init_resource::<PendingPlayerNpcSpawns>()
+            .init_resource::<NextPlayerNpcId>()
+            .init_resource::<TargetClaims>();
+
+        app.add_systems(Startup, load_player_npc_config);
         app.add_systems(OnEnter(GameState::Playing), spawn_player_npc);
 
         app.add_systems(
             Update,
             (
-                tick_weapon_cooldowns.in_set(VoidGameStage::FrameStart),
+                (
+                    (sync_attack_speed_cooldown, tick_weapon_cooldowns).chain(),
+                    (respawn_player_npcs, attach_spawned_player_npcs).chain(),
+                    apply_player_npc_config,
+                )
+                    .in_set(VoidGameStage::FrameStart),
                 player_npc_decision_logic.in_set(VoidGameStage::ResolveIntent),
                 (
                     player_npc_movement_logic,
                     melee_attack_emit,
                     ranged_attack_logic,
                     (move_projectiles, projectile_collision).chain(),
+                    (unequip_items, equip_items),
                 )
                     .in_set(VoidGameStage::Actions),
                 (
-                    resolve_melee_base_damage.pipe(apply_melee_damage),
-                    resolve_projectile_base_damage.pipe(apply_projectile_damage),
+                    attach_equipped_items,
+                    register_equipped_items,
+                    update_equipment_stats,
                 )
+                    .chain()
                     .in_set(VoidGameStage::Effect),
-                update_cooldown_text.in_set(VoidGameStage::FrameEnd),
+                (resolve_melee_damage, resolve_projectile_damage).in_set(DamageStep::Resolve),
+                apply_mastery_bonus.in_set(DamageStep::Bonus),
+                (update_cooldown_text, player_npc_death).in_set(VoidGameStage::FrameEnd),
             )
                 .run_if(in_state(GameState::Playing)),
         );
     }
 }
 
+/// Keeps the cooldown of weapons with an `AttackSpeed` at `1 / AttackSpeed`.
+/// Progress already made towards the next attack is kept.
+pub fn sync_attack_speed_cooldown(
+    mut commands: Commands,
+    mut weapon_query: Query<
+        (Entity, &AttackSpeed, Option<&mut WeaponCooldown>),
+        (With<Weapon>, Changed<AttackSpeed>),
+    >,
+) {
+    for (entity, attack_speed, cooldown) in weapon_query.iter_mut() {
+        let Some(secs) = attack_speed.cooldown_secs() else {
+            warn!(%entity, "Weapon has non-positive attack speed {}", attack_speed.0);
+            continue;
+        };
+
+        match cooldown {
+            Some(mut cooldown) => cooldown.timer.set_duration(Duration::from_secs_f32(secs)),
+            None => {
+                commands.entity(entity).insert(WeaponCooldown {
+                    timer: Timer::from_seconds(secs, TimerMode::Once),
+                });
+            }
+        }
+    }
+}
+
 pub fn tick_weapon_cooldowns(time: Res<Time>, mut weapon_query: Query<&mut WeaponCooldown>) {
     for mut cooldown in weapon_query.iter_mut() {
         cooldown.timer.tick(time.delta());
@@ -67,6 +127,7 @@ pub fn tick_weapon_cooldowns(time: Res<Time>, mut weapon_query: Query<&mut Weapo
 
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
+#[require(Equipment, EquipmentStats, Hostile, PlayerNpcId, TargetingPolicy)]
 pub struct PlayerNpc;
 
 #[derive(Component, Reflect, Default)]
@@ -113,6 +174,18 @@ pub struct Projectile {
     pub lifetime: Timer,
     pub source: Entity,
     pub weapon: Entity,
+    /// Monster it was fired at, its damage is claimed in [`TargetClaims`] until it lands.
+    pub target: Entity,
+    /// Monsters already hit, a piercing or chaining projectile never hits one twice.
+    pub hits: Vec<Entity>,
+    /// Copied from the weapon when fired, the weapon may be gone by the time it lands.
+    pub damage: f32,
+    pub damage_type: DamageType,
+    /// Radius of the weapon's `AreaOfEffect` splash around the impact.
+    pub splash: Option<f32>,
+    /// Weapon class and the shooter's mastery multiplier for it, taken when fired.
+    pub kind: WeaponKind,
+    pub mastery_bonus: f32,
 }
 
 #[derive(Debug, Clone, Reflect, Default)]
@@ -133,53 +206,70 @@ impl MasteryTrack {
         100.0 * (self.level as f32 + 1.0)
     }
 
-    /// Adds XP and returns true if leveled up
-    pub fn add_xp(&mut self, amount: f32) -> bool {
+    /// Adds XP and returns how many levels were gained (0 if none)
+    pub fn add_xp(&mut self, amount: f32) -> u32 {
         self.current_xp += amount;
 
         // Curve: Level 1 needs 100, Level 2 needs 200, etc.
-        let xp_needed = self.xp_for_next_level();
-
-        if self.current_xp >= xp_needed {
-            self.current_xp -= xp_needed;
+        let mut levels_gained = 0;
+        while self.current_xp >= self.xp_for_next_level() {
+            self.current_xp -= self.xp_for_next_level();
             self.level += 1;
-            return true;
+            levels_gained += 1;
         }
-        false
+        levels_gained
     }
 }
 
-#[derive(Component, Reflect, Default)]
+#[derive(Component, Reflect, Default, Clone)]
 #[reflect(Component)]
 pub struct WeaponExpertise {
     pub melee: MasteryTrack,
     pub ranged: MasteryTrack,
 }
 
+impl WeaponExpertise {
+    pub fn track(&self, kind: WeaponKind) -> &MasteryTrack {
+        match kind {
+            WeaponKind::Melee => &self.melee,
+            WeaponKind::Ranged => &self.ranged,
+        }
+    }
+
+    pub fn track_mut(&mut self, kind: WeaponKind) -> &mut MasteryTrack {
+        match kind {
+            WeaponKind::Melee => &mut self.melee,
+            WeaponKind::Ranged => &mut self.ranged,
+        }
+    }
+}
+
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
 pub struct CooldownText;
 
-// Systems
-
-pub fn spawn_player_npc(
-    player_npc_query: Query<Entity, With<PlayerNpc>>,
-    asset_server: Res<AssetServer>,
-    mut scene_spawner: ResMut<SceneSpawner>,
+/// Sends one message per level gained, ending at `new_level`.
+fn write_level_ups(
+    level_up_events: &mut MessageWriter<WeaponMasteryLeveledUp>,
+    entity: Entity,
+    kind: WeaponKind,
+    new_level: u32,
+    levels_gained: u32,
+    location: Vec3,
 ) {
-    // Only spawn if not already present (simple logic for now, or maybe we want multiple?)
-    // The previous logic checked `player_npc_query.is_empty()`. I'll keep that behavior.
-    if !player_npc_query.is_empty() {
-        return;
+    for level in (new_level + 1 - levels_gained)..=new_level {
+        level_up_events.write(WeaponMasteryLeveledUp {
+            entity,
+            kind,
+            level,
+            location,
+        });
+        info!(%entity, ?kind, level, "Weapon mastery leveled up");
     }
-
-    let soldier_handle = asset_server.load::<DynamicScene>("prefabs/player_npcs/soldier.scn.ron");
-    scene_spawner.spawn_dynamic(soldier_handle);
-
-    // let soldier_handle = asset_server.load::<DynamicScene>("prefabs/player_npcs/ranged.scn.ron");
-    // scene_spawner.spawn_dynamic(soldier_handle);
 }
 
+// Systems
+
 pub fn player_npc_decision_logic(
     mut player_npc_query: Query<
         (
@@ -187,24 +277,57 @@ pub fn player_npc_decision_logic(
             &mut Intent,
             &mut Target,
             &Transform,
+            Ref<TargetingPolicy>,
             Option<&Children>,
         ),
         With<PlayerNpc>,
     >,
-    weapon_query: Query<&ItemAttackRange, With<Weapon>>,
-    monster_query: Query<(Entity, &SpawnIndex, &Transform), With<Monster>>,
-    portal_tracker: Res<PortalSpawnTracker>,
+    weapon_query: Query<
+        (&ItemAttackRange, Option<&BaseDamage>, Option<&WeaponCooldown>),
+        With<Weapon>,
+    >,
+    // Projectiles lose their `Transform` once they hit
+    projectile_query: Query<&Projectile, With<Transform>>,
+    monster_query: Query<
+        (
+            Entity,
+            &SpawnIndex,
+            &SpawnedBy,
+            &Transform,
+            Option<&Health>,
+            Option<&Reward>,
+            Option<&Lifetime>,
+        ),
+        With<Monster>,
+    >,
+    portal_query: Query<&PortalSpawnTracker>,
+    monster_index: Res<MonsterIndex>,
+    mut claims: ResMut<TargetClaims>,
 ) {
-    let current_spawn_count = portal_tracker.0;
+    claims.clear();
+    for projectile in projectile_query.iter() {
+        if projectile.hits.contains(&projectile.target) {
+            continue;
+        }
+        claims.claim(projectile.target, projectile.damage);
+    }
+
+    // How many monsters their portal spawned after them, comparable across portals
+    let monster_age = |index: &SpawnIndex, spawned_by: &SpawnedBy| {
+        portal_query
+            .get(spawned_by.0)
+            .map(|tracker| tracker.0.wrapping_sub(index.0))
+            .unwrap_or_default()
+    };
 
-    for (_npc_entity, mut intent, mut target_comp, npc_transform, children) in
+    for (_npc_entity, mut intent, mut target_comp, npc_transform, policy, children) in
         player_npc_query.iter_mut()
     {
         // 1. Calculate Effective Range
         let mut max_range = 0.0;
         if let Some(children) = children {
             for child in children.iter() {
-                if let Ok(range) = weapon_query.get(child) {
+                if let Ok((range, ..)) = weapon_query.get(child) {
                     if range.0 > max_range {
                         max_range = range.0;
                     }
@@ -212,18 +335,50 @@ pub fn player_npc_decision_logic(
             }
         }
 
+        // Monsters other NPCs already have enough damage headed for are left alone
+        let covered = |health: Option<&Health>, entity| {
+            health.is_some_and(|health| claims.covers(entity, health.current))
+        };
+
         let mut target_valid = false;
         if let Some(target) = target_comp.0 {
-            if monster_query.get(target).is_ok() {
-                target_valid = true;
+            if let Ok((_, _, _, _, health, ..)) = monster_query.get(target) {
+                target_valid = !covered(health, target);
             }
         }
 
-        if !target_valid {
-            target_comp.0 = monster_query
-                .iter()
-                .max_by_key(|(_, index, _)| current_spawn_count.wrapping_sub(index.0))
-                .map(|(e, _, _)| e);
+        // A new policy applies right away, not only after the current target dies
+        if !target_valid || policy.is_changed() {
+            // Only the closest monsters can be the nearest one, and at most one
+            // per claim is covered
+            let monsters: Vec<_> = if *policy == TargetingPolicy::Nearest {
+                monster_index
+                    .nearest(npc_transform.translation.truncate(), claims.claimed() + 1)
+                    .iter()
+                    .filter_map(|entry| monster_query.get(entry.entity).ok())
+                    .collect()
+            } else {
+                monster_query.iter().collect()
+            };
+            let candidates = monsters
+                .into_iter()
+                .filter(|(entity, _, _, _, health, ..)| !covered(*health, *entity))
+                .map(
+                    |(entity, index, spawned_by, transform, health, reward, lifetime)| {
+                        TargetCandidate {
+                            entity,
+                            distance: npc_transform.translation.distance(transform.translation),
+                            health: health.map_or(f32::MAX, |health| health.current),
+                            reward: reward
+                                .map(|reward| reward.0.get(Currency::VoidShards))
+                                .unwrap_or_default(),
+                            lifetime_left: lifetime
+                                .map_or(f32::MAX, |lifetime| lifetime.timer.remaining_secs()),
+                            age: monster_age(index, spawned_by),
+                        }
+                    },
+                );
+            target_comp.0 = policy.pick(candidates);
         }
 
         // Decision logic based on target
@@ -232,13 +387,24 @@ pub fn player_npc_decision_logic(
             continue;
         };
 
-        if let Ok((_, _, target_transform)) = monster_query.get(target_entity) {
+        if let Ok((_, _, _, target_transform, ..)) = monster_query.get(target_entity) {
             let distance = npc_transform
                 .translation
                 .distance(target_transform.translation);
 
             if distance <= max_range {
                 *intent = Intent::Attack(target_entity);
+
+                // Weapons off cooldown hit this frame
+                if let Some(children) = children {
+                    for child in children.iter() {
+                        if let Ok((_, Some(damage), Some(cooldown))) = weapon_query.get(child) {
+                            if cooldown.timer.is_finished() {
+                                claims.claim(target_entity, damage.0);
+                            }
+                        }
+                    }
+                }
             } else {
                 *intent = Intent::MoveTo(target_transform.translation);
             }
@@ -252,19 +418,23 @@ pub fn player_npc_decision_logic(
 
 pub fn player_npc_movement_logic(
     time: Res<Time>,
-    mut player_npc_query: Query<(&mut Transform, &Intent, &MovementSpeed), With<PlayerNpc>>,
+    mut player_npc_query: Query<
+        (&mut Transform, &Intent, &MovementSpeed, &EquipmentStats),
+        With<PlayerNpc>,
+    >,
 ) {
-    for (mut transform, intent, speed) in player_npc_query.iter_mut() {
+    for (mut transform, intent, speed, equipment_stats) in player_npc_query.iter_mut() {
         if let Intent::MoveTo(target_pos) = intent {
             let dir = (*target_pos - transform.translation).normalize_or_zero();
-            transform.translation += dir * speed.0 * time.delta_secs();
+            transform.translation +=
+                dir * equipment_stats.effective_speed(speed.0) * time.delta_secs();
         }
     }
 }
 
 pub fn melee_attack_emit(
     mut player_npc_query: Query<
-        (Entity, &Intent, &Children, &mut WeaponExpertise),
+        (Entity, &Transform, &Intent, &Children, &mut WeaponExpertise),
         With<PlayerNpc>,
     >,
     mut weapon_query: Query<
@@ -272,18 +442,22 @@ pub fn melee_attack_emit(
             &mut WeaponCooldown,
             &ItemAttackRange,
             &WeaponExpertiseXp,
+            Option<&AreaOfEffect>,
         ),
         (With<Weapon>, With<Melee>),
     >,
+    monster_query: Query<&Transform, With<Monster>>,
+    monster_index: Res<MonsterIndex>,
     mut melee_hit_events: MessageWriter<MeleeHitMessage>,
+    mut level_up_events: MessageWriter<WeaponMasteryLeveledUp>,
 ) {
-    for (_npc_entity, intent, children, mut expertise) in player_npc_query.iter_mut() {
+    for (npc_entity, npc_tf, intent, children, mut expertise) in player_npc_query.iter_mut() {
         let Intent::Attack(target_entity) = intent else {
             continue;
         };
 
         for child in children.iter() {
-            let Ok((mut cooldown, _range, xp_reward)) = weapon_query.get_mut(child) else {
+            let Ok((mut cooldown, range, xp_reward, aoe)) = weapon_query.get_mut(child) else {
                 continue;
             };
 
@@ -296,8 +470,32 @@ pub fn melee_attack_emit(
                 target: *target_entity,
             });
 
+            // Swing through everything else inside the arc facing the target
+            if let (Some(aoe), Ok(target_tf)) = (aoe, monster_query.get(*target_entity)) {
+                let origin = npc_tf.translation.truncate();
+                let facing = target_tf.translation.truncate() - origin;
+                let reach = range.0.max(aoe.radius);
+                for entry in monster_index.query_cone(origin, facing, reach, aoe.angle) {
+                    if entry.entity != *target_entity {
+                        melee_hit_events.write(MeleeHitMessage {
+                            attacker: child,
+                            target: entry.entity,
+                        });
+                    }
+                }
+            }
+
             // add XP for weapon expertise
-            expertise.melee.add_xp(xp_reward.0);
+            let track = expertise.track_mut(WeaponKind::Melee);
+            let levels_gained = track.add_xp(xp_reward.0);
+            write_level_ups(
+                &mut level_up_events,
+                npc_entity,
+                WeaponKind::Melee,
+                track.level,
+                levels_gained,
+                npc_tf.translation,
+            );
 
             cooldown.timer.reset();
         }
@@ -323,10 +521,13 @@ pub fn ranged_attack_logic(
             &ItemAttackRange,
             &ItemProjectileStats,
             &WeaponExpertiseXp,
+            (Option<&BaseDamage>, Option<&DamageType>, Option<&AreaOfEffect>),
+            (Option<&Homing>, Option<&Pierce>, Option<&Chain>),
         ),
         (With<Weapon>, With<Ranged>),
     >,
     monster_query: Query<&Transform, With<Monster>>,
+    mut level_up_events: MessageWriter<WeaponMasteryLeveledUp>,
 ) {
     for (npc_entity, npc_tf, intent, children, mut expertise) in player_npc_query.iter_mut() {
         let Intent::Attack(target_entity) = intent else {
@@ -338,16 +539,28 @@ pub fn ranged_attack_logic(
         };
 
         for child in children.iter() {
-            if let Ok((mut cooldown, _range, proj_stats, xp_reward)) = weapon_query.get_mut(child) {
+            if let Ok((mut cooldown, _range, proj_stats, xp_reward, hit, behaviours)) =
+                weapon_query.get_mut(child)
+            {
                 if cooldown.timer.is_finished() {
                     let direction =
                         (target_tf.translation - npc_tf.translation).normalize_or_zero();
 
                     // 1. Add XP
-                    expertise.ranged.add_xp(xp_reward.0);
-
-                    // Spawn Projectile
-                    commands.spawn((
+                    let track = expertise.track_mut(WeaponKind::Ranged);
+                    let levels_gained = track.add_xp(xp_reward.0);
+                    write_level_ups(
+                        &mut level_up_events,
+                        npc_entity,
+                        WeaponKind::Ranged,
+                        track.level,
+                        levels_gained,
+                        npc_tf.translation,
+                    );
+
+                    // Spawn Projectile, it keeps its own copy of the weapon's stats and behaviours
+                    let (damage, damage_type, aoe) = hit;
+                    let mut projectile = commands.spawn((
                         Sprite {
                             color: Color::srgb(1.0, 1.0, 0.0), // Yellow
                             custom_size: Some(Vec2::new(8.0, 8.0)),
@@ -359,8 +572,25 @@ pub fn ranged_attack_logic(
                             lifetime: Timer::from_seconds(proj_stats.lifetime, TimerMode::Once),
                             source: npc_entity,
                             weapon: child,
+                            target: *target_entity,
+                            hits: Vec::new(),
+                            damage: damage.map_or(0.0, |damage| damage.0),
+                            damage_type: damage_type.copied().unwrap_or_default(),
+                            splash: aoe.map(|aoe| aoe.radius),
+                            kind: WeaponKind::Ranged,
+                            mastery_bonus: track.get_damage_bonus(),
                         },
                     ));
+                    let (homing, pierce, chain) = behaviours;
+                    if let Some(homing) = homing {
+                        projectile.insert(*homing);
+                    }
+                    if let Some(pierce) = pierce {
+                        projectile.insert(*pierce);
+                    }
+                    if let Some(chain) = chain {
+                        projectile.insert(*chain);
+                    }
 
                     cooldown.timer.reset();
                 }
@@ -372,120 +602,183 @@ pub fn ranged_attack_logic(
 pub fn move_projectiles(
     mut commands: Commands,
     time: Res<Time>,
-    mut projectile_query: Query<(Entity, &mut Transform, &mut Projectile)>,
+    mut projectile_query: Query<
+        (Entity, &mut Transform, &mut Projectile, Option<&Homing>),
+        Without<Monster>,
+    >,
+    monster_query: Query<&Transform, With<Monster>>,
 ) {
-    for (entity, mut transform, mut projectile) in projectile_query.iter_mut() {
+    for (entity, mut transform, mut projectile, homing) in projectile_query.iter_mut() {
         projectile.lifetime.tick(time.delta());
         if projectile.lifetime.is_finished() {
             commands.entity(entity).despawn();
             continue;
         }
 
+        // Turn toward the target until it is hit, at most `turn_rate` per second
+        if let (Some(homing), Ok(target_tf)) = (homing, monster_query.get(projectile.target)) {
+            if !projectile.hits.contains(&projectile.target) {
+                let velocity = projectile.velocity.truncate();
+                let wanted = (target_tf.translation - transform.translation).truncate();
+                if velocity != Vec2::ZERO && wanted != Vec2::ZERO {
+                    let max_turn = homing.turn_rate * time.delta_secs();
+                    let turn = velocity.angle_to(wanted).clamp(-max_turn, max_turn);
+                    projectile.velocity = Vec2::from_angle(turn)
+                        .rotate(velocity)
+                        .extend(projectile.velocity.z);
+                }
+            }
+        }
+
         transform.translation += projectile.velocity * time.delta_secs();
     }
 }
 
+/// Hits the nearest monster overlapping each projectile. A hit projectile
+/// keeps flying while it has [`Pierce`] left, then jumps along its [`Chain`],
+/// and stops once both are used up.
 pub fn projectile_collision(
     mut commands: Commands,
-    projectile_query: Query<(Entity, &Transform, &Projectile)>,
-    monster_query: Query<(Entity, &Transform), With<Monster>>,
+    monster_index: Res<MonsterIndex>,
+    mut projectile_query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut Projectile,
+            Option<&Collider>,
+            Option<&Sprite>,
+            Option<&mut Pierce>,
+            Option<&mut Chain>,
+        ),
+    >,
     mut collision_events: MessageWriter<ProjectileCollisionMessage>,
 ) {
-    for (proj_entity, proj_transform, projectile) in projectile_query.iter() {
-        let mut hit = false;
-        for (monster_entity, monster_transform) in monster_query.iter() {
-            let distance = proj_transform
-                .translation
-                .distance(monster_transform.translation);
-            // Monster size is 24, Projectile 8. Radius approx 12 + 4 = 16. Use 20 for buffer.
-            if distance < 20.0 {
-                collision_events.write(ProjectileCollisionMessage {
-                    projectile: proj_entity,
-                    source: projectile.source,
-                    target: monster_entity,
-                });
-                hit = true;
-                break;
-            }
+    for (proj_entity, proj_transform, mut projectile, collider, sprite, pierce, chain) in
+        projectile_query.iter_mut()
+    {
+        let position = proj_transform.translation.truncate();
+        let nearest_unhit = |radius: f32, hits: &[Entity]| {
+            monster_index
+                .query_radius(position, radius)
+                .filter(|entry| !hits.contains(&entry.entity))
+                .min_by(|a, b| {
+                    a.position
+                        .distance_squared(position)
+                        .total_cmp(&b.position.distance_squared(position))
+                })
+                .map(|entry| (entry.entity, entry.position))
+        };
+
+        let radius = Collider::radius_of(collider, sprite);
+        let Some((monster_entity, _)) = nearest_unhit(radius, &projectile.hits) else {
+            continue;
+        };
+
+        collision_events.write(ProjectileCollisionMessage {
+            projectile: proj_entity,
+            source: projectile.source,
+            target: monster_entity,
+            location: proj_transform.translation,
+        });
+        projectile.hits.push(monster_entity);
+
+        if let Some(mut pierce) = pierce.filter(|pierce| pierce.0 > 0) {
+            pierce.0 -= 1;
+            continue;
         }
 
-        if hit {
-            commands
-                .entity(proj_entity)
-                .remove::<Transform>()
-                .remove::<Sprite>()
-                .insert(MarkedForCleanUp {
-                    despawn_timer: Timer::new(Duration::from_secs(60), TimerMode::Once),
-                });
+        if let Some(mut chain) = chain.filter(|chain| chain.bounces > 0) {
+            if let Some((next, next_position)) = nearest_unhit(chain.range, &projectile.hits) {
+                chain.bounces -= 1;
+                let speed = projectile.velocity.length();
+                projectile.velocity =
+                    ((next_position - position).normalize_or_zero() * speed).extend(0.0);
+                projectile.target = next;
+                continue;
+            }
         }
+
+        // Kept around (without a body) so the damage step can still read it
+        commands
+            .entity(proj_entity)
+            .remove::<Transform>()
+            .remove::<Sprite>()
+            .insert(MarkedForCleanUp {
+                despawn_timer: Timer::new(Duration::from_secs(60), TimerMode::Once),
+            });
     }
 }
 
-pub fn resolve_melee_base_damage(
+pub fn resolve_melee_damage(
     mut messages: MessageReader<MeleeHitMessage>,
-    weapon_query: Query<(&BaseDamage, &ChildOf), With<Weapon>>,
-) -> Vec<MeleeDamageContext> {
-    let mut contexts = Vec::new();
+    weapon_query: Query<(&BaseDamage, &ChildOf, Option<&DamageType>), With<Weapon>>,
+    expertise_query: Query<&WeaponExpertise>,
+    mut damage_events: MessageWriter<DamageMessage>,
+) {
     for msg in messages.read() {
-        if let Ok((damage, parent)) = weapon_query.get(msg.attacker) {
-            contexts.push(MeleeDamageContext {
+        if let Ok((damage, parent, damage_type)) = weapon_query.get(msg.attacker) {
+            damage_events.write(DamageMessage {
                 source: parent.get(),
                 target: msg.target,
-                current_value: damage.0,
+                weapon: Some(msg.attacker),
+                amount: damage.0,
+                damage_type: damage_type.copied().unwrap_or_default(),
+                mastery_bonus: expertise_query
+                    .get(parent.get())
+                    .ok()
+                    .map(|expertise| expertise.track(WeaponKind::Melee).get_damage_bonus()),
             });
         }
     }
-    contexts
 }
 
-pub fn apply_melee_damage(
-    In(contexts): In<Vec<MeleeDamageContext>>,
-    mut monster_query: Query<&mut monsters::Health, With<Monster>>,
-) {
-    for ctx in contexts {
-        if let Ok(mut health) = monster_query.get_mut(ctx.target) {
-            health.current -= ctx.current_value.round();
-            debug!(
-                "Unit {:?} took {} damage from melee source {:?}",
-                ctx.target, ctx.current_value, ctx.source
-            );
-        }
-    }
-}
-
-pub fn resolve_projectile_base_damage(
+/// Damage comes from the projectile itself, so hits still land after the
+/// weapon that fired it was unequipped or its NPC died.
+pub fn resolve_projectile_damage(
     mut messages: MessageReader<ProjectileCollisionMessage>,
     projectile_query: Query<&Projectile>,
-    base_damage: Query<&BaseDamage, With<Ranged>>,
-) -> Vec<ProjectileDamageContext> {
-    let mut contexts = Vec::new();
+    monster_index: Res<MonsterIndex>,
+    mut damage_events: MessageWriter<DamageMessage>,
+) {
     for msg in messages.read() {
-        if let Ok(projectile) = projectile_query.get(msg.projectile) {
-            let base_damage = base_damage
-                .get(projectile.weapon)
-                .expect("projectile must be paired to weapon");
-            contexts.push(ProjectileDamageContext {
-                weapons: projectile.weapon,
-                source: msg.projectile,
-                target: msg.target,
-                current_value: base_damage.0,
-            });
+        let Ok(projectile) = projectile_query.get(msg.projectile) else {
+            continue;
+        };
+        damage_events.write(DamageMessage {
+            source: projectile.source,
+            target: msg.target,
+            weapon: Some(projectile.weapon),
+            amount: projectile.damage,
+            damage_type: projectile.damage_type,
+            mastery_bonus: Some(projectile.mastery_bonus),
+        });
+
+        // Splash hits every other monster around the impact
+        let Some(radius) = projectile.splash else {
+            continue;
+        };
+        for entry in monster_index.query_radius(msg.location.truncate(), radius) {
+            if entry.entity != msg.target {
+                damage_events.write(DamageMessage {
+                    source: projectile.source,
+                    target: entry.entity,
+                    weapon: Some(projectile.weapon),
+                    amount: projectile.damage,
+                    damage_type: projectile.damage_type,
+                    mastery_bonus: Some(projectile.mastery_bonus),
+                });
+            }
         }
     }
-    contexts
 }
 
-pub fn apply_projectile_damage(
-    In(contexts): In<Vec<ProjectileDamageContext>>,
-    mut monster_query: Query<&mut monsters::Health, With<Monster>>,
-) {
-    for ctx in contexts {
-        if let Ok(mut health) = monster_query.get_mut(ctx.target) {
-            health.current -= ctx.current_value.round();
-            debug!(
-                "Unit {:?} took {} damage from projectile {:?} fired by {:?}",
-                ctx.target, ctx.current_value, ctx.source, ctx.weapons
-            );
+/// Multiplies hits by the attacker's mastery of the weapon class used, as
+/// it was when the attack was made. Projectiles keep theirs after their
+/// weapon or shooter is gone.
+pub fn apply_mastery_bonus(mut messages: MessageMutator<DamageMessage>) {
+    for msg in messages.read() {
+        if let Some(bonus) = msg.mastery_bonus {
+            msg.amount *= bonus;
         }
     }
 }
@@ -515,5 +808,15 @@ pub fn update_cooldown_text(
     }
 }
 
+#[cfg(test)]
+mod tests_config;
+#[cfg(test)]
+mod tests_equipment;
 #[cfg(test)]
 mod tests_logic;
+#[cfg(test)]
+mod tests_projectiles;
+#[cfg(test)]
+mod tests_respawn;
+#[cfg(test)]
+mod tests_targeting;
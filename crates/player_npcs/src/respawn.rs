@@ -0,0 +1,188 @@
+use {
+    crate::{PlayerNpc, TargetingPolicy, WeaponExpertise},
+    bevy::{
+        prelude::*,
+        scene::{DynamicScene, InstanceId},
+    },
+    monsters::Health,
+    std::collections::HashMap,
+};
+
+/// Seconds a fallen player NPC waits before coming back.
+pub const PLAYER_NPC_RESPAWN_SECS: f32 = 5.0;
+
+/// Scene of the first player NPC, also used to bring back NPCs without a
+/// [`PlayerNpcPrefab`].
+pub const PLAYER_NPC_PREFAB_PATH: &str = "prefabs/player_npcs/soldier.scn.ron";
+
+// Components
+
+/// Identifies a player NPC across respawns and save files.
+#[derive(
+    Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+#[reflect(Component, Default)]
+pub struct PlayerNpcId(pub u32);
+
+/// Scene a player NPC was spawned from, spawned again when it respawns.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct PlayerNpcPrefab(pub Handle<DynamicScene>);
+
+// Resources
+
+/// A player NPC scene to spawn, with the progress it comes back with.
+#[derive(Clone)]
+pub struct PlayerNpcSpawn {
+    pub id: PlayerNpcId,
+    pub prefab: Handle<DynamicScene>,
+    /// Kept from before it fell, `None` for a new NPC.
+    pub kept: Option<(WeaponExpertise, TargetingPolicy)>,
+}
+
+/// A fallen player NPC waiting for its own timer.
+#[derive(Clone)]
+pub struct FallenPlayerNpc {
+    pub spawn: PlayerNpcSpawn,
+    pub timer: Timer,
+}
+
+/// Fallen player NPCs, each respawns on its own once its timer runs out.
+#[derive(Resource, Default)]
+pub struct PlayerNpcRespawn {
+    pub pending: Vec<FallenPlayerNpc>,
+}
+
+/// Hands every new player NPC its own [`PlayerNpcId`], respawns keep theirs.
+#[derive(Resource, Default, Debug)]
+pub struct NextPlayerNpcId(pub u32);
+
+impl NextPlayerNpcId {
+    pub fn take(&mut self) -> PlayerNpcId {
+        let id = PlayerNpcId(self.0);
+        self.0 += 1;
+        id
+    }
+}
+
+/// Player NPC scenes still being spawned, with who they bring back.
+#[derive(Resource, Default)]
+pub struct PendingPlayerNpcSpawns(HashMap<InstanceId, PlayerNpcSpawn>);
+
+impl PendingPlayerNpcSpawns {
+    pub fn spawn(&mut self, scene_spawner: &mut SceneSpawner, spawn: PlayerNpcSpawn) {
+        let instance_id = scene_spawner.spawn_dynamic(spawn.prefab.clone());
+        self.0.insert(instance_id, spawn);
+    }
+}
+
+// Systems
+
+/// Spawns the first player NPC when there is none yet, alive or about to respawn.
+pub fn spawn_player_npc(
+    player_npc_query: Query<(), With<PlayerNpc>>,
+    asset_server: Res<AssetServer>,
+    respawn: Res<PlayerNpcRespawn>,
+    mut next_id: ResMut<NextPlayerNpcId>,
+    mut scene_spawner: ResMut<SceneSpawner>,
+    mut pending_spawns: ResMut<PendingPlayerNpcSpawns>,
+) {
+    if !player_npc_query.is_empty() || !respawn.pending.is_empty() || !pending_spawns.0.is_empty()
+    {
+        return;
+    }
+
+    pending_spawns.spawn(
+        &mut scene_spawner,
+        PlayerNpcSpawn {
+            id: next_id.take(),
+            prefab: asset_server.load(PLAYER_NPC_PREFAB_PATH),
+            kept: None,
+        },
+    );
+}
+
+/// Player NPCs at zero health go down and come back after a delay.
+pub fn player_npc_death(
+    mut commands: Commands,
+    player_npc_query: Query<
+        (
+            Entity,
+            &Health,
+            &PlayerNpcId,
+            &WeaponExpertise,
+            &TargetingPolicy,
+            Option<&PlayerNpcPrefab>,
+        ),
+        With<PlayerNpc>,
+    >,
+    asset_server: Res<AssetServer>,
+    mut respawn: ResMut<PlayerNpcRespawn>,
+) {
+    for (entity, health, id, expertise, policy, prefab) in player_npc_query.iter() {
+        if health.current > 0.0 {
+            continue;
+        }
+
+        commands.entity(entity).despawn();
+        let prefab = match prefab {
+            Some(prefab) => prefab.0.clone(),
+            None => asset_server.load(PLAYER_NPC_PREFAB_PATH),
+        };
+        respawn.pending.push(FallenPlayerNpc {
+            spawn: PlayerNpcSpawn {
+                id: *id,
+                prefab,
+                kept: Some((expertise.clone(), *policy)),
+            },
+            timer: Timer::from_seconds(PLAYER_NPC_RESPAWN_SECS, TimerMode::Once),
+        });
+        info!(%entity, ?id, "Player NPC died, respawning in {}s", PLAYER_NPC_RESPAWN_SECS);
+    }
+}
+
+pub fn respawn_player_npcs(
+    time: Res<Time>,
+    mut respawn: ResMut<PlayerNpcRespawn>,
+    mut scene_spawner: ResMut<SceneSpawner>,
+    mut pending_spawns: ResMut<PendingPlayerNpcSpawns>,
+) {
+    respawn.pending.retain_mut(|fallen| {
+        fallen.timer.tick(time.delta());
+        if !fallen.timer.is_finished() {
+            return true;
+        }
+
+        pending_spawns.spawn(&mut scene_spawner, fallen.spawn.clone());
+        info!(id = ?fallen.spawn.id, "Player NPC respawning");
+        false
+    });
+}
+
+/// Gives spawned player NPC scenes their id, and respawned ones the progress
+/// they had when they fell.
+pub fn attach_spawned_player_npcs(
+    mut commands: Commands,
+    scene_spawner: Res<SceneSpawner>,
+    mut pending_spawns: ResMut<PendingPlayerNpcSpawns>,
+    player_npc_query: Query<(), With<PlayerNpc>>,
+) {
+    pending_spawns.0.retain(|instance_id, spawn| {
+        if !scene_spawner.instance_is_ready(*instance_id) {
+            return true;
+        }
+
+        for entity in scene_spawner.iter_instance_entities(*instance_id) {
+            if player_npc_query.get(entity).is_err() {
+                continue;
+            }
+
+            let mut npc_cmds = commands.entity(entity);
+            npc_cmds.insert((spawn.id, PlayerNpcPrefab(spawn.prefab.clone())));
+            if let Some((expertise, policy)) = &spawn.kept {
+                npc_cmds.insert((expertise.clone(), *policy));
+            }
+        }
+        false
+    });
+}
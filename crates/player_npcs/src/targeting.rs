@@ -0,0 +1,126 @@
+use {
+    bevy::prelude::*,
+    common::BigNumber,
+    serde::{Deserialize, Serialize},
+    std::{cmp::Ordering, collections::HashMap},
+};
+
+// Resources
+
+/// Damage already headed for each monster, so NPCs don't all pile onto a
+/// monster that is about to die anyway.
+///
+/// Rebuilt by `player_npc_decision_logic` every frame from the projectiles in
+/// flight, then each NPC claims the hits its ready weapons are about to deal,
+/// the `MeleeHitMessage`s and projectiles queued in `VoidGameStage::Actions`.
+#[derive(Resource, Debug, Default)]
+pub struct TargetClaims {
+    pending: HashMap<Entity, f32>,
+}
+
+impl TargetClaims {
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+
+    pub fn claim(&mut self, target: Entity, damage: f32) {
+        *self.pending.entry(target).or_default() += damage;
+    }
+
+    /// Number of monsters with damage on its way.
+    pub fn claimed(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn pending(&self, target: Entity) -> f32 {
+        self.pending.get(&target).copied().unwrap_or_default()
+    }
+
+    /// Whether the damage on its way is enough to kill a monster at `health`.
+    pub fn covers(&self, target: Entity, health: f32) -> bool {
+        let pending = self.pending(target);
+        pending > 0.0 && pending >= health
+    }
+}
+
+// Components
+
+/// How a player NPC picks its next target once the current one is gone.
+#[derive(
+    Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[reflect(Component, Default)]
+pub enum TargetingPolicy {
+    Nearest,
+    LowestHealth,
+    /// Highest void shard `Reward`.
+    HighestReward,
+    /// Least `Lifetime` left, so fewer monsters escape and get scavenged.
+    AboutToExpire,
+    /// Spawned the longest ago.
+    #[default]
+    Oldest,
+}
+
+impl TargetingPolicy {
+    pub const ALL: [Self; 5] = [
+        Self::Nearest,
+        Self::LowestHealth,
+        Self::HighestReward,
+        Self::AboutToExpire,
+        Self::Oldest,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Nearest => "Nearest",
+            Self::LowestHealth => "Lowest HP",
+            Self::HighestReward => "Highest Reward",
+            Self::AboutToExpire => "About to Expire",
+            Self::Oldest => "Oldest",
+        }
+    }
+
+    /// Following policy in [`Self::ALL`], wrapping around.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|policy| *policy == self).unwrap_or_default();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Candidate this policy prefers, ties go to the nearest one.
+    pub fn pick(self, candidates: impl IntoIterator<Item = TargetCandidate>) -> Option<Entity> {
+        candidates
+            .into_iter()
+            .min_by(|a, b| {
+                self.compare(a, b)
+                    .then_with(|| a.distance.total_cmp(&b.distance))
+            })
+            .map(|candidate| candidate.entity)
+    }
+
+    /// `Less` when `a` is preferred over `b`.
+    fn compare(self, a: &TargetCandidate, b: &TargetCandidate) -> Ordering {
+        match self {
+            Self::Nearest => a.distance.total_cmp(&b.distance),
+            Self::LowestHealth => a.health.total_cmp(&b.health),
+            Self::HighestReward => b.reward.partial_cmp(&a.reward).unwrap_or(Ordering::Equal),
+            Self::AboutToExpire => a.lifetime_left.total_cmp(&b.lifetime_left),
+            Self::Oldest => b.age.cmp(&a.age),
+        }
+    }
+}
+
+/// A monster as seen by a [`TargetingPolicy`].
+#[derive(Debug, Clone, Copy)]
+pub struct TargetCandidate {
+    pub entity: Entity,
+    pub distance: f32,
+    /// Current health, `f32::MAX` for monsters without `Health`.
+    pub health: f32,
+    /// Void shards paid on kill.
+    pub reward: BigNumber,
+    /// Seconds before the monster leaves, `f32::MAX` without a `Lifetime`.
+    pub lifetime_left: f32,
+    /// How many monsters its portal spawned after it, comparable across portals.
+    pub age: u32,
+}
@@ -0,0 +1,162 @@
+use {
+    crate::*,
+    bevy_common_assets::ron::RonAssetPlugin,
+    items::{
+        AttackRange as ItemAttackRange, AttackSpeed, BaseDamage,
+        ProjectileStats as ItemProjectileStats, Ranged,
+    },
+    std::time::Duration,
+};
+
+fn setup_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(AssetPlugin {
+            file_path: "../../assets".to_string(),
+            ..default()
+        })
+        .add_plugins(RonAssetPlugin::<PlayerNpcConfig>::new(&["soldier.ron"]));
+
+    app.add_systems(Startup, load_player_npc_config);
+    app.add_systems(Update, apply_player_npc_config);
+
+    app.update();
+    for _ in 0..500 {
+        let handle = &app.world().resource::<PlayerNpcConfigHandle>().0;
+        if app
+            .world()
+            .resource::<Assets<PlayerNpcConfig>>()
+            .contains(handle)
+        {
+            return app;
+        }
+        app.update();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    panic!("{PLAYER_NPC_CONFIG_PATH} never loaded");
+}
+
+fn weapon_cooldown(secs: f32) -> WeaponCooldown {
+    WeaponCooldown {
+        timer: Timer::from_seconds(secs, TimerMode::Once),
+    }
+}
+
+fn spawn_npc(app: &mut App) -> (Entity, Entity, Entity) {
+    let npc = app.world_mut().spawn((PlayerNpc, MovementSpeed(1.0))).id();
+    let gun = app
+        .world_mut()
+        .spawn((
+            ChildOf(npc),
+            Weapon,
+            DefaultWeapon,
+            Ranged,
+            weapon_cooldown(5.0),
+            ItemAttackRange(1.0),
+            BaseDamage(99.0),
+            ItemProjectileStats {
+                speed: 1.0,
+                lifetime: 1.0,
+            },
+        ))
+        .id();
+    let fast_sword = app
+        .world_mut()
+        .spawn((
+            ChildOf(npc),
+            Weapon,
+            DefaultWeapon,
+            weapon_cooldown(0.25),
+            AttackSpeed(4.0),
+            ItemAttackRange(1.0),
+            BaseDamage(7.0),
+        ))
+        .id();
+    (npc, gun, fast_sword)
+}
+
+#[test]
+fn test_config_applied_to_spawned_npc() {
+    let mut app = setup_app();
+    let (npc, gun, fast_sword) = spawn_npc(&mut app);
+    app.update();
+
+    let world = app.world();
+    assert_eq!(world.get::<MovementSpeed>(npc).unwrap().0, 175.0);
+
+    assert_eq!(world.get::<ItemAttackRange>(gun).unwrap().0, 30.0);
+    let cooldown = world.get::<WeaponCooldown>(gun).unwrap();
+    assert_eq!(cooldown.timer.duration().as_secs_f32(), 1.0);
+    let projectile = world.get::<ItemProjectileStats>(gun).unwrap();
+    assert_eq!((projectile.speed, projectile.lifetime), (800.0, 2.0));
+    assert_eq!(world.get::<BaseDamage>(gun).unwrap().0, 1.0);
+
+    // Melee damage and attack speed stay with the item
+    assert_eq!(world.get::<BaseDamage>(fast_sword).unwrap().0, 7.0);
+    let cooldown = world.get::<WeaponCooldown>(fast_sword).unwrap();
+    assert_eq!(cooldown.timer.duration().as_secs_f32(), 0.25);
+}
+
+#[test]
+fn test_config_change_updates_live_npcs() {
+    let mut app = setup_app();
+    let (npc, gun, _) = spawn_npc(&mut app);
+    app.update();
+
+    let handle = app.world().resource::<PlayerNpcConfigHandle>().0.clone();
+    {
+        let mut configs = app.world_mut().resource_mut::<Assets<PlayerNpcConfig>>();
+        let config = configs.get_mut(&handle).unwrap();
+        config.move_speed = 50.0;
+        config.projectile_speed = 400.0;
+    }
+    // The change is announced at the end of the frame and applied on the next one
+    app.update();
+    app.update();
+
+    assert_eq!(app.world().get::<MovementSpeed>(npc).unwrap().0, 50.0);
+    assert_eq!(
+        app.world().get::<ItemProjectileStats>(gun).unwrap().speed,
+        400.0
+    );
+}
+
+#[test]
+fn test_config_leaves_equipped_items_alone() {
+    let mut app = setup_app();
+    let (npc, _, _) = spawn_npc(&mut app);
+    // Equipped later from an item prefab, not part of the NPC's own
+    let bow = app
+        .world_mut()
+        .spawn((
+            ChildOf(npc),
+            Weapon,
+            Ranged,
+            weapon_cooldown(2.0),
+            ItemAttackRange(250.0),
+            BaseDamage(12.0),
+            ItemProjectileStats {
+                speed: 600.0,
+                lifetime: 3.0,
+            },
+        ))
+        .id();
+    app.update();
+
+    let handle = app.world().resource::<PlayerNpcConfigHandle>().0.clone();
+    app.world_mut()
+        .resource_mut::<Assets<PlayerNpcConfig>>()
+        .get_mut(&handle)
+        .unwrap()
+        .attack_range = 40.0;
+    app.update();
+    app.update();
+
+    let world = app.world();
+    assert_eq!(world.get::<ItemAttackRange>(bow).unwrap().0, 250.0);
+    assert_eq!(world.get::<BaseDamage>(bow).unwrap().0, 12.0);
+    let cooldown = world.get::<WeaponCooldown>(bow).unwrap();
+    assert_eq!(cooldown.timer.duration().as_secs_f32(), 2.0);
+    let projectile = world.get::<ItemProjectileStats>(bow).unwrap();
+    assert_eq!((projectile.speed, projectile.lifetime), (600.0, 3.0));
+}
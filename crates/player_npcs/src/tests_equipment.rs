@@ -0,0 +1,299 @@
+use {
+    crate::*,
+    bevy::scene::ScenePlugin,
+    common::{
+        DamageMessage, EquipItem, EquipmentSlot, ProjectileCollisionMessage, UnequipItem,
+        WeaponMasteryLeveledUp,
+    },
+    items::{
+        AreaOfEffect, Armor as ItemArmor, AttackRange as ItemAttackRange, AttackSpeed, ItemsPlugin,
+        Melee, Ranged,
+    },
+    monsters::{apply_damage, Armor, Health, Monster, SpawnIndex, SpawnedBy},
+    portal::PortalSpawnTracker,
+    std::time::Duration,
+};
+
+fn setup_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(AssetPlugin {
+            file_path: "../../assets".to_string(),
+            ..default()
+        })
+        .add_plugins(ScenePlugin)
+        .add_plugins(ItemsPlugin);
+
+    app.add_message::<EquipItem>();
+    app.add_message::<UnequipItem>();
+    app.register_type::<WeaponExpertiseXp>();
+    app.init_resource::<PendingEquips>()
+        .init_resource::<TargetClaims>()
+        .init_resource::<MonsterIndex>();
+
+    app.add_systems(
+        Update,
+        (
+            unequip_items,
+            equip_items,
+            attach_equipped_items,
+            register_equipped_items,
+            update_equipment_stats,
+            player_npc_decision_logic,
+        )
+            .chain(),
+    );
+
+    app
+}
+
+fn spawn_npc(app: &mut App) -> Entity {
+    app.world_mut()
+        .spawn((PlayerNpc, Target(None), Intent::Idle, Transform::default()))
+        .id()
+}
+
+/// Runs the app until the main hand holds something other than `previous`.
+fn wait_for_main_hand(app: &mut App, npc: Entity, previous: Option<Entity>) -> Entity {
+    for _ in 0..500 {
+        app.update();
+        let equipped = app.world().get::<Equipment>(npc).unwrap().main_hand;
+        if let Some(item) = equipped.filter(|item| Some(*item) != previous) {
+            return item;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    panic!("item was never equipped");
+}
+
+#[test]
+fn test_equip_swap_and_unequip_weapon() {
+    let mut app = setup_app();
+    let npc = spawn_npc(&mut app);
+
+    app.world_mut().write_message(EquipItem {
+        npc,
+        slot: EquipmentSlot::MainHand,
+        prefab: "prefabs/items/iron_sword.scn.ron".to_string(),
+    });
+    let sword = wait_for_main_hand(&mut app, npc, None);
+
+    let world = app.world();
+    assert_eq!(world.get::<ChildOf>(sword).unwrap().parent(), npc);
+    assert!(world.get::<Weapon>(sword).is_some());
+    assert!(world.get::<Melee>(sword).is_some());
+    assert!(world.get::<AreaOfEffect>(sword).is_some());
+    assert_eq!(world.get::<ItemAttackRange>(sword).unwrap().0, 30.0);
+    assert_eq!(
+        world
+            .get::<WeaponCooldown>(sword)
+            .unwrap()
+            .timer
+            .duration()
+            .as_secs_f32(),
+        1.0
+    );
+
+    // The new range is used for decisions right away
+    let portal = app.world_mut().spawn(PortalSpawnTracker(1)).id();
+    let monster = app
+        .world_mut()
+        .spawn((
+            Monster::default(),
+            SpawnIndex(0),
+            SpawnedBy(portal),
+            Transform::from_xyz(25.0, 0.0, 0.0),
+        ))
+        .id();
+    app.update();
+    assert!(matches!(
+        app.world().get::<Intent>(npc).unwrap(),
+        Intent::Attack(target) if *target == monster
+    ));
+
+    // Swap to the bow
+    app.world_mut().write_message(EquipItem {
+        npc,
+        slot: EquipmentSlot::MainHand,
+        prefab: "prefabs/items/elven_bow.scn.ron".to_string(),
+    });
+    let bow = wait_for_main_hand(&mut app, npc, Some(sword));
+    app.update();
+
+    assert!(app.world().get_entity(sword).is_err(), "Sword despawned");
+    assert!(app.world().get::<Ranged>(bow).is_some());
+    assert_eq!(app.world().get::<AttackSpeed>(bow).unwrap().0, 0.8);
+    assert_eq!(
+        app.world()
+            .get::<WeaponCooldown>(bow)
+            .unwrap()
+            .timer
+            .duration()
+            .as_secs_f32(),
+        1.25
+    );
+
+    app.world_mut().write_message(UnequipItem {
+        npc,
+        slot: EquipmentSlot::MainHand,
+    });
+    app.update();
+
+    assert_eq!(app.world().get::<Equipment>(npc).unwrap().main_hand, None);
+    assert!(app.world().get_entity(bow).is_err(), "Bow despawned");
+}
+
+#[test]
+fn test_swapping_weapon_keeps_its_projectiles_in_flight() {
+    let mut app = setup_app();
+    app.add_message::<WeaponMasteryLeveledUp>()
+        .add_message::<ProjectileCollisionMessage>()
+        .add_message::<DamageMessage>();
+    let npc = spawn_npc(&mut app);
+    app.world_mut().entity_mut(npc).insert(WeaponExpertise {
+        melee: MasteryTrack::default(),
+        ranged: MasteryTrack {
+            level: 5,
+            current_xp: 0.0,
+        },
+    });
+
+    app.world_mut().write_message(EquipItem {
+        npc,
+        slot: EquipmentSlot::MainHand,
+        prefab: "prefabs/items/elven_bow.scn.ron".to_string(),
+    });
+    let bow = wait_for_main_hand(&mut app, npc, None);
+
+    let portal = app.world_mut().spawn(PortalSpawnTracker(1)).id();
+    let monster = app
+        .world_mut()
+        .spawn((
+            Monster::default(),
+            Health {
+                current: 50.0,
+                max: 50.0,
+            },
+            SpawnIndex(0),
+            SpawnedBy(portal),
+            Transform::from_xyz(100.0, 0.0, 0.0),
+        ))
+        .id();
+    app.update();
+
+    // Loose an arrow
+    app.world_mut()
+        .get_mut::<WeaponCooldown>(bow)
+        .unwrap()
+        .timer
+        .tick(Duration::from_secs(2));
+    app.world_mut()
+        .run_system_cached(ranged_attack_logic)
+        .unwrap();
+    let projectile = app
+        .world_mut()
+        .query_filtered::<Entity, With<Projectile>>()
+        .single(app.world())
+        .unwrap();
+
+    // Swap to the sword while it flies
+    app.world_mut().write_message(EquipItem {
+        npc,
+        slot: EquipmentSlot::MainHand,
+        prefab: "prefabs/items/iron_sword.scn.ron".to_string(),
+    });
+    wait_for_main_hand(&mut app, npc, Some(bow));
+    app.update();
+    assert!(app.world().get_entity(bow).is_err(), "Bow despawned");
+
+    // The arrow still lands for the bow's damage and ranged mastery: 10 * 1.5
+    app.world_mut().write_message(ProjectileCollisionMessage {
+        projectile,
+        source: npc,
+        target: monster,
+        location: Vec3::new(100.0, 0.0, 0.0),
+    });
+    app.world_mut()
+        .run_system_cached(resolve_projectile_damage)
+        .unwrap();
+    app.world_mut()
+        .run_system_cached(apply_mastery_bonus)
+        .unwrap();
+    app.world_mut().run_system_cached(apply_damage).unwrap();
+    assert_eq!(app.world().get::<Health>(monster).unwrap().current, 35.0);
+}
+
+#[test]
+fn test_prefab_items_are_registered_in_their_slot() {
+    let mut app = setup_app();
+    let npc = spawn_npc(&mut app);
+
+    let sword = app
+        .world_mut()
+        .spawn((
+            Weapon,
+            Melee,
+            EquippedIn(EquipmentSlot::MainHand),
+            ChildOf(npc),
+        ))
+        .id();
+    app.update();
+
+    let equipment = app.world().get::<Equipment>(npc).unwrap();
+    assert_eq!(equipment.get(EquipmentSlot::MainHand), Some(sword));
+    assert_eq!(equipment.get(EquipmentSlot::OffHand), None);
+}
+
+#[test]
+fn test_armor_adds_defense_and_slows_movement() {
+    let mut app = setup_app();
+    app.add_systems(Update, player_npc_movement_logic);
+    let npc = spawn_npc(&mut app);
+    app.world_mut().entity_mut(npc).insert((
+        MovementSpeed(100.0),
+        Intent::MoveTo(Vec3::new(1000.0, 0.0, 0.0)),
+    ));
+
+    let armor = app
+        .world_mut()
+        .spawn((
+            ItemArmor {
+                defense: 25.0,
+                movement_speed_modifier: -0.2,
+            },
+            EquippedIn(EquipmentSlot::Armor),
+            ChildOf(npc),
+        ))
+        .id();
+    app.update();
+
+    let world = app.world();
+    assert_eq!(
+        *world.get::<EquipmentStats>(npc).unwrap(),
+        EquipmentStats {
+            defense: 25.0,
+            movement_speed_modifier: -0.2,
+        }
+    );
+    assert_eq!(world.get::<Armor>(npc).unwrap().physical, 25.0);
+    assert_eq!(
+        world
+            .get::<EquipmentStats>(npc)
+            .unwrap()
+            .effective_speed(100.0),
+        80.0
+    );
+
+    app.world_mut().write_message(UnequipItem {
+        npc,
+        slot: EquipmentSlot::Armor,
+    });
+    app.update();
+
+    assert!(app.world().get_entity(armor).is_err());
+    assert_eq!(
+        *app.world().get::<EquipmentStats>(npc).unwrap(),
+        EquipmentStats::default()
+    );
+    assert_eq!(app.world().get::<Armor>(npc).unwrap().physical, 0.0);
+}
@@ -1,9 +1,18 @@
 use {
     crate::*,
     bevy::time::{Time, TimePlugin},
-    common::{MeleeHitMessage, MonsterKilled, ProjectileCollisionMessage},
-    items::{AttackRange as ItemAttackRange, BaseDamage, Melee, ProjectileStats, Ranged},
-    monsters::{Health, Monster, SpawnIndex},
+    common::{
+        DamageMessage, DamageType, MeleeHitMessage, MonsterIndex, MonsterKilled,
+        ProjectileCollisionMessage, WeaponKind, WeaponMasteryLeveledUp,
+    },
+    items::{
+        AreaOfEffect, AttackRange as ItemAttackRange, AttackSpeed, BaseDamage, Melee,
+        ProjectileStats, Ranged,
+    },
+    monsters::{
+        apply_damage, index_monsters, mitigate_damage, Armor, Health, Monster, SpawnIndex,
+        SpawnedBy,
+    },
     portal::PortalSpawnTracker,
 };
 
@@ -13,9 +22,13 @@ fn create_app_with_minimal_plugins() -> App {
     let mut app = App::new();
     app.add_plugins(MinimalPlugins.build().disable::<TimePlugin>()); // Manually inserting Time to control it
     app.insert_resource(Time::<()>::default());
+    app.init_resource::<TargetClaims>()
+        .init_resource::<MonsterIndex>();
     app.add_message::<MonsterKilled>(); // Register MonsterKilled message
     app.add_message::<MeleeHitMessage>();
     app.add_message::<ProjectileCollisionMessage>();
+    app.add_message::<DamageMessage>();
+    app.add_message::<WeaponMasteryLeveledUp>();
 
     // Register types
     app.register_type::<PlayerNpc>()
@@ -33,15 +46,16 @@ fn create_app_with_minimal_plugins() -> App {
     app
 }
 
-fn spawn_portal_and_tracker(app: &mut App) {
-    app.insert_resource(PortalSpawnTracker(0));
-    app.world_mut().spawn((Transform::default(),));
+fn spawn_portal_and_tracker(app: &mut App) -> Entity {
+    app.world_mut()
+        .spawn((Transform::default(), PortalSpawnTracker(1)))
+        .id()
 }
 
 #[test]
 fn test_npc_acquires_target() {
     let mut app = create_app_with_minimal_plugins();
-    spawn_portal_and_tracker(&mut app);
+    let portal = spawn_portal_and_tracker(&mut app);
 
     app.add_systems(Update, player_npc_decision_logic);
 
@@ -69,23 +83,54 @@ fn test_npc_acquires_target() {
                 max: 100.0,
             },
             SpawnIndex(0),
+            SpawnedBy(portal),
             Transform::from_xyz(10.0, 0.0, 0.0),
         ))
         .id();
 
-    // Update Tracker
-    app.world_mut().resource_mut::<PortalSpawnTracker>().0 = 1;
-
     app.update();
 
     let target = app.world().get::<Target>(npc).unwrap();
     assert_eq!(target.0, Some(monster), "NPC should target the monster");
 }
 
+#[test]
+fn test_npc_targets_oldest_monster_across_portals() {
+    let mut app = create_app_with_minimal_plugins();
+    app.add_systems(Update, player_npc_decision_logic);
+
+    // The first portal spawned 2 monsters since, the second one 3
+    let busy_portal = app.world_mut().spawn(PortalSpawnTracker(10)).id();
+    let quiet_portal = app.world_mut().spawn(PortalSpawnTracker(3)).id();
+
+    let npc = app
+        .world_mut()
+        .spawn((PlayerNpc, Target(None), Transform::default(), Intent::Idle))
+        .id();
+
+    let mut spawn_monster = |portal, index| {
+        app.world_mut()
+            .spawn((
+                Monster::default(),
+                SpawnIndex(index),
+                SpawnedBy(portal),
+                Transform::from_xyz(10.0, 0.0, 0.0),
+            ))
+            .id()
+    };
+    spawn_monster(busy_portal, 8);
+    let oldest = spawn_monster(quiet_portal, 0);
+
+    app.update();
+
+    let target = app.world().get::<Target>(npc).unwrap();
+    assert_eq!(target.0, Some(oldest));
+}
+
 #[test]
 fn test_npc_moves_to_target() {
     let mut app = create_app_with_minimal_plugins();
-    spawn_portal_and_tracker(&mut app);
+    let portal = spawn_portal_and_tracker(&mut app);
 
     app.add_systems(
         Update,
@@ -121,13 +166,11 @@ fn test_npc_moves_to_target() {
                 max: 100.0,
             },
             SpawnIndex(0),
+            SpawnedBy(portal),
             Transform::from_xyz(100.0, 0.0, 0.0),
         ))
         .id();
 
-    // Update Tracker
-    app.world_mut().resource_mut::<PortalSpawnTracker>().0 = 1;
-
     // 1. Decision Logic
     app.update();
 
@@ -152,7 +195,7 @@ fn test_npc_moves_to_target() {
 #[test]
 fn test_npc_stops_in_range() {
     let mut app = create_app_with_minimal_plugins();
-    spawn_portal_and_tracker(&mut app);
+    let portal = spawn_portal_and_tracker(&mut app);
 
     app.add_systems(
         Update,
@@ -186,11 +229,10 @@ fn test_npc_stops_in_range() {
             max: 100.0,
         },
         SpawnIndex(0),
+        SpawnedBy(portal),
         Transform::from_xyz(100.0, 0.0, 0.0),
     ));
 
-    app.world_mut().resource_mut::<PortalSpawnTracker>().0 = 1;
-
     app.update(); // Decision
     app.update(); // Movement
 
@@ -204,7 +246,7 @@ fn test_npc_stops_in_range() {
 #[test]
 fn test_melee_attack() {
     let mut app = create_app_with_minimal_plugins();
-    spawn_portal_and_tracker(&mut app);
+    let portal = spawn_portal_and_tracker(&mut app);
 
     app.add_systems(
         Update,
@@ -212,7 +254,8 @@ fn test_melee_attack() {
             tick_weapon_cooldowns,
             player_npc_decision_logic,
             melee_attack_emit,
-            resolve_melee_base_damage.pipe(apply_melee_damage),
+            resolve_melee_damage,
+            apply_damage,
         )
             .chain(),
     );
@@ -257,12 +300,11 @@ fn test_melee_attack() {
                 max: 50.0,
             },
             SpawnIndex(0),
+            SpawnedBy(portal),
             Transform::from_xyz(100.0, 0.0, 0.0),
         ))
         .id();
 
-    app.world_mut().resource_mut::<PortalSpawnTracker>().0 = 1;
-
     // 1. Decision (Acquire Target)
     app.update();
 
@@ -281,7 +323,7 @@ fn test_melee_attack() {
 #[test]
 fn test_ranged_attack_spawns_projectile() {
     let mut app = create_app_with_minimal_plugins();
-    spawn_portal_and_tracker(&mut app);
+    let portal = spawn_portal_and_tracker(&mut app);
 
     app.add_systems(
         Update,
@@ -291,7 +333,8 @@ fn test_ranged_attack_spawns_projectile() {
             ranged_attack_logic,
             move_projectiles,
             projectile_collision,
-            resolve_projectile_base_damage.pipe(apply_projectile_damage),
+            resolve_projectile_damage,
+            apply_damage,
         )
             .chain(),
     );
@@ -340,12 +383,11 @@ fn test_ranged_attack_spawns_projectile() {
                 max: 50.0,
             },
             SpawnIndex(0),
+            SpawnedBy(portal),
             Transform::from_xyz(50.0, 0.0, 0.0),
         ))
         .id();
 
-    app.world_mut().resource_mut::<PortalSpawnTracker>().0 = 1;
-
     // 1. Decision
     app.update();
 
@@ -358,14 +400,81 @@ fn test_ranged_attack_spawns_projectile() {
 
     // Verify Projectile Exists
     let mut query = app.world_mut().query::<&Projectile>();
-    let projectile_count = query.iter(app.world()).count();
-    assert_eq!(projectile_count, 1, "Should spawn 1 projectile");
+    let projectiles: Vec<_> = query.iter(app.world()).collect();
+    assert_eq!(projectiles.len(), 1, "Should spawn 1 projectile");
+    assert_eq!(projectiles[0].damage, 10.0);
+    assert_eq!(projectiles[0].damage_type, DamageType::Physical);
+    assert_eq!(projectiles[0].splash, None);
+    assert_eq!(projectiles[0].kind, WeaponKind::Ranged);
+    assert_eq!(projectiles[0].mastery_bonus, 1.0);
+}
+
+#[test]
+fn test_projectile_lands_after_its_npc_died() {
+    let mut app = create_app_with_minimal_plugins();
+    app.add_systems(
+        Update,
+        (resolve_projectile_damage, apply_mastery_bonus, apply_damage).chain(),
+    );
+
+    let monster = app
+        .world_mut()
+        .spawn((
+            Monster::default(),
+            Health {
+                current: 50.0,
+                max: 50.0,
+            },
+            Transform::default(),
+        ))
+        .id();
+    let bow = app
+        .world_mut()
+        .spawn((Weapon, Ranged, BaseDamage(10.0), DamageType::Magic))
+        .id();
+    let npc = app
+        .world_mut()
+        .spawn((PlayerNpc, WeaponExpertise::default()))
+        .add_child(bow)
+        .id();
+    let projectile = app
+        .world_mut()
+        .spawn(Projectile {
+            velocity: Vec3::ZERO,
+            lifetime: Timer::from_seconds(5.0, TimerMode::Once),
+            source: npc,
+            weapon: bow,
+            target: monster,
+            hits: vec![monster],
+            damage: 10.0,
+            damage_type: DamageType::Magic,
+            splash: None,
+            kind: WeaponKind::Ranged,
+            // Fired at ranged mastery level 5
+            mastery_bonus: 1.5,
+        })
+        .id();
+
+    // Like `player_npc_death`, takes the bow along
+    app.world_mut().despawn(npc);
+    assert!(app.world().get_entity(bow).is_err());
+
+    app.world_mut().write_message(ProjectileCollisionMessage {
+        projectile,
+        source: npc,
+        target: monster,
+        location: Vec3::ZERO,
+    });
+    app.update();
+
+    // The mastery bonus left with the shooter, the projectile still has it: 10 * 1.5
+    assert_eq!(app.world().get::<Health>(monster).unwrap().current, 35.0);
 }
 
 #[test]
 fn test_melee_attack_emits_hit_message() {
     let mut app = create_app_with_minimal_plugins();
-    spawn_portal_and_tracker(&mut app);
+    let portal = spawn_portal_and_tracker(&mut app);
 
     app.add_systems(
         Update,
@@ -414,12 +523,11 @@ fn test_melee_attack_emits_hit_message() {
                 max: 50.0,
             },
             SpawnIndex(0),
+            SpawnedBy(portal),
             Transform::from_xyz(100.0, 0.0, 0.0),
         ))
         .id();
 
-    app.world_mut().resource_mut::<PortalSpawnTracker>().0 = 1;
-
     app.update(); // Decision
 
     {
@@ -441,7 +549,7 @@ fn test_melee_attack_emits_hit_message() {
 fn test_projectile_collision_emits_collision_message() {
     let mut app = create_app_with_minimal_plugins();
 
-    app.add_systems(Update, projectile_collision);
+    app.add_systems(Update, (index_monsters, projectile_collision).chain());
 
     let source = app.world_mut().spawn_empty().id();
     let monster = app
@@ -462,6 +570,13 @@ fn test_projectile_collision_emits_collision_message() {
                 lifetime: Timer::from_seconds(5.0, TimerMode::Once),
                 source,
                 weapon: source, // Dummy weapon for test
+                target: monster,
+                hits: Vec::new(),
+                damage: 10.0,
+                damage_type: DamageType::Physical,
+                splash: None,
+                kind: WeaponKind::Ranged,
+                mastery_bonus: 1.0,
             },
             Transform::from_xyz(50.0, 0.0, 0.0),
         ))
@@ -484,3 +599,346 @@ fn test_projectile_collision_emits_collision_message() {
     assert_eq!(collision_msgs[0].source, source);
     assert_eq!(collision_msgs[0].target, monster);
 }
+
+#[test]
+fn test_melee_damage_mitigated_by_armor() {
+    let mut app = create_app_with_minimal_plugins();
+    app.add_systems(
+        Update,
+        (resolve_melee_damage, mitigate_damage, apply_damage).chain(),
+    );
+
+    let npc = app.world_mut().spawn(PlayerNpc).id();
+    let sword = app
+        .world_mut()
+        .spawn((Weapon, Melee, BaseDamage(10.0)))
+        .id();
+    let staff = app
+        .world_mut()
+        .spawn((Weapon, Melee, BaseDamage(10.0), DamageType::Void))
+        .id();
+    app.world_mut()
+        .entity_mut(npc)
+        .add_children(&[sword, staff]);
+
+    let monster = app
+        .world_mut()
+        .spawn((
+            Monster::default(),
+            Health {
+                current: 50.0,
+                max: 50.0,
+            },
+            Armor {
+                physical: 100.0,
+                magic: 0.0,
+                void: 0.0,
+            },
+        ))
+        .id();
+
+    // 100 physical armor halves the sword hit
+    app.world_mut().write_message(MeleeHitMessage {
+        attacker: sword,
+        target: monster,
+    });
+    app.update();
+    assert_eq!(app.world().get::<Health>(monster).unwrap().current, 45.0);
+
+    // Void damage ignores physical armor
+    app.world_mut().write_message(MeleeHitMessage {
+        attacker: staff,
+        target: monster,
+    });
+    app.update();
+    assert_eq!(app.world().get::<Health>(monster).unwrap().current, 35.0);
+}
+
+#[test]
+fn test_add_xp_handles_multiple_level_ups() {
+    let mut track = MasteryTrack::default();
+
+    // Level 1 needs 100, level 2 needs 200, level 3 needs 300
+    assert_eq!(track.add_xp(50.0), 0);
+    assert_eq!(track.add_xp(600.0), 3);
+    assert_eq!(track.level, 3);
+    assert_eq!(track.current_xp, 50.0);
+}
+
+#[test]
+fn test_mastery_bonus_applied_to_damage() {
+    let mut app = create_app_with_minimal_plugins();
+    app.add_systems(
+        Update,
+        (resolve_melee_damage, apply_mastery_bonus, apply_damage).chain(),
+    );
+
+    let npc = app
+        .world_mut()
+        .spawn((
+            PlayerNpc,
+            WeaponExpertise {
+                melee: MasteryTrack {
+                    level: 5,
+                    current_xp: 0.0,
+                },
+                ranged: MasteryTrack::default(),
+            },
+        ))
+        .id();
+    let sword = app
+        .world_mut()
+        .spawn((Weapon, Melee, BaseDamage(10.0)))
+        .id();
+    app.world_mut().entity_mut(npc).add_child(sword);
+
+    let monster = app
+        .world_mut()
+        .spawn((
+            Monster::default(),
+            Health {
+                current: 50.0,
+                max: 50.0,
+            },
+        ))
+        .id();
+
+    app.world_mut().write_message(MeleeHitMessage {
+        attacker: sword,
+        target: monster,
+    });
+    app.update();
+
+    // Level 5 melee mastery: 10 * 1.5
+    assert_eq!(app.world().get::<Health>(monster).unwrap().current, 35.0);
+}
+
+#[test]
+fn test_melee_attack_reports_mastery_level_up() {
+    let mut app = create_app_with_minimal_plugins();
+    let portal = spawn_portal_and_tracker(&mut app);
+    app.add_systems(
+        Update,
+        (
+            tick_weapon_cooldowns,
+            player_npc_decision_logic,
+            melee_attack_emit,
+        )
+            .chain(),
+    );
+
+    let npc = app
+        .world_mut()
+        .spawn((
+            PlayerNpc,
+            Target(None),
+            Transform::from_xyz(90.0, 0.0, 0.0),
+            Intent::Idle,
+            WeaponExpertise::default(),
+        ))
+        .id();
+    let child = app
+        .world_mut()
+        .spawn((
+            Weapon,
+            Melee,
+            BaseDamage(10.0),
+            ItemAttackRange(20.0),
+            WeaponCooldown {
+                timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+            },
+            // Enough for levels 1 and 2 in one hit
+            WeaponExpertiseXp(300.0),
+        ))
+        .id();
+    app.world_mut().entity_mut(npc).add_child(child);
+
+    app.world_mut().spawn((
+        Monster::default(),
+        Health {
+            current: 50.0,
+            max: 50.0,
+        },
+        SpawnIndex(0),
+        SpawnedBy(portal),
+        Transform::from_xyz(100.0, 0.0, 0.0),
+    ));
+
+    app.update(); // Decision
+    {
+        let mut time = app.world_mut().resource_mut::<Time>();
+        time.advance_by(std::time::Duration::from_secs_f32(1.1));
+    }
+    app.update(); // Attack logic
+
+    let level_ups: Vec<_> = app
+        .world_mut()
+        .resource_mut::<Messages<WeaponMasteryLeveledUp>>()
+        .drain()
+        .collect();
+    assert_eq!(level_ups.len(), 2);
+    assert_eq!(level_ups[0].kind, WeaponKind::Melee);
+    assert_eq!(level_ups[0].level, 1);
+    assert_eq!(level_ups[1].level, 2);
+    assert_eq!(level_ups[1].entity, npc);
+}
+
+#[test]
+fn test_attack_speed_drives_cooldown() {
+    let mut app = create_app_with_minimal_plugins();
+    app.add_systems(Update, sync_attack_speed_cooldown);
+
+    let bow = app.world_mut().spawn((Weapon, AttackSpeed(0.8))).id();
+    let sword = app
+        .world_mut()
+        .spawn((
+            Weapon,
+            AttackSpeed(2.0),
+            WeaponCooldown {
+                timer: Timer::from_seconds(1.5, TimerMode::Once),
+            },
+        ))
+        .id();
+    app.update();
+
+    let cooldown_secs = |app: &App, e: Entity| {
+        app.world()
+            .get::<WeaponCooldown>(e)
+            .unwrap()
+            .timer
+            .duration()
+    };
+    assert_eq!(cooldown_secs(&app, bow).as_secs_f32(), 1.25);
+    assert_eq!(cooldown_secs(&app, sword).as_secs_f32(), 0.5);
+
+    // Faster attacks after a buff
+    app.world_mut().get_mut::<AttackSpeed>(bow).unwrap().0 = 2.5;
+    app.update();
+    assert_eq!(cooldown_secs(&app, bow).as_secs_f32(), 0.4);
+}
+
+#[test]
+fn test_melee_area_of_effect_hits_monsters_in_arc() {
+    let mut app = create_app_with_minimal_plugins();
+    spawn_portal_and_tracker(&mut app);
+    app.add_systems(Update, (index_monsters, melee_attack_emit).chain());
+
+    let mut spawn_monster = |x: f32, y: f32| {
+        app.world_mut()
+            .spawn((Monster::default(), Transform::from_xyz(x, y, 0.0)))
+            .id()
+    };
+    let target = spawn_monster(30.0, 0.0);
+    let beside = spawn_monster(25.0, 10.0);
+    let behind = spawn_monster(-20.0, 0.0);
+    let too_far = spawn_monster(80.0, 0.0);
+
+    let npc = app
+        .world_mut()
+        .spawn((
+            PlayerNpc,
+            Transform::default(),
+            Intent::Attack(target),
+            WeaponExpertise::default(),
+        ))
+        .id();
+    // Ready to swing
+    let mut timer = Timer::from_seconds(1.0, TimerMode::Once);
+    timer.tick(std::time::Duration::from_secs(1));
+    let sword = app
+        .world_mut()
+        .spawn((
+            Weapon,
+            Melee,
+            BaseDamage(10.0),
+            ItemAttackRange(30.0),
+            WeaponCooldown { timer },
+            WeaponExpertiseXp::default(),
+            AreaOfEffect {
+                radius: 40.0,
+                angle: std::f32::consts::FRAC_PI_2,
+            },
+        ))
+        .id();
+    app.world_mut().entity_mut(npc).add_child(sword);
+
+    app.update();
+
+    let targets: Vec<Entity> = app
+        .world_mut()
+        .resource_mut::<Messages<MeleeHitMessage>>()
+        .drain()
+        .map(|msg| msg.target)
+        .collect();
+    assert_eq!(targets, vec![target, beside]);
+    assert!(!targets.contains(&behind));
+    assert!(!targets.contains(&too_far));
+}
+
+#[test]
+fn test_projectile_splash_damages_monsters_around_impact() {
+    let mut app = create_app_with_minimal_plugins();
+    app.add_systems(
+        Update,
+        (index_monsters, resolve_projectile_damage, apply_damage).chain(),
+    );
+
+    let mut spawn_monster = |x: f32| {
+        app.world_mut()
+            .spawn((
+                Monster::default(),
+                Health {
+                    current: 50.0,
+                    max: 50.0,
+                },
+                Transform::from_xyz(x, 0.0, 0.0),
+            ))
+            .id()
+    };
+    let hit = spawn_monster(100.0);
+    let near = spawn_monster(130.0);
+    let far = spawn_monster(200.0);
+
+    let npc = app.world_mut().spawn(PlayerNpc).id();
+    let bow = app
+        .world_mut()
+        .spawn((
+            Weapon,
+            Ranged,
+            BaseDamage(10.0),
+            AreaOfEffect {
+                radius: 50.0,
+                angle: 0.0,
+            },
+        ))
+        .id();
+    let projectile = app
+        .world_mut()
+        .spawn(Projectile {
+            velocity: Vec3::ZERO,
+            lifetime: Timer::from_seconds(5.0, TimerMode::Once),
+            source: npc,
+            weapon: bow,
+            target: hit,
+            hits: Vec::new(),
+            damage: 10.0,
+            damage_type: DamageType::Physical,
+            splash: Some(50.0),
+            kind: WeaponKind::Ranged,
+            mastery_bonus: 1.0,
+        })
+        .id();
+
+    app.world_mut().write_message(ProjectileCollisionMessage {
+        projectile,
+        source: npc,
+        target: hit,
+        location: Vec3::new(95.0, 0.0, 0.0),
+    });
+    app.update();
+
+    let health = |app: &App, e: Entity| app.world().get::<Health>(e).unwrap().current;
+    assert_eq!(health(&app, hit), 40.0);
+    assert_eq!(health(&app, near), 40.0);
+    assert_eq!(health(&app, far), 50.0);
+}
@@ -0,0 +1,160 @@
+use {
+    crate::*,
+    bevy::time::{Time, TimePlugin},
+    common::{MonsterIndex, ProjectileCollisionMessage},
+    items::{Chain, Homing, Pierce},
+    monsters::{index_monsters, Monster},
+    std::f32::consts::FRAC_PI_2,
+};
+
+fn setup_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins.build().disable::<TimePlugin>());
+    app.insert_resource(Time::<()>::default());
+    app.add_message::<ProjectileCollisionMessage>()
+        .init_resource::<MonsterIndex>();
+    app.add_systems(
+        Update,
+        (index_monsters, move_projectiles, projectile_collision).chain(),
+    );
+    app
+}
+
+fn spawn_monster(app: &mut App, x: f32, y: f32) -> Entity {
+    app.world_mut()
+        .spawn((
+            Monster::default(),
+            Transform::from_xyz(x, y, 0.0),
+            Sprite {
+                custom_size: Some(Vec2::splat(32.0)),
+                ..default()
+            },
+        ))
+        .id()
+}
+
+fn spawn_projectile(app: &mut App, x: f32, velocity: Vec3, target: Entity) -> Entity {
+    let source = app.world_mut().spawn_empty().id();
+    app.world_mut()
+        .spawn((
+            Projectile {
+                velocity,
+                lifetime: Timer::from_seconds(5.0, TimerMode::Once),
+                source,
+                weapon: source,
+                target,
+                hits: Vec::new(),
+                damage: 10.0,
+                damage_type: DamageType::Physical,
+                splash: None,
+                kind: WeaponKind::Ranged,
+                mastery_bonus: 1.0,
+            },
+            Transform::from_xyz(x, 0.0, 0.0),
+            Sprite {
+                custom_size: Some(Vec2::splat(8.0)),
+                ..default()
+            },
+        ))
+        .id()
+}
+
+fn advance(app: &mut App, secs: f32) {
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(std::time::Duration::from_secs_f32(secs));
+    app.update();
+}
+
+fn hits(app: &App, projectile: Entity) -> Vec<Entity> {
+    app.world().get::<Projectile>(projectile).unwrap().hits.clone()
+}
+
+#[test]
+fn test_hit_circle_comes_from_sprites() {
+    let mut app = setup_app();
+    let monster = spawn_monster(&mut app, 100.0, 0.0);
+    // Radii 16 + 4: touching at 19, missing at 21
+    let touching = spawn_projectile(&mut app, 81.0, Vec3::ZERO, monster);
+    let missing = spawn_projectile(&mut app, 121.0, Vec3::ZERO, monster);
+
+    app.update();
+
+    assert_eq!(hits(&app, touching), vec![monster]);
+    assert!(hits(&app, missing).is_empty());
+}
+
+#[test]
+fn test_homing_turns_toward_target() {
+    let mut app = setup_app();
+    let target = spawn_monster(&mut app, 0.0, 500.0);
+    let homing = spawn_projectile(&mut app, 0.0, Vec3::new(100.0, 0.0, 0.0), target);
+    app.world_mut()
+        .entity_mut(homing)
+        .insert(Homing { turn_rate: 1.0 });
+    let straight = spawn_projectile(&mut app, 0.0, Vec3::new(100.0, 0.0, 0.0), target);
+
+    advance(&mut app, 0.5);
+
+    // Limited by the turn rate, the target is a quarter turn away
+    let velocity = app.world().get::<Projectile>(homing).unwrap().velocity;
+    assert!((velocity.truncate().to_angle() - 0.5).abs() < 1e-4);
+    assert!((velocity.length() - 100.0).abs() < 1e-3);
+
+    // With enough time it ends up flying straight at the target
+    let position = app.world().get::<Transform>(homing).unwrap().translation;
+    let wanted = (Vec2::new(0.0, 500.0) - position.truncate()).to_angle();
+    advance(&mut app, 2.0);
+    let velocity = app.world().get::<Projectile>(homing).unwrap().velocity;
+    assert!((velocity.truncate().to_angle() - wanted).abs() < 1e-4);
+    assert!(wanted > FRAC_PI_2);
+
+    let velocity = app.world().get::<Projectile>(straight).unwrap().velocity;
+    assert_eq!(velocity, Vec3::new(100.0, 0.0, 0.0));
+}
+
+#[test]
+fn test_pierce_passes_through_monsters() {
+    let mut app = setup_app();
+    let first = spawn_monster(&mut app, 50.0, 0.0);
+    let second = spawn_monster(&mut app, 55.0, 0.0);
+    let third = spawn_monster(&mut app, 60.0, 0.0);
+    let projectile = spawn_projectile(&mut app, 50.0, Vec3::ZERO, first);
+    app.world_mut().entity_mut(projectile).insert(Pierce(1));
+
+    app.update();
+    assert_eq!(hits(&app, projectile), vec![first]);
+    assert!(app.world().get::<Transform>(projectile).is_some());
+
+    app.update();
+    assert_eq!(hits(&app, projectile), vec![first, second]);
+    assert_eq!(app.world().get::<Pierce>(projectile).unwrap().0, 0);
+    // Out of pierce, the projectile is spent
+    assert!(app.world().get::<Transform>(projectile).is_none());
+
+    app.update();
+    assert!(!hits(&app, projectile).contains(&third));
+}
+
+#[test]
+fn test_chain_bounces_to_nearest_unhit_monster() {
+    let mut app = setup_app();
+    let first = spawn_monster(&mut app, 0.0, 0.0);
+    let near = spawn_monster(&mut app, 0.0, 80.0);
+    spawn_monster(&mut app, 90.0, 0.0);
+    spawn_monster(&mut app, 0.0, 300.0);
+    let projectile = spawn_projectile(&mut app, 0.0, Vec3::new(50.0, 0.0, 0.0), first);
+    app.world_mut().entity_mut(projectile).insert(Chain {
+        bounces: 1,
+        range: 100.0,
+    });
+
+    app.update();
+
+    let state = app.world().get::<Projectile>(projectile).unwrap();
+    assert_eq!(state.hits, vec![first]);
+    assert_eq!(state.target, near);
+    assert!((state.velocity - Vec3::new(0.0, 50.0, 0.0)).length() < 1e-4);
+    assert_eq!(app.world().get::<Chain>(projectile).unwrap().bounces, 0);
+    assert!(app.world().get::<Transform>(projectile).is_some());
+}
@@ -0,0 +1,154 @@
+use {
+    crate::*,
+    bevy::{
+        scene::{DynamicScene, ScenePlugin},
+        time::{Time, TimePlugin},
+    },
+    monsters::{Health, Hostile},
+    std::time::Duration,
+};
+
+fn setup_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins.build().disable::<TimePlugin>())
+        .add_plugins(AssetPlugin::default())
+        .add_plugins(ScenePlugin);
+    app.insert_resource(Time::<()>::default());
+    app.register_type::<PlayerNpc>()
+        .register_type::<PlayerNpcId>()
+        .register_type::<Health>()
+        .register_type::<MasteryTrack>()
+        .register_type::<WeaponExpertise>();
+    app.init_resource::<PlayerNpcRespawn>()
+        .init_resource::<PendingPlayerNpcSpawns>();
+    app.add_systems(
+        Update,
+        (
+            respawn_player_npcs,
+            attach_spawned_player_npcs,
+            player_npc_death,
+        )
+            .chain(),
+    );
+    app
+}
+
+/// A bare player NPC at full health, the soldier prefab without its gear.
+fn npc_prefab(app: &mut App) -> Handle<DynamicScene> {
+    let mut world = World::new();
+    world.insert_resource(app.world().resource::<AppTypeRegistry>().clone());
+    world.spawn((
+        PlayerNpc,
+        Health {
+            current: 100.0,
+            max: 100.0,
+        },
+        WeaponExpertise::default(),
+    ));
+    let scene = DynamicScene::from_world(&world);
+    app.world_mut()
+        .resource_mut::<Assets<DynamicScene>>()
+        .add(scene)
+}
+
+fn spawn_npc(app: &mut App, prefab: &Handle<DynamicScene>, id: u32, melee_level: u32) -> Entity {
+    app.world_mut()
+        .spawn((
+            PlayerNpc,
+            PlayerNpcId(id),
+            PlayerNpcPrefab(prefab.clone()),
+            Health {
+                current: 100.0,
+                max: 100.0,
+            },
+            WeaponExpertise {
+                melee: MasteryTrack {
+                    level: melee_level,
+                    current_xp: 20.0,
+                },
+                ranged: MasteryTrack::default(),
+            },
+        ))
+        .id()
+}
+
+fn kill(app: &mut App, npc: Entity) {
+    app.world_mut().get_mut::<Health>(npc).unwrap().current = 0.0;
+}
+
+/// Runs one frame lasting `secs`, the frames after it last no time at all.
+fn advance(app: &mut App, secs: f32) {
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_secs_f32(secs));
+    app.update();
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::ZERO);
+}
+
+/// NPC brought back by a respawn, they carry the prefab they came from.
+fn find_npc(app: &mut App, id: u32) -> Option<Entity> {
+    app.world_mut()
+        .query_filtered::<(Entity, &PlayerNpcId), With<PlayerNpcPrefab>>()
+        .iter(app.world())
+        .find(|(_, npc_id)| npc_id.0 == id)
+        .map(|(entity, _)| entity)
+}
+
+fn wait_for_npc(app: &mut App, id: u32) -> Entity {
+    for _ in 0..10 {
+        if let Some(npc) = find_npc(app, id) {
+            return npc;
+        }
+        app.update();
+    }
+    panic!("player NPC {id} never respawned");
+}
+
+fn melee_level(app: &App, npc: Entity) -> u32 {
+    app.world().get::<WeaponExpertise>(npc).unwrap().melee.level
+}
+
+#[test]
+fn test_each_fallen_npc_respawns_on_its_own() {
+    let mut app = setup_app();
+    let prefab = npc_prefab(&mut app);
+    let first = spawn_npc(&mut app, &prefab, 0, 3);
+    let second = spawn_npc(&mut app, &prefab, 1, 5);
+    app.world_mut()
+        .entity_mut(first)
+        .insert(TargetingPolicy::LowestHealth);
+    assert!(
+        app.world().get::<Hostile>(first).is_some(),
+        "Monsters can target player NPCs"
+    );
+
+    kill(&mut app, first);
+    advance(&mut app, 0.0);
+    assert!(app.world().get_entity(first).is_err(), "Fallen NPC despawned");
+
+    advance(&mut app, 2.0);
+    kill(&mut app, second);
+    advance(&mut app, 0.0);
+    assert_eq!(app.world().resource::<PlayerNpcRespawn>().pending.len(), 2);
+
+    // Back while the other one is still down, with what it had
+    advance(&mut app, PLAYER_NPC_RESPAWN_SECS - 2.0);
+    let first_back = wait_for_npc(&mut app, 0);
+    assert_eq!(melee_level(&app, first_back), 3);
+    assert_eq!(
+        *app.world().get::<TargetingPolicy>(first_back).unwrap(),
+        TargetingPolicy::LowestHealth
+    );
+    assert_eq!(find_npc(&mut app, 1), None);
+
+    advance(&mut app, 2.0);
+    let second_back = wait_for_npc(&mut app, 1);
+    assert_eq!(melee_level(&app, second_back), 5);
+    assert_eq!(
+        *app.world().get::<TargetingPolicy>(second_back).unwrap(),
+        TargetingPolicy::default()
+    );
+    assert!(app.world().resource::<PlayerNpcRespawn>().pending.is_empty());
+}
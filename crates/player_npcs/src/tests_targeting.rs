@@ -0,0 +1,267 @@
+use {
+    crate::*,
+    common::Currencies,
+    items::{AttackRange as ItemAttackRange, BaseDamage, Melee},
+    monsters::{index_monsters, Health, Lifetime, Monster, SpawnIndex, SpawnedBy},
+    portal::PortalSpawnTracker,
+    std::time::Duration,
+};
+
+fn candidate(index: u32, distance: f32) -> TargetCandidate {
+    TargetCandidate {
+        entity: Entity::from_raw_u32(index).unwrap(),
+        distance,
+        health: 10.0,
+        reward: 1.0.into(),
+        lifetime_left: 10.0,
+        age: 0,
+    }
+}
+
+/// Each candidate wins exactly one policy.
+fn candidates() -> Vec<TargetCandidate> {
+    vec![
+        TargetCandidate {
+            health: 2.0,
+            ..candidate(1, 50.0)
+        },
+        TargetCandidate {
+            reward: 9.0.into(),
+            ..candidate(2, 60.0)
+        },
+        TargetCandidate {
+            lifetime_left: 0.5,
+            ..candidate(3, 70.0)
+        },
+        TargetCandidate {
+            age: 4,
+            ..candidate(4, 80.0)
+        },
+        candidate(5, 10.0),
+    ]
+}
+
+#[test]
+fn test_each_policy_picks_its_candidate() {
+    let expected = [
+        (TargetingPolicy::LowestHealth, 1),
+        (TargetingPolicy::HighestReward, 2),
+        (TargetingPolicy::AboutToExpire, 3),
+        (TargetingPolicy::Oldest, 4),
+        (TargetingPolicy::Nearest, 5),
+    ];
+
+    for (policy, index) in expected {
+        assert_eq!(
+            policy.pick(candidates()),
+            Entity::from_raw_u32(index),
+            "{policy:?}"
+        );
+    }
+}
+
+#[test]
+fn test_ties_go_to_nearest() {
+    let picked = TargetingPolicy::LowestHealth.pick([candidate(1, 30.0), candidate(2, 20.0)]);
+    assert_eq!(picked, Entity::from_raw_u32(2));
+    assert_eq!(TargetingPolicy::Oldest.pick([]), None);
+}
+
+#[test]
+fn test_next_policy_wraps_around() {
+    let mut policy = TargetingPolicy::default();
+    for _ in 0..TargetingPolicy::ALL.len() {
+        policy = policy.next();
+    }
+    assert_eq!(policy, TargetingPolicy::default());
+    assert_eq!(TargetingPolicy::Oldest.next(), TargetingPolicy::Nearest);
+}
+
+#[test]
+fn test_changing_policy_retargets() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<TargetClaims>()
+        .init_resource::<MonsterIndex>();
+    app.add_systems(Update, player_npc_decision_logic);
+
+    let portal = app.world_mut().spawn(PortalSpawnTracker(2)).id();
+    let npc = app
+        .world_mut()
+        .spawn((PlayerNpc, Target(None), Transform::default(), Intent::Idle))
+        .id();
+
+    let mut spawn_monster = |index, x, health, seconds_left| {
+        app.world_mut()
+            .spawn((
+                Monster::default(),
+                Health {
+                    current: health,
+                    max: 10.0,
+                },
+                Reward(Currencies::from(1.0)),
+                Lifetime {
+                    timer: Timer::from_seconds(seconds_left, TimerMode::Once),
+                },
+                SpawnIndex(index),
+                SpawnedBy(portal),
+                Transform::from_xyz(x, 0.0, 0.0),
+            ))
+            .id()
+    };
+    let oldest = spawn_monster(0, 200.0, 10.0, 30.0);
+    let weakest = spawn_monster(1, 100.0, 3.0, 20.0);
+
+    app.update();
+    assert_eq!(app.world().get::<Target>(npc).unwrap().0, Some(oldest));
+
+    // The current target is still alive, only the new policy triggers a retarget
+    *app.world_mut().get_mut::<TargetingPolicy>(npc).unwrap() = TargetingPolicy::LowestHealth;
+    app.update();
+    assert_eq!(app.world().get::<Target>(npc).unwrap().0, Some(weakest));
+}
+
+fn setup_claims_app() -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<TargetClaims>()
+        .init_resource::<MonsterIndex>();
+    app.add_systems(Update, player_npc_decision_logic);
+    let portal = app.world_mut().spawn(PortalSpawnTracker(2)).id();
+    (app, portal)
+}
+
+/// NPC at the origin with a melee weapon ready to swing for 10.
+fn spawn_swordsman(app: &mut App) -> Entity {
+    let mut timer = Timer::from_seconds(1.0, TimerMode::Once);
+    timer.tick(Duration::from_secs(1));
+    let sword = app
+        .world_mut()
+        .spawn((
+            Weapon,
+            Melee,
+            BaseDamage(10.0),
+            ItemAttackRange(50.0),
+            WeaponCooldown { timer },
+        ))
+        .id();
+    app.world_mut()
+        .spawn((PlayerNpc, Target(None), Transform::default(), Intent::Idle))
+        .add_child(sword)
+        .id()
+}
+
+fn spawn_claimable_monster(app: &mut App, portal: Entity, index: u32, health: f32) -> Entity {
+    app.world_mut()
+        .spawn((
+            Monster::default(),
+            Health {
+                current: health,
+                max: health,
+            },
+            SpawnIndex(index),
+            SpawnedBy(portal),
+            Transform::from_xyz(10.0, 0.0, 0.0),
+        ))
+        .id()
+}
+
+#[test]
+fn test_claims_cover_health() {
+    let target = Entity::from_raw_u32(1).unwrap();
+    let mut claims = TargetClaims::default();
+    assert!(!claims.covers(target, 0.0));
+
+    claims.claim(target, 4.0);
+    claims.claim(target, 4.0);
+    assert_eq!(claims.pending(target), 8.0);
+    assert!(claims.covers(target, 8.0));
+    assert!(!claims.covers(target, 8.5));
+}
+
+#[test]
+fn test_npcs_split_up_instead_of_overkilling() {
+    let (mut app, portal) = setup_claims_app();
+    let first = spawn_swordsman(&mut app);
+    let second = spawn_swordsman(&mut app);
+    // One swing is enough for the oldest monster
+    let oldest = spawn_claimable_monster(&mut app, portal, 0, 5.0);
+    let younger = spawn_claimable_monster(&mut app, portal, 1, 50.0);
+
+    app.update();
+
+    let mut targets = [first, second].map(|npc| app.world().get::<Target>(npc).unwrap().0);
+    targets.sort();
+    let mut expected = [Some(oldest), Some(younger)];
+    expected.sort();
+    assert_eq!(targets, expected);
+}
+
+#[test]
+fn test_projectile_in_flight_claims_its_target() {
+    let (mut app, portal) = setup_claims_app();
+    let npc = spawn_swordsman(&mut app);
+    let oldest = spawn_claimable_monster(&mut app, portal, 0, 5.0);
+    let younger = spawn_claimable_monster(&mut app, portal, 1, 50.0);
+
+    let bow = app.world_mut().spawn((Weapon, BaseDamage(10.0))).id();
+    app.world_mut().spawn((
+        Projectile {
+            velocity: Vec3::ZERO,
+            lifetime: Timer::from_seconds(5.0, TimerMode::Once),
+            source: npc,
+            weapon: bow,
+            target: oldest,
+            hits: Vec::new(),
+            damage: 10.0,
+            damage_type: DamageType::Physical,
+            splash: None,
+            kind: WeaponKind::Ranged,
+            mastery_bonus: 1.0,
+        },
+        Transform::default(),
+    ));
+
+    app.update();
+    assert_eq!(app.world().get::<Target>(npc).unwrap().0, Some(younger));
+
+    // Nothing left that isn't already taken care of
+    app.world_mut().despawn(younger);
+    app.update();
+    assert_eq!(app.world().get::<Target>(npc).unwrap().0, None);
+    assert!(matches!(
+        app.world().get::<Intent>(npc).unwrap(),
+        Intent::Idle
+    ));
+}
+
+#[test]
+fn test_nearest_policy_skips_covered_monsters() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<TargetClaims>()
+        .init_resource::<MonsterIndex>();
+    app.add_systems(Update, (index_monsters, player_npc_decision_logic).chain());
+    let portal = app.world_mut().spawn(PortalSpawnTracker(3)).id();
+
+    let npcs = [spawn_swordsman(&mut app), spawn_swordsman(&mut app)];
+    for npc in npcs {
+        app.world_mut().entity_mut(npc).insert(TargetingPolicy::Nearest);
+    }
+    let mut spawn_at = |index, x| {
+        let monster = spawn_claimable_monster(&mut app, portal, index, 5.0);
+        app.world_mut().get_mut::<Transform>(monster).unwrap().translation.x = x;
+        monster
+    };
+    let nearest = spawn_at(2, 10.0);
+    let second = spawn_at(1, 20.0);
+    spawn_at(0, 300.0);
+
+    app.update();
+
+    let mut targets = npcs.map(|npc| app.world().get::<Target>(npc).unwrap().0);
+    targets.sort();
+    let mut expected = [Some(nearest), Some(second)];
+    expected.sort();
+    assert_eq!(targets, expected);
+}
@@ -108,7 +108,7 @@ fn test_soldier_attack_timer_reset_on_retarget() {
                 current: 100.0,
                 max: 100.0,
             },
-            Reward(10.0),
+            Reward(10.0.into()),
             Speed(150.0),
         ))
         .id();
@@ -144,7 +144,7 @@ fn test_soldier_attack_timer_reset_on_retarget() {
                 current: 100.0,
                 max: 100.0,
             },
-            Reward(10.0),
+            Reward(10.0.into()),
             Speed(150.0),
         ))
         .id();
@@ -4,7 +4,11 @@ use {
     bevy::prelude::*,
     common::GameState,
     items::{AttackRange as ItemAttackRange, BaseDamage, Item, Melee, Ranged},
-    player_npcs::{MovementSpeed, PlayerNpc, Weapon, WeaponCooldown, WeaponExpertise},
+    monsters::Health,
+    player_npcs::{
+        EquipmentStats, MovementSpeed, PlayerNpc, TargetingPolicy, Weapon, WeaponCooldown,
+        WeaponExpertise,
+    },
 };
 
 pub struct PlayerNpcsUiPlugin;
@@ -28,6 +32,10 @@ struct SoldierUiRoot;
 #[derive(Component)]
 struct SoldierUiCloseButton;
 
+/// Cycles the targeting policy of the soldier the panel was opened for.
+#[derive(Component)]
+struct SoldierUiPolicyButton(Entity);
+
 // Attach observer to PlayerNpc entities
 fn attach_soldier_ui_observer(
     mut commands: Commands,
@@ -45,7 +53,14 @@ fn attach_soldier_ui_observer(
 fn on_soldier_click(
     trigger: On<Pointer<Click>>,
     mut commands: Commands,
-    soldier_query: Query<(&MovementSpeed, &Children, &WeaponExpertise)>,
+    soldier_query: Query<(
+        &MovementSpeed,
+        &Children,
+        &WeaponExpertise,
+        &EquipmentStats,
+        &TargetingPolicy,
+        Option<&Health>,
+    )>,
     weapon_query: Query<
         (
             &Item,
@@ -65,7 +80,9 @@ fn on_soldier_click(
     }
 
     let entity = trigger.entity;
-    if let Ok((speed, children, expertise)) = soldier_query.get(entity) {
+    if let Ok((speed, children, expertise, equipment_stats, policy, health)) =
+        soldier_query.get(entity)
+    {
         // Find Weapon
         let mut weapon_info = None;
         for &child in children {
@@ -88,15 +105,36 @@ fn on_soldier_click(
             }
         }
 
-        spawn_soldier_ui(&mut commands, speed.0, weapon_info, expertise);
+        let vitals = SoldierVitals {
+            health: health.map(|health| (health.current, health.max)),
+            defense: equipment_stats.defense,
+            movement_speed: equipment_stats.effective_speed(speed.0),
+        };
+        spawn_soldier_ui(
+            &mut commands,
+            entity,
+            vitals,
+            weapon_info,
+            expertise,
+            *policy,
+        );
     }
 }
 
+/// Soldier stats after equipment is applied.
+struct SoldierVitals {
+    health: Option<(f32, f32)>, // Current, Max
+    defense: f32,
+    movement_speed: f32,
+}
+
 fn spawn_soldier_ui(
     commands: &mut Commands,
-    movement_speed: f32,
+    soldier: Entity,
+    vitals: SoldierVitals,
     weapon_info: Option<(String, &str, f32, f32, f32)>, // Name, Type, Damage, Range, Cooldown
     expertise: &WeaponExpertise,
+    policy: TargetingPolicy,
 ) {
     commands
         .spawn((
@@ -144,25 +182,59 @@ fn spawn_soldier_ui(
                     ));
 
                     // Basic Stats
-                    // Inlined logic for spawn_stat_row("Movement Speed:", &format!("{:.1}", movement_speed));
-                    p.spawn((Node {
-                        width: Val::Percent(100.0),
-                        flex_direction: FlexDirection::Row,
-                        justify_content: JustifyContent::SpaceBetween,
-                        ..default()
-                    },))
-                        .with_children(|row| {
-                            row.spawn((
-                                Text::new("Movement Speed:"),
-                                TextFont::default(),
-                                TextColor(Color::srgb(0.8, 0.8, 0.8)),
-                            ));
-                            row.spawn((
-                                Text::new(format!("{:.1}", movement_speed)),
-                                TextFont::default(),
-                                TextColor(Color::WHITE),
-                            ));
-                        });
+                    let health = vitals
+                        .health
+                        .map(|(current, max)| format!("{:.0}/{:.0}", current, max))
+                        .unwrap_or_else(|| "-".to_string());
+                    let basic_stats = [
+                        ("Health:", health),
+                        ("Defense:", format!("{:.0}", vitals.defense)),
+                        ("Movement Speed:", format!("{:.1}", vitals.movement_speed)),
+                    ];
+
+                    for (label, value) in basic_stats {
+                        p.spawn((Node {
+                            width: Val::Percent(100.0),
+                            flex_direction: FlexDirection::Row,
+                            justify_content: JustifyContent::SpaceBetween,
+                            ..default()
+                        },))
+                            .with_children(|row| {
+                                row.spawn((
+                                    Text::new(label),
+                                    TextFont::default(),
+                                    TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                                ));
+                                row.spawn((
+                                    Text::new(value),
+                                    TextFont::default(),
+                                    TextColor(Color::WHITE),
+                                ));
+                            });
+                    }
+
+                    // Targeting Policy
+                    p.spawn((
+                        Button,
+                        Node {
+                            width: Val::Percent(100.0),
+                            height: Val::Px(30.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        BackgroundColor(Color::hsla(210.0, 0.6, 0.4, 1.0)),
+                        BorderRadius::all(Val::Px(5.0)),
+                        SoldierUiPolicyButton(soldier),
+                    ))
+                    .observe(on_policy_click)
+                    .with_children(|btn| {
+                        btn.spawn((
+                            Text::new(policy_label(policy)),
+                            TextFont::default(),
+                            TextColor(Color::WHITE),
+                        ));
+                    });
 
                     // Weapon Section
                     if let Some((name, w_type, damage, range, cooldown)) = weapon_info {
@@ -332,6 +404,31 @@ fn on_scrim_click(
     }
 }
 
+fn policy_label(policy: TargetingPolicy) -> String {
+    format!("Targeting: {}", policy.label())
+}
+
+fn on_policy_click(
+    trigger: On<Pointer<Click>>,
+    button_query: Query<(&SoldierUiPolicyButton, &Children)>,
+    mut soldier_query: Query<&mut TargetingPolicy>,
+    mut text_query: Query<&mut Text>,
+) {
+    let Ok((button, children)) = button_query.get(trigger.entity) else {
+        return;
+    };
+    let Ok(mut policy) = soldier_query.get_mut(button.0) else {
+        return;
+    };
+
+    *policy = policy.next();
+    for child in children.iter() {
+        if let Ok(mut text) = text_query.get_mut(child) {
+            **text = policy_label(*policy);
+        }
+    }
+}
+
 // Block clicks from propagating
 fn block_click(mut trigger: On<Pointer<Click>>) {
     trigger.propagate(false);
@@ -418,14 +515,31 @@ mod tests {
         // Test spawning UI manually
         spawn_soldier_ui(
             &mut app.world_mut().commands(),
-            100.0,
+            soldier,
+            SoldierVitals {
+                health: Some((80.0, 100.0)),
+                defense: 25.0,
+                movement_speed: 90.0,
+            },
             Some(("Test Sword".to_string(), "Melee", 10.0, 30.0, 1.0)),
             &WeaponExpertise::default(),
+            TargetingPolicy::Nearest,
         );
         app.update();
 
         // Check if UI Root exists
         let mut ui_roots = app.world_mut().query::<&SoldierUiRoot>();
         assert_eq!(ui_roots.iter(app.world()).count(), 1);
+
+        let texts: Vec<String> = app
+            .world_mut()
+            .query::<&Text>()
+            .iter(app.world())
+            .map(|text| text.0.clone())
+            .collect();
+        assert!(texts.contains(&"80/100".to_string()));
+        assert!(texts.contains(&"25".to_string()));
+        assert!(texts.contains(&"90.0".to_string()));
+        assert!(texts.contains(&"Targeting: Nearest".to_string()));
     }
 }
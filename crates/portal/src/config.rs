@@ -0,0 +1,291 @@
+use {
+    bevy::prelude::*,
+    common::{
+        components::{
+            BaseMonsterArmor, BaseMonsterAttack, BaseMonsterHealth, BaseMonsterLifetime,
+            BaseMonsterReward, BaseMonsterSpeed, PortalId, PortalLevel, PortalRoot, PortalSpawner,
+            PortalTopOffset, ScavengerPenalty, UpgradeCost, UpgradePrerequisites, UpgradeSlot,
+        },
+        GrowthStrategy, Prerequisite, PriceStrategy, UpgradeableStat,
+    },
+    serde::Deserialize,
+    std::{collections::BTreeMap, time::Duration},
+};
+
+// Configs
+
+/// Everything needed to spawn a portal, loaded from `*.portal.ron`.
+#[derive(Deserialize, Asset, Clone, Debug, Reflect)]
+pub struct PortalConfig {
+    /// Level the portal starts at.
+    #[serde(default)]
+    pub level: u32,
+    pub level_up_price: PriceStrategy,
+    /// Distance between the top edge of the window and the portal.
+    pub portal_top_offset: f32,
+    pub scavenger_penalty_coef: f32,
+    pub level_scaled_stats: LevelScaledStats,
+    /// Upgrade slot name -> how its value and price grow.
+    #[serde(default)]
+    pub upgrades: BTreeMap<String, UpgradeConfig>,
+}
+
+/// Stats of the spawned monsters, scaled by the active portal level.
+#[derive(Deserialize, Clone, Debug, Reflect)]
+pub struct LevelScaledStats {
+    pub void_shards_reward: GrowthStrategy,
+    /// Seconds between two spawns.
+    pub spawn_timer: GrowthStrategy,
+    pub monster_health: GrowthStrategy,
+    pub base_monster_speed: GrowthStrategy,
+    pub base_monster_lifetime: GrowthStrategy,
+    #[serde(default)]
+    pub monster_armor: GrowthStrategy,
+    /// Portals without an attack spawn harmless monsters.
+    #[serde(default)]
+    pub monster_attack: Option<MonsterAttackConfig>,
+}
+
+#[derive(Deserialize, Clone, Debug, Reflect)]
+pub struct MonsterAttackConfig {
+    pub damage: GrowthStrategy,
+    pub range: GrowthStrategy,
+    pub cooldown: GrowthStrategy,
+}
+
+#[derive(Deserialize, Clone, Debug, Reflect)]
+pub struct UpgradeConfig {
+    pub value: GrowthStrategy,
+    pub price: PriceStrategy,
+    /// The slot stays locked until all of these hold.
+    #[serde(default)]
+    pub requires: Vec<Prerequisite>,
+}
+
+impl PortalConfig {
+    /// Components derived from the config alone, independent of the portal's progress.
+    fn static_components(&self) -> impl Bundle {
+        let stats = &self.level_scaled_stats;
+        (
+            PortalTopOffset(self.portal_top_offset),
+            ScavengerPenalty(self.scavenger_penalty_coef),
+            BaseMonsterReward(stats.void_shards_reward.clone()),
+            BaseMonsterHealth(stats.monster_health.clone()),
+            BaseMonsterSpeed(stats.base_monster_speed.clone()),
+            BaseMonsterLifetime(stats.base_monster_lifetime.clone()),
+            BaseMonsterArmor(stats.monster_armor.clone()),
+        )
+    }
+
+    fn monster_attack(&self) -> Option<BaseMonsterAttack> {
+        self.level_scaled_stats
+            .monster_attack
+            .as_ref()
+            .map(|attack| BaseMonsterAttack {
+                damage: attack.damage.clone(),
+                range: attack.range.clone(),
+                cooldown: attack.cooldown.clone(),
+            })
+    }
+}
+
+// Components
+
+/// Config a portal was built from, so edits to the file reach the live portal.
+#[derive(Component, Debug, Clone)]
+pub struct PortalSource(pub Handle<PortalConfig>);
+
+// Resources
+
+/// Hands every spawned portal its own [`PortalId`], in spawn order.
+#[derive(Resource, Default, Debug)]
+pub struct NextPortalId(pub u32);
+
+impl NextPortalId {
+    pub fn take(&mut self) -> u32 {
+        let id = self.0;
+        self.0 += 1;
+        id
+    }
+}
+
+// Builder
+
+/// Spawns a `PortalRoot` with its `UpgradeSlot` children from a [`PortalConfig`].
+pub struct PortalBuilder<'a> {
+    config: &'a PortalConfig,
+    id: PortalId,
+    source: Option<Handle<PortalConfig>>,
+    translation: Vec3,
+}
+
+impl<'a> PortalBuilder<'a> {
+    pub fn new(config: &'a PortalConfig) -> Self {
+        Self {
+            config,
+            id: PortalId::default(),
+            source: None,
+            translation: Vec3::ZERO,
+        }
+    }
+
+    /// Key of the portal in save files, `0` unless set. Take it from
+    /// [`NextPortalId`] so no two portals share one.
+    pub fn with_id(mut self, id: u32) -> Self {
+        self.id = PortalId(id);
+        self
+    }
+
+    /// Handle of `config`, lets the portal follow changes to the file.
+    pub fn with_source(mut self, handle: Handle<PortalConfig>) -> Self {
+        self.source = Some(handle);
+        self
+    }
+
+    /// Starting position, `layout_portal` moves the portal into its slot afterwards.
+    pub fn with_translation(mut self, translation: Vec3) -> Self {
+        self.translation = translation;
+        self
+    }
+
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        let config = self.config;
+        let spawn_timer = &config.level_scaled_stats.spawn_timer;
+        let level = config.level as f32;
+
+        let mut portal = commands.spawn((
+            PortalRoot,
+            self.id,
+            Name::new("Portal"),
+            Transform::from_translation(self.translation),
+            Sprite {
+                color: Color::srgb(0.5, 0.0, 0.5),
+                custom_size: Some(Vec2::new(16.0, 32.0)),
+                ..default()
+            },
+            Pickable {
+                should_block_lower: true,
+                is_hoverable: true,
+            },
+            PortalLevel {
+                active: config.level,
+                max_unlocked: config.level,
+            },
+            UpgradeCost {
+                strategy: config.level_up_price.clone(),
+                current_price: config.level_up_price.calculate(level),
+            },
+            PortalSpawner {
+                timer: Timer::from_seconds(spawn_timer.calculate(level), TimerMode::Repeating),
+                interval_strategy: spawn_timer.clone(),
+            },
+            config.static_components(),
+        ));
+
+        if let Some(attack) = config.monster_attack() {
+            portal.insert(attack);
+        }
+        if let Some(source) = self.source {
+            portal.insert(PortalSource(source));
+        }
+
+        portal.with_children(|parent| {
+            for (name, upgrade) in &config.upgrades {
+                parent.spawn(upgrade_slot(name, upgrade));
+            }
+        });
+
+        portal.id()
+    }
+}
+
+fn upgrade_slot(name: &str, upgrade: &UpgradeConfig) -> impl Bundle {
+    (
+        UpgradeSlot {
+            name: name.to_string(),
+        },
+        UpgradeableStat::new(upgrade.value.clone(), upgrade.price.clone()),
+        UpgradePrerequisites(upgrade.requires.clone()),
+    )
+}
+
+// Systems
+
+/// Re-applies edited portal configs to the portals built from them. Levels and
+/// purchased upgrades are kept, everything derived from them is recalculated.
+pub fn reload_portal_configs(
+    mut commands: Commands,
+    mut config_events: MessageReader<AssetEvent<PortalConfig>>,
+    configs: Res<Assets<PortalConfig>>,
+    mut portal_query: Query<
+        (
+            Entity,
+            &PortalSource,
+            &PortalLevel,
+            &mut UpgradeCost,
+            &mut PortalSpawner,
+            Option<&Children>,
+        ),
+        With<PortalRoot>,
+    >,
+    mut upgrade_query: Query<(&UpgradeSlot, &mut UpgradeableStat)>,
+) {
+    let modified: Vec<AssetId<PortalConfig>> = config_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+    if modified.is_empty() {
+        return;
+    }
+
+    for (entity, source, level, mut cost, mut spawner, children) in portal_query.iter_mut() {
+        if !modified.contains(&source.0.id()) {
+            continue;
+        }
+        let Some(config) = configs.get(&source.0) else {
+            continue;
+        };
+
+        cost.strategy = config.level_up_price.clone();
+        cost.current_price = cost.strategy.calculate(level.max_unlocked as f32);
+
+        spawner.interval_strategy = config.level_scaled_stats.spawn_timer.clone();
+        let new_time = spawner.interval_strategy.calculate(level.active as f32);
+        spawner
+            .timer
+            .set_duration(Duration::from_secs_f32(new_time));
+
+        let mut missing: BTreeMap<&String, &UpgradeConfig> = config.upgrades.iter().collect();
+        for child in children.into_iter().flat_map(|children| children.iter()) {
+            let Ok((slot, mut stat)) = upgrade_query.get_mut(child) else {
+                continue;
+            };
+            let Some(upgrade) = missing.remove(&slot.name) else {
+                continue;
+            };
+            stat.value_strategy = upgrade.value.clone();
+            stat.price_strategy = upgrade.price.clone();
+            stat.recalculate();
+            commands
+                .entity(child)
+                .insert(UpgradePrerequisites(upgrade.requires.clone()));
+        }
+
+        let mut portal = commands.entity(entity);
+        portal.insert(config.static_components());
+        match config.monster_attack() {
+            Some(attack) => portal.insert(attack),
+            None => portal.remove::<BaseMonsterAttack>(),
+        };
+
+        // Upgrades added to the file start at level 0
+        for (name, upgrade) in missing {
+            portal.with_child(upgrade_slot(name, upgrade));
+        }
+
+        info!(%entity, "Portal config reloaded");
+    }
+}
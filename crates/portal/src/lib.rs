@@ -1,28 +1,28 @@
 #![allow(clippy::type_complexity)]
 #![allow(clippy::too_many_arguments)]
 
-pub use common::components::*;
 use {
     bevy::prelude::*,
+    bevy_common_assets::ron::RonAssetPlugin,
     common::{
-        ChangeActiveLevel, GameState, RequestUpgrade, SpawnMonsterRequest, UpgradePortal,
-        UpgradeableStat,
+        ChangeActiveLevel, GameState, RequestUpgrade, SetPortalLevel, SpawnMonsterRequest,
+        UpgradePortal, UpgradeableStat,
     },
     monster_factory::SpawnMonsterEvent,
-    monsters::{AvailableEnemies, Monster},
+    monsters::{AvailableEnemies, Monster, MonsterConfig, PortalMonsters},
     rand::Rng,
     wallet::Wallet,
 };
+pub use {common::components::*, config::*};
 
-// No longer exporting local components
-// mod components;
-// pub use components::*;
+mod config;
 
 pub struct PortalPlugin;
 
 impl Plugin for PortalPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<PortalSpawnTracker>();
+        app.add_plugins(RonAssetPlugin::<PortalConfig>::new(&["portal.ron"]));
+        app.init_resource::<NextPortalId>();
 
         app.add_systems(
             Update,
@@ -32,33 +32,42 @@ impl Plugin for PortalPlugin {
                 handle_portal_upgrade,
                 handle_generic_upgrades,
                 handle_active_level_change,
+                handle_set_portal_level,
+                reload_portal_configs,
+                update_upgrade_locks
+                    .after(handle_portal_upgrade)
+                    .after(handle_generic_upgrades)
+                    .after(handle_set_portal_level),
             )
                 .run_if(in_state(GameState::Playing)),
         );
     }
 }
 
-// Resources
-#[derive(Resource, Default)]
-pub struct PortalSpawnTracker(pub u32);
-
 // Systems
 
 // A. Layout
+/// Spreads portals evenly across the window width in `PortalId` order, each
+/// `PortalTopOffset` below the top edge.
 pub fn layout_portal(
     window_query: Query<&Window, With<bevy::window::PrimaryWindow>>,
-    mut portal_query: Query<&mut Transform, (With<PortalRoot>, Changed<Transform>)>,
+    mut portal_query: Query<(&PortalId, &mut Transform, &PortalTopOffset), With<PortalRoot>>,
 ) {
     let Some(window) = window_query.iter().next() else {
         return;
     };
 
-    // Hardcoded offset as per plan/requirements
-    let top_offset = 100.0;
-    let portal_y = (window.height() / 2.0) - top_offset;
-
-    for mut transform in portal_query.iter_mut() {
-        if (transform.translation.y - portal_y).abs() > 0.01 {
+    let mut portals: Vec<_> = portal_query.iter_mut().collect();
+    portals.sort_by_key(|(id, ..)| id.0);
+
+    let slot_width = window.width() / portals.len().max(1) as f32;
+    for (index, (_, mut transform, top_offset)) in portals.into_iter().enumerate() {
+        let portal_y = (window.height() / 2.0) - top_offset.0;
+        let portal_x = -window.width() / 2.0 + slot_width * (index as f32 + 0.5);
+        let target = Vec2::new(portal_x, portal_y);
+        // Only write on change so `Changed<Transform>` stays meaningful
+        if transform.translation.truncate().distance(target) > 0.01 {
+            transform.translation.x = portal_x;
             transform.translation.y = portal_y;
         }
     }
@@ -67,15 +76,18 @@ pub fn layout_portal(
 // B. Tick Logic
 pub fn portal_tick_logic(
     time: Res<Time>,
-    mut portal_query: Query<(Entity, &mut PortalSpawner, &Children)>,
+    mut portal_query: Query<(
+        Entity,
+        &mut PortalSpawner,
+        &Children,
+        Option<&PortalMonsters>,
+    )>,
     upgrade_query: Query<(&UpgradeSlot, &UpgradeableStat)>,
     monster_query: Query<(), With<Monster>>,
     available_monsters: Res<AvailableEnemies>,
     mut spawn_events: MessageWriter<SpawnMonsterRequest>,
 ) {
-    let current_monster_count = monster_query.iter().count();
-
-    for (entity, mut spawner, children) in portal_query.iter_mut() {
+    for (entity, mut spawner, children, portal_monsters) in portal_query.iter_mut() {
         spawner.timer.tick(time.delta());
 
         if spawner.timer.just_finished() {
@@ -90,10 +102,21 @@ pub fn portal_tick_logic(
                 .filter_map(|child| upgrade_query.get(child).ok())
                 .find(|(slot, _)| slot.name == "Capacity");
 
+            // Dying monsters lose `Monster` but stay linked until despawned
+            let current_monster_count = portal_monsters
+                .map(|monsters| {
+                    monsters
+                        .iter()
+                        .filter(|monster| monster_query.contains(*monster))
+                        .count()
+                })
+                .unwrap_or_default();
+
             if let Some((_, cap_stat)) = capacity {
                 if current_monster_count < cap_stat.value as usize {
                     spawn_events.write(SpawnMonsterRequest {
                         portal_entity: entity,
+                        monster: None,
                     });
                 }
             }
@@ -105,9 +128,10 @@ pub fn portal_tick_logic(
 pub fn portal_spawn_logic(
     mut events: MessageReader<SpawnMonsterRequest>,
     mut monster_events: MessageWriter<SpawnMonsterEvent>,
-    portal_query: Query<(
+    mut portal_query: Query<(
         &Transform,
         &PortalLevel,
+        &mut PortalSpawnTracker,
         &BaseMonsterHealth,
         &BaseMonsterReward,
         &BaseMonsterSpeed,
@@ -115,7 +139,7 @@ pub fn portal_spawn_logic(
         &Children,
         Option<&ScavengerPenalty>,
     )>,
-    mut spawn_tracker: ResMut<PortalSpawnTracker>,
+    available_monsters: Res<AvailableEnemies>,
     window_query: Query<&Window, With<bevy::window::PrimaryWindow>>,
 ) {
     if events.is_empty() {
@@ -128,24 +152,46 @@ pub fn portal_spawn_logic(
     let half_width = window.width() / 2.0;
     let half_height = window.height() / 2.0;
 
+    let mut rng = rand::rng();
+
     for request in events.read() {
         // Validation query to ensure portal components exist, but hydration logic moved to monster_factory.
-        if portal_query.get(request.portal_entity).is_err() {
+        let Ok((_, level, mut spawn_tracker, ..)) = portal_query.get_mut(request.portal_entity)
+        else {
             continue;
         };
 
+        // Prefabs requested without a config still spawn, walking straight
+        let (asset_path, config) = match &request.monster {
+            Some(prefab) => (
+                prefab.clone(),
+                available_monsters
+                    .0
+                    .iter()
+                    .find(|config| config.prefab == *prefab),
+            ),
+            None => match pick_monster(&available_monsters.0, level.active, &mut rng) {
+                Some(config) => (config.prefab.clone(), Some(config)),
+                None => {
+                    warn!("No monster config allowed at portal level {}", level.active);
+                    continue;
+                }
+            },
+        };
+
         // Random target position calculation
-        let mut rng = rand::rng();
         let target_x = rng.random_range(-half_width..half_width);
         let target_y = rng.random_range(-half_height..half_height);
         let target_position = Vec2::new(target_x, target_y);
 
         // Emit event with minimal data
         monster_events.write(SpawnMonsterEvent {
-            asset_path: "prefabs/monsters/goblin.scn.ron".to_string(),
+            asset_path,
             portal_entity: request.portal_entity,
             spawn_index: spawn_tracker.0,
             target_position,
+            movement: config.map(|config| config.movement).unwrap_or_default(),
+            return_to_portal: config.and_then(|config| config.return_to_portal),
         });
 
         spawn_tracker.0 = spawn_tracker.0.wrapping_add(1);
@@ -156,18 +202,49 @@ pub fn portal_spawn_logic(
     }
 }
 
+/// Weighted random pick among the configs allowed at `portal_level`.
+/// Returns `None` when no config is allowed or all of them have zero weight.
+pub fn pick_monster<'a>(
+    configs: &'a [MonsterConfig],
+    portal_level: u32,
+    rng: &mut impl Rng,
+) -> Option<&'a MonsterConfig> {
+    let eligible: Vec<&MonsterConfig> = configs
+        .iter()
+        .filter(|config| config.min_portal_level <= portal_level && config.spawn_weight > 0.0)
+        .collect();
+
+    let total_weight: f32 = eligible.iter().map(|config| config.spawn_weight).sum();
+    if total_weight <= 0.0 {
+        return None;
+    }
+
+    let mut roll = rng.random_range(0.0..total_weight);
+    for config in &eligible {
+        if roll < config.spawn_weight {
+            return Some(config);
+        }
+        roll -= config.spawn_weight;
+    }
+
+    // Float rounding can leave a tiny remainder past the last weight
+    eligible.last().copied()
+}
+
 // D. Upgrade Portal
 pub fn handle_portal_upgrade(
     mut events: MessageReader<UpgradePortal>,
     mut portal_query: Query<(&mut PortalLevel, &mut UpgradeCost, &mut PortalSpawner)>,
     mut wallet: ResMut<Wallet>,
 ) {
-    for _ in events.read() {
-        if let Some((mut level, mut cost, mut spawner)) = portal_query.iter_mut().next() {
-            if wallet.void_shards >= cost.current_price {
-                wallet.void_shards -= cost.current_price;
+    for event in events.read() {
+        if let Ok((mut level, mut cost, mut spawner)) = portal_query.get_mut(event.portal_entity) {
+            let from_level = level.max_unlocked as f32;
+            let levels = event.amount.levels(&cost.strategy, from_level, &wallet.balances);
+            let price = cost.strategy.cumulative_cost(from_level, levels);
 
-                level.max_unlocked += 1;
+            if levels > 0 && wallet.spend(&price) {
+                level.max_unlocked = level.max_unlocked.saturating_add(levels);
                 level.active = level.max_unlocked; // Auto-snap
 
                 // Recalculate Price
@@ -180,11 +257,11 @@ pub fn handle_portal_upgrade(
                     .set_duration(std::time::Duration::from_secs_f32(new_time));
 
                 info!(
-                    "Portal upgraded to Max Level {}. New Price: {}",
-                    level.max_unlocked, cost.current_price
+                    "Portal {:?} upgraded to Max Level {}. New Price: {}",
+                    event.portal_entity, level.max_unlocked, cost.current_price
                 );
             } else {
-                warn!("Not enough shards to upgrade portal!");
+                warn!("Not enough currency to upgrade portal (Cost: {})", price);
             }
         }
     }
@@ -215,27 +292,73 @@ pub fn handle_active_level_change(
     }
 }
 
+/// Free level change, unlike [`handle_portal_upgrade`] the level may also go down.
+pub fn handle_set_portal_level(
+    mut events: MessageReader<SetPortalLevel>,
+    mut portal_query: Query<(&mut PortalLevel, &mut UpgradeCost, &mut PortalSpawner)>,
+) {
+    for event in events.read() {
+        let Ok((mut level, mut cost, mut spawner)) = portal_query.get_mut(event.portal_entity)
+        else {
+            warn!("Portal {:?} not found!", event.portal_entity);
+            continue;
+        };
+
+        level.max_unlocked = event.level;
+        level.active = event.level;
+        cost.current_price = cost.strategy.calculate(event.level as f32);
+
+        let new_time = spawner.interval_strategy.calculate(event.level as f32);
+        spawner
+            .timer
+            .set_duration(std::time::Duration::from_secs_f32(new_time));
+
+        info!("Portal {:?} set to level {}", event.portal_entity, event.level);
+    }
+}
+
 // F. Generic Upgrades
 pub fn handle_generic_upgrades(
     mut events: MessageReader<RequestUpgrade>,
     mut upgrade_query: Query<(&mut UpgradeableStat, &UpgradeSlot)>,
+    prerequisite_query: Query<(&UpgradePrerequisites, &ChildOf)>,
+    portal_query: Query<(&PortalLevel, &Children)>,
     mut wallet: ResMut<Wallet>,
 ) {
     for event in events.read() {
+        if let Ok((prerequisites, parent)) = prerequisite_query.get(event.upgrade_entity) {
+            let met = portal_query
+                .get(parent.parent())
+                .is_ok_and(|(level, siblings)| {
+                    prerequisites.all_met(level.max_unlocked, |name| {
+                        siblings
+                            .iter()
+                            .filter_map(|sibling| upgrade_query.get(sibling).ok())
+                            .find_map(|(stat, slot)| (slot.name == name).then_some(stat.level))
+                    })
+                });
+            if !met {
+                warn!(
+                    "Upgrade {:?} is locked, prerequisites not met",
+                    event.upgrade_entity
+                );
+                continue;
+            }
+        }
+
         if let Ok((mut stat, slot)) = upgrade_query.get_mut(event.upgrade_entity) {
-            if wallet.void_shards >= stat.price {
-                wallet.void_shards -= stat.price;
-                stat.upgrade();
+            let levels = event.amount.levels(&stat.price_strategy, stat.level, &wallet.balances);
+            let price = stat.price_of(levels);
+
+            if levels > 0 && wallet.spend(&price) {
+                stat.upgrade_by(levels);
 
                 info!(
-                    "Upgrade '{}' upgraded to {}. New Price: {}",
-                    slot.name, stat.value, stat.price
+                    "Upgrade '{}' upgraded {} level(s) to {}. New Price: {}",
+                    slot.name, levels, stat.value, stat.price
                 );
             } else {
-                warn!(
-                    "Not enough shards to upgrade '{}' (Cost: {})",
-                    slot.name, stat.price
-                );
+                warn!("Not enough currency to upgrade '{}' (Cost: {})", slot.name, price);
             }
         } else {
             warn!("Upgrade entity {:?} not found!", event.upgrade_entity);
@@ -243,5 +366,36 @@ pub fn handle_generic_upgrades(
     }
 }
 
+// G. Upgrade Locks
+/// Keeps `LockedFeature` on exactly the upgrade slots whose prerequisites are unmet.
+/// Portal level prerequisites check the highest unlocked level.
+pub fn update_upgrade_locks(
+    mut commands: Commands,
+    slot_query: Query<(Entity, &UpgradePrerequisites, &ChildOf, Has<LockedFeature>)>,
+    portal_query: Query<(&PortalLevel, &Children)>,
+    upgrade_query: Query<(&UpgradeSlot, &UpgradeableStat)>,
+) {
+    for (entity, prerequisites, parent, locked) in slot_query.iter() {
+        let Ok((level, siblings)) = portal_query.get(parent.parent()) else {
+            continue;
+        };
+
+        let met = prerequisites.all_met(level.max_unlocked, |name| {
+            siblings
+                .iter()
+                .filter_map(|sibling| upgrade_query.get(sibling).ok())
+                .find_map(|(slot, stat)| (slot.name == name).then_some(stat.level))
+        });
+
+        if met && locked {
+            commands.entity(entity).remove::<LockedFeature>();
+        } else if !met && !locked {
+            commands.entity(entity).insert(LockedFeature);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_config;
 #[cfg(test)]
 mod tests_mechanics;
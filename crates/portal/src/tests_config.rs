@@ -0,0 +1,214 @@
+use {
+    crate::*,
+    bevy_common_assets::ron::RonAssetPlugin,
+    common::{GrowthStrategy, UpgradeableStat},
+    std::time::Duration,
+};
+
+fn load_main_config() -> (App, PortalConfig) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(AssetPlugin {
+            file_path: "../../assets".to_string(),
+            ..default()
+        })
+        .add_plugins(RonAssetPlugin::<PortalConfig>::new(&["portal.ron"]));
+    app.init_resource::<NextPortalId>();
+
+    let handle: Handle<PortalConfig> = app
+        .world()
+        .resource::<AssetServer>()
+        .load("configs/main.portal.ron");
+
+    for _ in 0..500 {
+        app.update();
+        if let Some(config) = app.world().resource::<Assets<PortalConfig>>().get(&handle) {
+            let config = config.clone();
+            return (app, config);
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    panic!("configs/main.portal.ron never loaded");
+}
+
+fn spawn_portal(app: &mut App, config: PortalConfig) -> Entity {
+    let source = app
+        .world_mut()
+        .resource_mut::<Assets<PortalConfig>>()
+        .add(config);
+    app.world_mut()
+        .run_system_cached_with(
+            |In(source): In<Handle<PortalConfig>>,
+             mut commands: Commands,
+             mut portal_ids: ResMut<NextPortalId>,
+             configs: Res<Assets<PortalConfig>>| {
+                PortalBuilder::new(configs.get(&source).unwrap())
+                    .with_id(portal_ids.take())
+                    .with_source(source.clone())
+                    .spawn(&mut commands)
+            },
+            source,
+        )
+        .unwrap()
+}
+
+fn upgrade_stat(app: &App, portal: Entity, name: &str) -> (Entity, UpgradeableStat) {
+    let world = app.world();
+    world
+        .get::<Children>(portal)
+        .unwrap()
+        .iter()
+        .find(|child| world.get::<UpgradeSlot>(*child).unwrap().name == name)
+        .map(|child| (child, world.get::<UpgradeableStat>(child).unwrap().clone()))
+        .unwrap()
+}
+
+#[test]
+fn test_builder_spawns_portal_from_main_config() {
+    let (mut app, config) = load_main_config();
+    let portal = spawn_portal(&mut app, config);
+    app.update();
+
+    let world = app.world();
+    assert!(world.get::<PortalRoot>(portal).is_some());
+    assert_eq!(*world.get::<PortalId>(portal).unwrap(), PortalId(0));
+    assert_eq!(world.get::<PortalSpawnTracker>(portal).unwrap().0, 0);
+    assert_eq!(world.get::<PortalTopOffset>(portal).unwrap().0, 100.0);
+    assert_eq!(world.get::<ScavengerPenalty>(portal).unwrap().0, 1.0);
+
+    let level = world.get::<PortalLevel>(portal).unwrap();
+    assert_eq!((level.active, level.max_unlocked), (0, 0));
+    assert_eq!(
+        world.get::<UpgradeCost>(portal).unwrap().current_price,
+        500.0.into()
+    );
+
+    let spawner = world.get::<PortalSpawner>(portal).unwrap();
+    assert_eq!(spawner.timer.duration().as_secs_f32(), 2.0);
+    assert_eq!(spawner.timer.mode(), TimerMode::Repeating);
+
+    assert_eq!(
+        world.get::<BaseMonsterHealth>(portal).unwrap().0,
+        GrowthStrategy::Exponential {
+            base: 5.0,
+            factor: 1.5
+        }
+    );
+    assert!(world.get::<BaseMonsterReward>(portal).is_some());
+    assert!(world.get::<BaseMonsterSpeed>(portal).is_some());
+    assert!(world.get::<BaseMonsterLifetime>(portal).is_some());
+    assert!(world.get::<BaseMonsterArmor>(portal).is_some());
+    assert!(world.get::<BaseMonsterAttack>(portal).is_some());
+}
+
+#[test]
+fn test_each_portal_gets_its_own_id() {
+    let (mut app, config) = load_main_config();
+    let first = spawn_portal(&mut app, config.clone());
+    let second = spawn_portal(&mut app, config);
+
+    let world = app.world();
+    assert_eq!(*world.get::<PortalId>(first).unwrap(), PortalId(0));
+    assert_eq!(*world.get::<PortalId>(second).unwrap(), PortalId(1));
+}
+
+#[test]
+fn test_builder_spawns_upgrade_slots_from_config() {
+    let (mut app, config) = load_main_config();
+    let portal = spawn_portal(&mut app, config);
+    app.update();
+
+    let world = app.world();
+    let upgrades: Vec<(&UpgradeSlot, &UpgradeableStat)> = world
+        .get::<Children>(portal)
+        .unwrap()
+        .iter()
+        .filter_map(|child| {
+            Some((
+                world.get::<UpgradeSlot>(child)?,
+                world.get::<UpgradeableStat>(child)?,
+            ))
+        })
+        .collect();
+
+    let names: Vec<&str> = upgrades
+        .iter()
+        .map(|(slot, _)| slot.name.as_str())
+        .collect();
+    assert_eq!(names, ["Capacity", "Lifetime"]);
+
+    let (_, capacity) = upgrades[0];
+    assert_eq!(capacity.level, 0.0);
+    assert_eq!(capacity.value, 5.0);
+    assert_eq!(capacity.price, 200.0.into());
+    assert_eq!(
+        capacity.price_strategy,
+        GrowthStrategy::Exponential {
+            base: 200.0,
+            factor: 2.0
+        }
+        .into()
+    );
+}
+
+#[test]
+fn test_modified_config_reapplies_strategies_and_keeps_progress() {
+    let (mut app, config) = load_main_config();
+    app.add_systems(Update, reload_portal_configs);
+    let portal = spawn_portal(&mut app, config);
+    app.update();
+
+    // Progress made before the edit
+    let (capacity, _) = upgrade_stat(&app, portal, "Capacity");
+    app.world_mut()
+        .get_mut::<UpgradeableStat>(capacity)
+        .unwrap()
+        .set_level(2.0);
+    *app.world_mut().get_mut::<PortalLevel>(portal).unwrap() = PortalLevel {
+        active: 1,
+        max_unlocked: 3,
+    };
+
+    let source = app.world().get::<PortalSource>(portal).unwrap().0.clone();
+    {
+        let mut configs = app.world_mut().resource_mut::<Assets<PortalConfig>>();
+        let config = configs.get_mut(&source).unwrap();
+        config.level_up_price = GrowthStrategy::Linear {
+            base: 100.0,
+            coefficient: 10.0,
+        }
+        .into();
+        config.level_scaled_stats.spawn_timer = GrowthStrategy::Linear {
+            base: 4.0,
+            coefficient: -1.0,
+        };
+        config.upgrades.get_mut("Capacity").unwrap().price = GrowthStrategy::Static(42.0).into();
+        config.upgrades.insert(
+            "Speed".to_string(),
+            UpgradeConfig {
+                value: GrowthStrategy::Static(1.0),
+                price: GrowthStrategy::Static(10.0).into(),
+                requires: Vec::new(),
+            },
+        );
+    }
+    // Asset events are sent at the end of the frame
+    app.update();
+    app.update();
+
+    let world = app.world();
+    assert_eq!(
+        world.get::<UpgradeCost>(portal).unwrap().current_price,
+        130.0.into()
+    );
+    let spawner = world.get::<PortalSpawner>(portal).unwrap();
+    assert_eq!(spawner.timer.duration().as_secs_f32(), 3.0);
+
+    let (_, capacity) = upgrade_stat(&app, portal, "Capacity");
+    assert_eq!(capacity.level, 2.0);
+    assert_eq!(capacity.value, 7.0);
+    assert_eq!(capacity.price, 42.0.into());
+
+    let (_, speed) = upgrade_stat(&app, portal, "Speed");
+    assert_eq!((speed.level, speed.price), (0.0, 10.0.into()));
+}
@@ -1,22 +1,43 @@
 use {
     crate::{
-        handle_generic_upgrades, handle_portal_upgrade, portal_spawn_logic, portal_tick_logic,
-        PortalLevel, PortalSpawnTracker, PortalSpawner, UpgradeCost, UpgradeSlot,
+        handle_generic_upgrades, handle_portal_upgrade, handle_set_portal_level, layout_portal,
+        pick_monster, portal_spawn_logic, portal_tick_logic, update_upgrade_locks, LockedFeature,
+        PortalLevel, PortalRoot, PortalSpawnTracker, PortalSpawner, PortalTopOffset, UpgradeCost,
+        UpgradePrerequisites, UpgradeSlot,
     },
     bevy::{prelude::*, time::TimePlugin},
     common::{
-        BaseMonsterHealth, BaseMonsterLifetime, BaseMonsterReward, BaseMonsterSpeed,
-        GrowthStrategy, RequestUpgrade, Reward, SpawnMonsterRequest, UpgradePortal,
-        UpgradeableStat,
+        BaseMonsterHealth, BaseMonsterLifetime, BaseMonsterReward, BaseMonsterSpeed, Condition,
+        Currency, GrowthStrategy, Prerequisite, PurchaseAmount, RequestUpgrade, SetPortalLevel,
+        SpawnMonsterRequest, UpgradePortal, UpgradeableStat,
     },
-    monsters::{AvailableEnemies, Health, Lifetime, Monster, MonsterConfig},
+    monster_factory::SpawnMonsterEvent,
+    monsters::{AvailableEnemies, Monster, MonsterConfig, MovementPattern, SpawnedBy},
+    rand::{rngs::StdRng, SeedableRng},
     wallet::Wallet,
 };
 
+fn monster_config(prefab: &str, spawn_weight: f32, min_portal_level: u32) -> MonsterConfig {
+    MonsterConfig {
+        prefab: prefab.to_string(),
+        spawn_weight,
+        min_portal_level,
+        health_coef: 1.0,
+        lifetime_coef: 1.0,
+        speed_coef: 1.0,
+        reward_coef: 1.0,
+        movement: MovementPattern::default(),
+        return_to_portal: None,
+    }
+}
+
 // Helper to manually spawn the "Archetype" (Mocking the Scene)
 fn spawn_test_portal(commands: &mut Commands) -> Entity {
     let portal_entity = commands
         .spawn((
+            PortalRoot,
+            PortalTopOffset(100.0),
+            Transform::default(),
             PortalLevel {
                 active: 0,
                 max_unlocked: 0,
@@ -25,8 +46,9 @@ fn spawn_test_portal(commands: &mut Commands) -> Entity {
                 strategy: GrowthStrategy::Linear {
                     base: 100.0,
                     coefficient: 50.0,
-                },
-                current_price: 100.0,
+                }
+                .into(),
+                current_price: 100.0.into(),
             },
             PortalSpawner {
                 timer: Timer::from_seconds(1.0, TimerMode::Repeating),
@@ -61,7 +83,7 @@ fn spawn_test_portal(commands: &mut Commands) -> Entity {
             UpgradeableStat {
                 level: 0.0,
                 value: 5.0,
-                price: 200.0,
+                price: 200.0.into(),
                 value_strategy: GrowthStrategy::Incremental {
                     base: 5.0,
                     step: 1.0,
@@ -69,7 +91,8 @@ fn spawn_test_portal(commands: &mut Commands) -> Entity {
                 price_strategy: GrowthStrategy::Exponential {
                     base: 200.0,
                     factor: 1.5,
-                },
+                }
+                .into(),
             },
         ));
 
@@ -81,7 +104,7 @@ fn spawn_test_portal(commands: &mut Commands) -> Entity {
             UpgradeableStat {
                 level: 0.0,
                 value: 0.0,
-                price: 100.0,
+                price: 100.0.into(),
                 value_strategy: GrowthStrategy::Incremental {
                     base: 0.0,
                     step: 1.0,
@@ -89,7 +112,8 @@ fn spawn_test_portal(commands: &mut Commands) -> Entity {
                 price_strategy: GrowthStrategy::Exponential {
                     base: 100.0,
                     factor: 1.5,
-                },
+                }
+                .into(),
             },
         ));
     });
@@ -110,7 +134,9 @@ fn setup_app() -> App {
 
     app.add_message::<UpgradePortal>();
     app.add_message::<RequestUpgrade>();
+    app.add_message::<SetPortalLevel>();
     app.add_message::<SpawnMonsterRequest>();
+    app.add_message::<SpawnMonsterEvent>();
 
     // Mock Window
     app.world_mut().spawn((
@@ -121,17 +147,17 @@ fn setup_app() -> App {
         bevy::window::PrimaryWindow,
     ));
 
-    app.insert_resource(AvailableEnemies(vec![MonsterConfig {
-        health_coef: 1.0,
-        lifetime_coef: 1.0,
-        speed_coef: 1.0,
-        reward_coef: 1.0,
-    }]));
-
-    app.init_resource::<PortalSpawnTracker>();
+    app.insert_resource(AvailableEnemies(vec![
+        monster_config("prefabs/monsters/goblin.scn.ron", 1.0, 0),
+        MonsterConfig {
+            movement: MovementPattern::Flee { radius: 100.0 },
+            return_to_portal: Some(2.0),
+            ..monster_config("prefabs/monsters/ogre.scn.ron", 1.0, 5)
+        },
+    ]));
 
     app.insert_resource(Wallet {
-        void_shards: 1000.0,
+        balances: 1000.0.into(),
     });
 
     // Add Systems
@@ -142,6 +168,11 @@ fn setup_app() -> App {
             (portal_tick_logic, portal_spawn_logic).chain(),
             handle_portal_upgrade,
             handle_generic_upgrades,
+            handle_set_portal_level,
+            layout_portal,
+            update_upgrade_locks
+                .after(handle_portal_upgrade)
+                .after(handle_generic_upgrades),
         ),
     );
 
@@ -161,11 +192,11 @@ fn test_portal_initial_level() {
 
     assert_eq!(level.max_unlocked, 0);
     assert_eq!(level.active, 0);
-    assert_eq!(cost.current_price, 100.0);
+    assert_eq!(cost.current_price, 100.0.into());
 }
 
 #[test]
-fn test_monster_stats_at_level_0() {
+fn test_monster_spawn_uses_config_prefab() {
     let mut app = setup_app();
     app.update(); // Spawns portal
 
@@ -175,23 +206,91 @@ fn test_monster_stats_at_level_0() {
         // Spawn timer base is 1.0 + 0*0.1 = 1.0
         time.advance_by(std::time::Duration::from_secs_f32(1.1));
     }
-    app.update(); // Spawns monster (timer finished)
+    app.update(); // Emits SpawnMonsterEvent (timer finished)
 
-    let mut monster_query = app
+    let events: Vec<SpawnMonsterEvent> = app
         .world_mut()
-        .query::<(&Monster, &Health, &Reward, &Lifetime)>();
-    let monster = monster_query.iter(app.world()).next();
-
-    assert!(monster.is_some(), "Monster should be spawned at level 0");
-    let (_, health, reward, lifetime) = monster.unwrap();
-
-    // Level 0:
-    // Health: base 50 + 0*10 = 50
-    // Reward: base 10 + 0*0 = 10
-    // Lifetime: base 5 + 0*0.5 = 5 (plus 0 bonus)
-    assert_eq!(health.max, 50.0);
-    assert_eq!(reward.0, 10.0);
-    assert_eq!(lifetime.timer.duration().as_secs_f32(), 5.0);
+        .resource_mut::<Messages<SpawnMonsterEvent>>()
+        .drain()
+        .collect();
+
+    // Level 0: the ogre requires portal level 5, so only the goblin is allowed
+    assert_eq!(events.len(), 1, "Monster should be requested at level 0");
+    assert_eq!(events[0].asset_path, "prefabs/monsters/goblin.scn.ron");
+    assert_eq!(events[0].movement, MovementPattern::Straight);
+    assert_eq!(events[0].return_to_portal, None);
+}
+
+#[test]
+fn test_spawn_request_for_named_monster_skips_level_check() {
+    let mut app = setup_app();
+    app.update(); // Spawns portal
+
+    let portal_entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<PortalRoot>>()
+        .single(app.world())
+        .unwrap();
+    app.world_mut().write_message(SpawnMonsterRequest {
+        portal_entity,
+        monster: Some("prefabs/monsters/ogre.scn.ron".to_string()),
+    });
+    app.update();
+
+    let events: Vec<SpawnMonsterEvent> = app
+        .world_mut()
+        .resource_mut::<Messages<SpawnMonsterEvent>>()
+        .drain()
+        .collect();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].asset_path, "prefabs/monsters/ogre.scn.ron");
+    // Movement comes from the prefab's config
+    assert_eq!(events[0].movement, MovementPattern::Flee { radius: 100.0 });
+    assert_eq!(events[0].return_to_portal, Some(2.0));
+}
+
+#[test]
+fn test_pick_monster_respects_min_portal_level() {
+    let configs = vec![
+        monster_config("goblin", 1.0, 0),
+        monster_config("ogre", 1.0, 5),
+    ];
+    let mut rng = StdRng::seed_from_u64(7);
+
+    for _ in 0..50 {
+        let picked = pick_monster(&configs, 4, &mut rng).unwrap();
+        assert_eq!(picked.prefab, "goblin");
+    }
+
+    let at_level_5: Vec<_> = (0..200)
+        .map(|_| pick_monster(&configs, 5, &mut rng).unwrap().prefab.clone())
+        .collect();
+    assert!(at_level_5.iter().any(|prefab| prefab == "ogre"));
+
+    assert!(pick_monster(&configs[1..], 0, &mut rng).is_none());
+}
+
+#[test]
+fn test_pick_monster_follows_weights() {
+    let configs = vec![
+        monster_config("common", 9.0, 0),
+        monster_config("rare", 1.0, 0),
+        monster_config("disabled", 0.0, 0),
+    ];
+    let mut rng = StdRng::seed_from_u64(42);
+
+    let rolls = 10_000;
+    let mut rare = 0;
+    for _ in 0..rolls {
+        match pick_monster(&configs, 0, &mut rng).unwrap().prefab.as_str() {
+            "rare" => rare += 1,
+            "common" => {}
+            other => panic!("zero weight config picked: {other}"),
+        }
+    }
+
+    // Expected 10%
+    assert!((800..1200).contains(&rare), "rare picked {rare} times");
 }
 
 #[test]
@@ -200,13 +299,21 @@ fn test_portal_upgrade() {
     app.update(); // Spawn portal
 
     // Trigger upgrade
+    let portal_entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<PortalRoot>>()
+        .single(app.world())
+        .unwrap();
     let mut messages = app.world_mut().resource_mut::<Messages<UpgradePortal>>();
-    messages.write(UpgradePortal);
+    messages.write(UpgradePortal {
+        portal_entity,
+        amount: PurchaseAmount::default(),
+    });
 
     // Initial check
     {
         let wallet = app.world().resource::<Wallet>();
-        assert_eq!(wallet.void_shards, 1000.0);
+        assert_eq!(wallet.get(Currency::VoidShards), 1000.0);
     }
 
     app.update(); // Process upgrade
@@ -215,7 +322,7 @@ fn test_portal_upgrade() {
     {
         let wallet = app.world().resource::<Wallet>();
         // Cost at L0 is 100. 1000 - 100 = 900
-        assert_eq!(wallet.void_shards, 900.0);
+        assert_eq!(wallet.get(Currency::VoidShards), 900.0);
 
         let (level, cost) = app
             .world_mut()
@@ -229,10 +336,39 @@ fn test_portal_upgrade() {
         assert_eq!(level.active, 1);
 
         // New Price at L1: 100 + 1*50 = 150
-        assert_eq!(cost.current_price, 150.0);
+        assert_eq!(cost.current_price, 150.0.into());
     }
 }
 
+#[test]
+fn test_set_portal_level_is_free() {
+    let mut app = setup_app();
+    app.update(); // Spawn portal
+
+    let portal_entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<PortalRoot>>()
+        .single(app.world())
+        .unwrap();
+    app.world_mut().write_message(SetPortalLevel {
+        portal_entity,
+        level: 10,
+    });
+    app.update();
+
+    assert_eq!(app.world().resource::<Wallet>().get(Currency::VoidShards), 1000.0);
+    let world = app.world();
+    let level = world.get::<PortalLevel>(portal_entity).unwrap();
+    assert_eq!((level.active, level.max_unlocked), (10, 10));
+    // 100 + 10*50
+    assert_eq!(
+        world.get::<UpgradeCost>(portal_entity).unwrap().current_price,
+        600.0.into()
+    );
+    let spawner = world.get::<PortalSpawner>(portal_entity).unwrap();
+    assert_eq!(spawner.timer.duration().as_secs_f32(), 2.0);
+}
+
 #[test]
 fn test_capacity_upgrade() {
     let mut app = setup_app();
@@ -253,7 +389,7 @@ fn test_capacity_upgrade() {
         // Base 5
         assert_eq!(stat.value, 5.0);
         // Base 200
-        assert_eq!(stat.price, 200.0);
+        assert_eq!(stat.price, 200.0.into());
     }
 
     // Request Upgrade
@@ -261,6 +397,7 @@ fn test_capacity_upgrade() {
         .resource_mut::<Messages<RequestUpgrade>>()
         .write(RequestUpgrade {
             upgrade_entity: capacity_entity,
+            amount: PurchaseAmount::default(),
         });
 
     app.update();
@@ -269,7 +406,7 @@ fn test_capacity_upgrade() {
     {
         let wallet = app.world().resource::<Wallet>();
         // 1000 - 200 = 800
-        assert_eq!(wallet.void_shards, 800.0);
+        assert_eq!(wallet.get(Currency::VoidShards), 800.0);
 
         let stat = app.world().get::<UpgradeableStat>(capacity_entity).unwrap();
         // Level 1
@@ -277,7 +414,7 @@ fn test_capacity_upgrade() {
         // Value: 5 + 1*1 = 6
         assert_eq!(stat.value, 6.0);
         // Price: 200 * 1.5^1 = 300
-        assert_eq!(stat.price, 300.0);
+        assert_eq!(stat.price, 300.0.into());
     }
 }
 
@@ -301,7 +438,7 @@ fn test_bonus_lifetime_upgrade() {
         // Base 0
         assert_eq!(stat.value, 0.0);
         // Base 100
-        assert_eq!(stat.price, 100.0);
+        assert_eq!(stat.price, 100.0.into());
     }
 
     // Request Upgrade (System checks wallet now)
@@ -309,6 +446,7 @@ fn test_bonus_lifetime_upgrade() {
         .resource_mut::<Messages<RequestUpgrade>>()
         .write(RequestUpgrade {
             upgrade_entity: lifetime_entity,
+            amount: PurchaseAmount::default(),
         });
 
     app.update();
@@ -317,7 +455,7 @@ fn test_bonus_lifetime_upgrade() {
     {
         let wallet = app.world().resource::<Wallet>();
         // 1000 - 100 = 900
-        assert_eq!(wallet.void_shards, 900.0);
+        assert_eq!(wallet.get(Currency::VoidShards), 900.0);
 
         let stat = app.world().get::<UpgradeableStat>(lifetime_entity).unwrap();
         // Level 1
@@ -325,6 +463,237 @@ fn test_bonus_lifetime_upgrade() {
         // Value: 0 + 1*1 = 1
         assert_eq!(stat.value, 1.0);
         // Price: 100 * 1.5^1 = 150
-        assert_eq!(stat.price, 150.0);
+        assert_eq!(stat.price, 150.0.into());
     }
 }
+
+fn find_upgrade(app: &mut App, name: &str) -> Entity {
+    app.world_mut()
+        .query::<(&UpgradeSlot, Entity)>()
+        .iter(app.world())
+        .find(|(slot, _)| slot.name == name)
+        .map(|(_, e)| e)
+        .unwrap()
+}
+
+fn request_upgrade(app: &mut App, upgrade_entity: Entity) {
+    app.world_mut().write_message(RequestUpgrade {
+        upgrade_entity,
+        amount: PurchaseAmount::default(),
+    });
+    app.update();
+}
+
+#[test]
+fn test_upgrade_locked_until_other_upgrade_reaches_level() {
+    let mut app = setup_app();
+    app.update();
+
+    let capacity = find_upgrade(&mut app, "Capacity");
+    let lifetime = find_upgrade(&mut app, "Lifetime");
+    app.world_mut()
+        .entity_mut(lifetime)
+        .insert(UpgradePrerequisites(vec![Prerequisite::Upgrade {
+            name: "Capacity".to_string(),
+            level: 1,
+        }]));
+
+    request_upgrade(&mut app, lifetime);
+    assert_eq!(
+        app.world().get::<UpgradeableStat>(lifetime).unwrap().level,
+        0.0
+    );
+    assert_eq!(app.world().resource::<Wallet>().get(Currency::VoidShards), 1000.0);
+    assert!(app.world().get::<LockedFeature>(lifetime).is_some());
+
+    request_upgrade(&mut app, capacity);
+    assert!(app.world().get::<LockedFeature>(lifetime).is_none());
+
+    request_upgrade(&mut app, lifetime);
+    assert_eq!(
+        app.world().get::<UpgradeableStat>(lifetime).unwrap().level,
+        1.0
+    );
+}
+
+#[test]
+fn test_upgrade_locked_until_portal_level() {
+    let mut app = setup_app();
+    app.update();
+
+    let capacity = find_upgrade(&mut app, "Capacity");
+    app.world_mut()
+        .entity_mut(capacity)
+        .insert(UpgradePrerequisites(vec![Prerequisite::PortalLevel(
+            Condition::MinLevel(1),
+        )]));
+    app.update();
+    assert!(app.world().get::<LockedFeature>(capacity).is_some());
+
+    let portal_entity = portal_entities(&mut app)[0];
+    app.world_mut().write_message(UpgradePortal {
+        portal_entity,
+        amount: PurchaseAmount::default(),
+    });
+    app.update();
+    app.update();
+    assert!(app.world().get::<LockedFeature>(capacity).is_none());
+
+    request_upgrade(&mut app, capacity);
+    assert_eq!(
+        app.world().get::<UpgradeableStat>(capacity).unwrap().level,
+        1.0
+    );
+}
+
+#[test]
+fn test_bulk_upgrade_buys_max_affordable_levels() {
+    let mut app = setup_app();
+    app.update();
+
+    let capacity = find_upgrade(&mut app, "Capacity");
+    app.world_mut().write_message(RequestUpgrade {
+        upgrade_entity: capacity,
+        amount: PurchaseAmount::MaxAffordable,
+    });
+    app.update();
+
+    // 200 + 300 + 450 = 950, the fourth level (675) is out of reach
+    let stat = app.world().get::<UpgradeableStat>(capacity).unwrap();
+    assert_eq!(stat.level, 3.0);
+    assert_eq!(stat.value, 8.0);
+    assert_eq!(app.world().resource::<Wallet>().get(Currency::VoidShards), 50.0);
+}
+
+#[test]
+fn test_bulk_upgrade_is_all_or_nothing() {
+    let mut app = setup_app();
+    app.update();
+
+    let capacity = find_upgrade(&mut app, "Capacity");
+    app.world_mut().write_message(RequestUpgrade {
+        upgrade_entity: capacity,
+        amount: PurchaseAmount::Levels(10),
+    });
+    app.update();
+
+    assert_eq!(
+        app.world().get::<UpgradeableStat>(capacity).unwrap().level,
+        0.0
+    );
+    assert_eq!(app.world().resource::<Wallet>().get(Currency::VoidShards), 1000.0);
+}
+
+#[test]
+fn test_bulk_portal_upgrade() {
+    let mut app = setup_app();
+    app.update();
+
+    let portal_entity = portal_entities(&mut app)[0];
+    app.world_mut().write_message(UpgradePortal {
+        portal_entity,
+        amount: PurchaseAmount::Levels(5),
+    });
+    app.update();
+
+    // 100 + 150 + 200 + 250 + 300 = 1000
+    assert_eq!(app.world().resource::<Wallet>().get(Currency::VoidShards), 0.0);
+    let level = app.world().get::<PortalLevel>(portal_entity).unwrap();
+    assert_eq!(level.max_unlocked, 5);
+    assert_eq!(level.active, 5);
+    assert_eq!(
+        app.world().get::<UpgradeCost>(portal_entity).unwrap().current_price,
+        350.0.into()
+    );
+}
+
+fn spawn_second_portal(app: &mut App) -> Entity {
+    app.world_mut()
+        .run_system_cached(spawn_test_portal_system)
+        .unwrap()
+}
+
+fn spawn_test_portal_system(mut commands: Commands) -> Entity {
+    spawn_test_portal(&mut commands)
+}
+
+fn portal_entities(app: &mut App) -> Vec<Entity> {
+    let mut portals: Vec<Entity> = app
+        .world_mut()
+        .query_filtered::<Entity, With<PortalRoot>>()
+        .iter(app.world())
+        .collect();
+    portals.sort_by_key(|entity| entity.index());
+    portals
+}
+
+#[test]
+fn test_upgrade_targets_only_the_given_portal() {
+    let mut app = setup_app();
+    app.update();
+    let first = portal_entities(&mut app)[0];
+    let second = spawn_second_portal(&mut app);
+
+    app.world_mut().write_message(UpgradePortal {
+        portal_entity: second,
+        amount: PurchaseAmount::default(),
+    });
+    app.update();
+
+    let level = |app: &App, e: Entity| app.world().get::<PortalLevel>(e).unwrap().max_unlocked;
+    assert_eq!(level(&app, first), 0);
+    assert_eq!(level(&app, second), 1);
+}
+
+#[test]
+fn test_portals_track_spawns_and_capacity_separately() {
+    let mut app = setup_app();
+    app.update();
+    spawn_second_portal(&mut app);
+    let portals = portal_entities(&mut app);
+
+    // Fill the first portal up to its capacity of 5
+    for _ in 0..5 {
+        app.world_mut()
+            .spawn((Monster::default(), SpawnedBy(portals[0])));
+    }
+
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(std::time::Duration::from_secs_f32(1.1));
+    app.update();
+
+    let events: Vec<SpawnMonsterEvent> = app
+        .world_mut()
+        .resource_mut::<Messages<SpawnMonsterEvent>>()
+        .drain()
+        .collect();
+    assert_eq!(events.len(), 1, "Only the portal with room spawns");
+    assert_eq!(events[0].portal_entity, portals[1]);
+    assert_eq!(events[0].spawn_index, 0);
+
+    let tracker = |app: &App, e: Entity| app.world().get::<PortalSpawnTracker>(e).unwrap().0;
+    assert_eq!(tracker(&app, portals[0]), 0);
+    assert_eq!(tracker(&app, portals[1]), 1);
+}
+
+#[test]
+fn test_portals_are_laid_out_side_by_side() {
+    let mut app = setup_app();
+    app.update();
+    spawn_second_portal(&mut app);
+    app.update();
+
+    let portals = portal_entities(&mut app);
+    let translation = |app: &App, e: Entity| app.world().get::<Transform>(e).unwrap().translation;
+
+    // 800x600 window split in two slots, 100 below the top edge
+    assert_eq!(
+        translation(&app, portals[0]).truncate(),
+        Vec2::new(-200.0, 200.0)
+    );
+    assert_eq!(
+        translation(&app, portals[1]).truncate(),
+        Vec2::new(200.0, 200.0)
+    );
+}
@@ -0,0 +1,293 @@
+#![allow(clippy::type_complexity)]
+#![allow(clippy::too_many_arguments)]
+
+use {
+    bevy::prelude::*,
+    common::{
+        components::{PortalLevel, PortalRoot, PortalSpawner, UpgradeCost},
+        BigNumber, Currencies, Currency, GameState, GrowthStrategy, PrestigeMultipliers,
+        PriceStrategy, PurchaseAmount, UpgradeableStat,
+    },
+    monsters::Monster,
+    portal::{PortalConfig, PortalSource},
+    serde::{Deserialize, Serialize},
+    std::{collections::BTreeMap, time::Duration},
+    wallet::Wallet,
+};
+
+pub struct PrestigePlugin;
+
+impl Plugin for PrestigePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Prestige>()
+            .init_resource::<PrestigeSettings>()
+            .init_resource::<PrestigeMultipliers>()
+            .add_message::<RequestPrestige>()
+            .add_message::<BuyPrestigeUpgrade>()
+            .register_type::<PrestigeBonus>();
+
+        app.add_systems(
+            Update,
+            (
+                (handle_prestige, handle_prestige_upgrades)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+                // Also runs while loading so restored upgrades apply to the first monsters
+                update_prestige_multipliers,
+            )
+                .chain(),
+        );
+
+        info!("Void Prestige initialized");
+    }
+}
+
+// Messages
+
+/// Collapses every portal back to its starting level in exchange for prestige points.
+#[derive(Message, Debug, Clone, Default)]
+pub struct RequestPrestige;
+
+#[derive(Message, Debug, Clone)]
+pub struct BuyPrestigeUpgrade {
+    pub bonus: PrestigeBonus,
+    pub amount: PurchaseAmount,
+}
+
+// Resources
+
+/// Permanent bonus bought with prestige points.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Reflect,
+)]
+pub enum PrestigeBonus {
+    /// Multiplies every void shard payout.
+    VoidShardGain,
+    /// Multiplies the health of new monsters, each level makes them weaker.
+    MonsterFrailty,
+}
+
+impl PrestigeBonus {
+    pub const ALL: [Self; 2] = [Self::VoidShardGain, Self::MonsterFrailty];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::VoidShardGain => "Shard Gain",
+            Self::MonsterFrailty => "Monster Frailty",
+        }
+    }
+
+    /// Value is the multiplier, price is in prestige points.
+    fn stat(self) -> UpgradeableStat {
+        let (value, price) = match self {
+            Self::VoidShardGain => (
+                GrowthStrategy::Linear {
+                    base: 1.0,
+                    coefficient: 0.25,
+                },
+                GrowthStrategy::Exponential {
+                    base: 1.0,
+                    factor: 2.0,
+                },
+            ),
+            Self::MonsterFrailty => (
+                GrowthStrategy::Exponential {
+                    base: 1.0,
+                    factor: 0.9,
+                },
+                GrowthStrategy::Exponential {
+                    base: 2.0,
+                    factor: 2.0,
+                },
+            ),
+        };
+        UpgradeableStat::new(value, PriceStrategy::single(Currency::PrestigePoints, price))
+    }
+}
+
+/// Progress that survives a prestige, next to the prestige points in the `Wallet`.
+#[derive(Resource, Debug, Clone)]
+pub struct Prestige {
+    /// Number of prestiges done so far.
+    pub resets: u32,
+    pub upgrades: BTreeMap<PrestigeBonus, UpgradeableStat>,
+}
+
+impl Default for Prestige {
+    fn default() -> Self {
+        Self {
+            resets: 0,
+            upgrades: PrestigeBonus::ALL
+                .into_iter()
+                .map(|bonus| (bonus, bonus.stat()))
+                .collect(),
+        }
+    }
+}
+
+impl Prestige {
+    pub fn multipliers(&self) -> PrestigeMultipliers {
+        let value = |bonus| self.upgrades.get(&bonus).map_or(1.0, |stat| stat.value);
+        PrestigeMultipliers {
+            void_shards: value(PrestigeBonus::VoidShardGain),
+            monster_health: value(PrestigeBonus::MonsterFrailty),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct PrestigeSettings {
+    /// Highest portal level needed before a prestige pays anything.
+    pub min_level: u32,
+    /// Points awarded for the highest level reached, rounded down.
+    pub points: GrowthStrategy,
+}
+
+impl Default for PrestigeSettings {
+    fn default() -> Self {
+        Self {
+            min_level: 10,
+            points: GrowthStrategy::Chunked {
+                base: 0.0,
+                interval: 10.0,
+                step: 1.0,
+            },
+        }
+    }
+}
+
+impl PrestigeSettings {
+    pub fn points_for(&self, highest_level: u32) -> BigNumber {
+        if highest_level < self.min_level {
+            return BigNumber::ZERO;
+        }
+        self.points
+            .calculate(highest_level as f32)
+            .floor()
+            .max(0.0)
+            .into()
+    }
+}
+
+// Systems
+
+/// Resets every portal to the level of its config (0 without one), clears its
+/// upgrades, the wallet and the monsters on the field, then awards points for
+/// the highest unlocked level. Prestige points and prestige upgrades are kept.
+pub fn handle_prestige(
+    mut commands: Commands,
+    mut events: MessageReader<RequestPrestige>,
+    settings: Res<PrestigeSettings>,
+    mut prestige: ResMut<Prestige>,
+    mut wallet: ResMut<Wallet>,
+    configs: Res<Assets<PortalConfig>>,
+    mut portal_query: Query<
+        (
+            &mut PortalLevel,
+            &mut UpgradeCost,
+            &mut PortalSpawner,
+            Option<&PortalSource>,
+            Option<&Children>,
+        ),
+        With<PortalRoot>,
+    >,
+    mut upgrade_query: Query<&mut UpgradeableStat>,
+    monster_query: Query<Entity, With<Monster>>,
+) {
+    // Several requests in one frame still count as a single prestige
+    if events.read().count() == 0 {
+        return;
+    }
+
+    let highest_level = portal_query
+        .iter()
+        .map(|(level, ..)| level.max_unlocked)
+        .max()
+        .unwrap_or_default();
+    let points = settings.points_for(highest_level);
+    if points.is_zero() {
+        warn!(
+            "Prestige needs portal level {}, highest reached is {}",
+            settings.min_level, highest_level
+        );
+        return;
+    }
+
+    for (mut level, mut cost, mut spawner, source, children) in portal_query.iter_mut() {
+        let start_level = source
+            .and_then(|source| configs.get(&source.0))
+            .map(|config| config.level)
+            .unwrap_or_default();
+
+        level.active = start_level;
+        level.max_unlocked = start_level;
+        cost.current_price = cost.strategy.calculate(start_level as f32);
+
+        let new_time = spawner.interval_strategy.calculate(start_level as f32);
+        spawner.timer.set_duration(Duration::from_secs_f32(new_time));
+        spawner.timer.reset();
+
+        if let Some(children) = children {
+            for child in children.iter() {
+                if let Ok(mut stat) = upgrade_query.get_mut(child) {
+                    stat.set_level(0.0);
+                }
+            }
+        }
+    }
+
+    for monster in &monster_query {
+        commands.entity(monster).despawn();
+    }
+
+    let total_points = wallet.get(Currency::PrestigePoints) + points;
+    wallet.balances = Currencies::single(Currency::PrestigePoints, total_points);
+    prestige.resets += 1;
+
+    info!(
+        "Prestige #{} from level {}: +{} points, {} total",
+        prestige.resets, highest_level, points, total_points
+    );
+}
+
+pub fn handle_prestige_upgrades(
+    mut events: MessageReader<BuyPrestigeUpgrade>,
+    mut prestige: ResMut<Prestige>,
+    mut wallet: ResMut<Wallet>,
+) {
+    for event in events.read() {
+        let Some(stat) = prestige.upgrades.get_mut(&event.bonus) else {
+            warn!("Prestige upgrade {:?} not found!", event.bonus);
+            continue;
+        };
+
+        let levels = event.amount.levels(&stat.price_strategy, stat.level, &wallet.balances);
+        let price = stat.price_of(levels);
+
+        if levels > 0 && wallet.spend(&price) {
+            stat.upgrade_by(levels);
+
+            info!(
+                "Prestige upgrade {:?} upgraded {} level(s) to {}",
+                event.bonus, levels, stat.value
+            );
+        } else {
+            warn!(
+                "Not enough prestige points for {:?} (Cost: {})",
+                event.bonus, price
+            );
+        }
+    }
+}
+
+pub fn update_prestige_multipliers(
+    prestige: Res<Prestige>,
+    mut multipliers: ResMut<PrestigeMultipliers>,
+) {
+    if prestige.is_changed() {
+        *multipliers = prestige.multipliers();
+    }
+}
+
+#[cfg(test)]
+mod tests;
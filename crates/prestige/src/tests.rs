@@ -0,0 +1,210 @@
+use {
+    crate::*,
+    common::{components::UpgradeSlot, GrowthStrategy},
+};
+
+fn setup_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(AssetPlugin::default());
+    app.init_asset::<PortalConfig>();
+    app.insert_resource(Wallet {
+        balances: 500.0.into(),
+    });
+    app.init_resource::<Prestige>()
+        .init_resource::<PrestigeSettings>()
+        .init_resource::<PrestigeMultipliers>()
+        .add_message::<RequestPrestige>()
+        .add_message::<BuyPrestigeUpgrade>();
+    app.add_systems(
+        Update,
+        (
+            handle_prestige,
+            handle_prestige_upgrades,
+            update_prestige_multipliers,
+        )
+            .chain(),
+    );
+    app
+}
+
+fn spawn_portal(app: &mut App, max_unlocked: u32) -> (Entity, Entity) {
+    let portal = app
+        .world_mut()
+        .spawn((
+            PortalRoot,
+            PortalLevel {
+                active: max_unlocked,
+                max_unlocked,
+            },
+            UpgradeCost {
+                strategy: GrowthStrategy::Linear {
+                    base: 100.0,
+                    coefficient: 50.0,
+                }
+                .into(),
+                current_price: 0.0.into(),
+            },
+            PortalSpawner {
+                timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+                interval_strategy: GrowthStrategy::Linear {
+                    base: 1.0,
+                    coefficient: 1.0,
+                },
+            },
+        ))
+        .id();
+
+    let mut capacity = UpgradeableStat::new(
+        GrowthStrategy::Incremental {
+            base: 5.0,
+            step: 1.0,
+        },
+        GrowthStrategy::Static(10.0),
+    );
+    capacity.set_level(3.0);
+    let capacity = app
+        .world_mut()
+        .spawn((
+            UpgradeSlot {
+                name: "Capacity".to_string(),
+            },
+            capacity,
+        ))
+        .id();
+    app.world_mut().entity_mut(portal).add_child(capacity);
+
+    (portal, capacity)
+}
+
+#[test]
+fn test_points_for_highest_level() {
+    let settings = PrestigeSettings::default();
+    assert_eq!(settings.points_for(9), 0.0);
+    assert_eq!(settings.points_for(10), 1.0);
+    assert_eq!(settings.points_for(29), 2.0);
+}
+
+#[test]
+fn test_prestige_resets_progress() {
+    let mut app = setup_app();
+    let (portal, capacity) = spawn_portal(&mut app, 25);
+    let monster = app
+        .world_mut()
+        .spawn(Monster {
+            target_position: Vec2::ZERO,
+        })
+        .id();
+
+    app.world_mut().write_message(RequestPrestige);
+    app.update();
+
+    let level = app.world().get::<PortalLevel>(portal).unwrap();
+    assert_eq!((level.active, level.max_unlocked), (0, 0));
+    assert_eq!(
+        app.world().get::<UpgradeCost>(portal).unwrap().current_price,
+        100.0.into()
+    );
+    let spawner = app.world().get::<PortalSpawner>(portal).unwrap();
+    assert_eq!(spawner.timer.duration().as_secs_f32(), 1.0);
+
+    let stat = app.world().get::<UpgradeableStat>(capacity).unwrap();
+    assert_eq!(stat.level, 0.0);
+    assert_eq!(stat.value, 5.0);
+
+    assert!(app.world().get_entity(monster).is_err());
+    assert_eq!(app.world().resource::<Wallet>().get(Currency::VoidShards), 0.0);
+}
+
+#[test]
+fn test_prestige_keeps_points_and_upgrades() {
+    let mut app = setup_app();
+    spawn_portal(&mut app, 25);
+    app.world_mut().resource_mut::<Wallet>().balances = Currencies::from(500.0)
+        .with(Currency::Essence, 4.0)
+        .with(Currency::PrestigePoints, 3.0);
+    app.world_mut()
+        .resource_mut::<Prestige>()
+        .upgrades
+        .get_mut(&PrestigeBonus::VoidShardGain)
+        .unwrap()
+        .set_level(2.0);
+
+    app.world_mut().write_message(RequestPrestige);
+    app.update();
+
+    // Everything but prestige points is spent or lost
+    assert_eq!(
+        app.world().resource::<Wallet>().balances,
+        Currencies::single(Currency::PrestigePoints, 5.0)
+    );
+    let prestige = app.world().resource::<Prestige>();
+    assert_eq!(prestige.resets, 1);
+    assert_eq!(
+        prestige.upgrades[&PrestigeBonus::VoidShardGain].level,
+        2.0
+    );
+    assert_eq!(app.world().resource::<PrestigeMultipliers>().void_shards, 1.5);
+}
+
+#[test]
+fn test_prestige_below_min_level_is_ignored() {
+    let mut app = setup_app();
+    let (portal, capacity) = spawn_portal(&mut app, 9);
+
+    app.world_mut().write_message(RequestPrestige);
+    app.update();
+
+    assert_eq!(
+        app.world().get::<PortalLevel>(portal).unwrap().max_unlocked,
+        9
+    );
+    assert_eq!(
+        app.world().get::<UpgradeableStat>(capacity).unwrap().level,
+        3.0
+    );
+    assert_eq!(app.world().resource::<Wallet>().get(Currency::VoidShards), 500.0);
+    assert_eq!(app.world().resource::<Prestige>().resets, 0);
+}
+
+#[test]
+fn test_buy_prestige_upgrades() {
+    let mut app = setup_app();
+    app.world_mut().resource_mut::<Wallet>().balances =
+        Currencies::single(Currency::PrestigePoints, 3.0);
+
+    app.world_mut().write_message(BuyPrestigeUpgrade {
+        bonus: PrestigeBonus::VoidShardGain,
+        amount: PurchaseAmount::default(),
+    });
+    // Costs 2, only 2 points are left after the first purchase
+    app.world_mut().write_message(BuyPrestigeUpgrade {
+        bonus: PrestigeBonus::MonsterFrailty,
+        amount: PurchaseAmount::MaxAffordable,
+    });
+    app.update();
+
+    assert!(app.world().resource::<Wallet>().balances.is_zero());
+    let prestige = app.world().resource::<Prestige>();
+    assert_eq!(prestige.upgrades[&PrestigeBonus::VoidShardGain].level, 1.0);
+    assert_eq!(prestige.upgrades[&PrestigeBonus::MonsterFrailty].level, 1.0);
+
+    let multipliers = app.world().resource::<PrestigeMultipliers>();
+    assert_eq!(multipliers.void_shards, 1.25);
+    assert!((multipliers.monster_health - 0.9).abs() < 1e-6);
+}
+
+#[test]
+fn test_prestige_upgrade_needs_points() {
+    let mut app = setup_app();
+
+    app.world_mut().write_message(BuyPrestigeUpgrade {
+        bonus: PrestigeBonus::VoidShardGain,
+        amount: PurchaseAmount::default(),
+    });
+    app.update();
+
+    let prestige = app.world().resource::<Prestige>();
+    assert_eq!(prestige.upgrades[&PrestigeBonus::VoidShardGain].level, 0.0);
+    assert_eq!(app.world().resource::<PrestigeMultipliers>().void_shards, 1.0);
+}
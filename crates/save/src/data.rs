@@ -0,0 +1,67 @@
+use {
+    common::Currencies,
+    player_npcs::TargetingPolicy,
+    prestige::PrestigeBonus,
+    serde::{Deserialize, Serialize},
+    std::collections::BTreeMap,
+};
+
+/// On-disk layout of the current save version.
+///
+/// Only progression (levels, balances, xp) is stored. Anything derived from a
+/// `GrowthStrategy` (prices, spawn intervals, stat values) is recalculated on
+/// load, so a balance patch never leaves stale numbers behind.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct SaveData {
+    pub version: u32,
+    /// Unix timestamp (seconds) of the moment the save was written.
+    pub saved_at: u64,
+    pub wallet: WalletSave,
+    /// One entry per portal, ordered by id.
+    pub portals: Vec<PortalSave>,
+    /// One entry per player NPC, ordered by id.
+    pub player_npcs: Vec<PlayerNpcSave>,
+    pub prestige: PrestigeSave,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct WalletSave {
+    /// Currency -> balance, prestige points included.
+    pub balances: Currencies,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct PortalSave {
+    /// `PortalId` of the portal this entry is restored to.
+    #[serde(default)]
+    pub id: u32,
+    pub active_level: u32,
+    pub max_unlocked: u32,
+    /// Upgrade slot name -> purchased level.
+    pub upgrades: BTreeMap<String, f32>,
+}
+
+/// Everything a prestige keeps, apart from the prestige points in the wallet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct PrestigeSave {
+    pub resets: u32,
+    /// Prestige upgrade -> purchased level.
+    pub upgrades: BTreeMap<PrestigeBonus, f32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct PlayerNpcSave {
+    /// `PlayerNpcId` of the player NPC this entry is restored to.
+    #[serde(default)]
+    pub id: u32,
+    pub melee: MasteryTrackSave,
+    pub ranged: MasteryTrackSave,
+    #[serde(default)]
+    pub targeting: TargetingPolicy,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct MasteryTrackSave {
+    pub level: u32,
+    pub current_xp: f32,
+}
@@ -0,0 +1,428 @@
+#![allow(clippy::type_complexity)]
+
+use {
+    bevy::{ecs::system::SystemParam, prelude::*},
+    common::{
+        components::{
+            PortalId, PortalLevel, PortalRoot, PortalSpawner, UpgradeCost, UpgradeSlot,
+        },
+        Currencies, GameState, OfflineProgressReport, UpgradeableStat,
+    },
+    player_npcs::{MasteryTrack, PlayerNpc, PlayerNpcId, TargetingPolicy, WeaponExpertise},
+    prestige::Prestige,
+    std::{
+        path::{Path, PathBuf},
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    },
+    wallet::Wallet,
+};
+
+mod data;
+mod migrations;
+mod offline;
+
+pub use {data::*, migrations::*, offline::*};
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SaveSettings>()
+            .init_resource::<OfflineProgressSettings>()
+            .init_resource::<AutosaveTimer>();
+
+        app.add_systems(Startup, load_save_file);
+
+        app.add_systems(
+            Update,
+            (
+                (
+                    restore_portal_state,
+                    restore_player_npc_state,
+                    finish_restore,
+                )
+                    .chain(),
+                grant_offline_progress,
+                autosave,
+            )
+                .chain()
+                .run_if(in_state(GameState::Playing)),
+        );
+
+        app.add_systems(Last, save_on_exit.run_if(in_state(GameState::Playing)));
+
+        info!("Void Save initialized");
+    }
+}
+
+// Resources
+
+#[derive(Resource, Debug, Clone)]
+pub struct SaveSettings {
+    pub path: PathBuf,
+    pub autosave_interval: Duration,
+}
+
+impl Default for SaveSettings {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("saves/savegame.ron"),
+            autosave_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct AutosaveTimer(pub Timer);
+
+/// Save loaded at startup that still has to be applied to the spawned entities.
+/// While it exists, autosave is suspended so a half-restored world is never written.
+#[derive(Resource, Debug)]
+pub struct PendingRestore {
+    pub data: SaveData,
+    pub portal_restored: bool,
+    pub player_npcs_restored: bool,
+}
+
+impl PendingRestore {
+    pub fn new(data: SaveData) -> Self {
+        Self {
+            portal_restored: data.portals.is_empty(),
+            player_npcs_restored: data.player_npcs.is_empty(),
+            data,
+        }
+    }
+}
+
+/// Everything that ends up in the save file.
+#[derive(SystemParam)]
+pub struct SaveSnapshot<'w, 's> {
+    wallet: Res<'w, Wallet>,
+    prestige: Res<'w, Prestige>,
+    portal_query: Query<
+        'w,
+        's,
+        (&'static PortalId, &'static PortalLevel, &'static Children),
+        With<PortalRoot>,
+    >,
+    upgrade_query: Query<'w, 's, (&'static UpgradeSlot, &'static UpgradeableStat)>,
+    player_npc_query: Query<
+        'w,
+        's,
+        (
+            &'static PlayerNpcId,
+            &'static WeaponExpertise,
+            &'static TargetingPolicy,
+        ),
+        With<PlayerNpc>,
+    >,
+}
+
+impl SaveSnapshot<'_, '_> {
+    pub fn capture(&self) -> SaveData {
+        let mut portals: Vec<_> = self.portal_query.iter().collect();
+        portals.sort_by_key(|(id, ..)| id.0);
+        let portals = portals
+            .into_iter()
+            .map(|(id, level, children)| PortalSave {
+                id: id.0,
+                active_level: level.active,
+                max_unlocked: level.max_unlocked,
+                upgrades: children
+                    .iter()
+                    .filter_map(|child| self.upgrade_query.get(child).ok())
+                    .map(|(slot, stat)| (slot.name.clone(), stat.level))
+                    .collect(),
+            })
+            .collect();
+
+        let mut player_npcs: Vec<_> = self.player_npc_query.iter().collect();
+        player_npcs.sort_by_key(|(id, ..)| id.0);
+        let player_npcs = player_npcs
+            .into_iter()
+            .map(|(id, expertise, policy)| PlayerNpcSave {
+                id: id.0,
+                melee: track_to_save(&expertise.melee),
+                ranged: track_to_save(&expertise.ranged),
+                targeting: *policy,
+            })
+            .collect();
+
+        SaveData {
+            version: CURRENT_VERSION,
+            saved_at: unix_now(),
+            wallet: WalletSave {
+                balances: self.wallet.balances.clone(),
+            },
+            portals,
+            player_npcs,
+            prestige: PrestigeSave {
+                resets: self.prestige.resets,
+                upgrades: self
+                    .prestige
+                    .upgrades
+                    .iter()
+                    .map(|(bonus, stat)| (*bonus, stat.level))
+                    .collect(),
+            },
+        }
+    }
+}
+
+// IO
+
+pub fn read_save(path: &Path) -> Result<SaveData, SaveError> {
+    let text = std::fs::read_to_string(path)?;
+    migrate(&text)
+}
+
+/// Writes to a temporary file first so a crash mid-write never corrupts the previous save.
+pub fn write_save(path: &Path, data: &SaveData) -> Result<(), SaveError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, serialize(data)?)?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn track_to_save(track: &MasteryTrack) -> MasteryTrackSave {
+    MasteryTrackSave {
+        level: track.level,
+        current_xp: track.current_xp,
+    }
+}
+
+fn track_from_save(track: &MasteryTrackSave) -> MasteryTrack {
+    MasteryTrack {
+        level: track.level,
+        current_xp: track.current_xp,
+    }
+}
+
+/// Prestige lives in a resource, so it is applied right away instead of waiting
+/// for spawned entities like the rest of [`PendingRestore`].
+pub fn restore_prestige(prestige: &mut Prestige, saved: &PrestigeSave) {
+    prestige.resets = saved.resets;
+    for (bonus, stat) in prestige.upgrades.iter_mut() {
+        stat.set_level(saved.upgrades.get(bonus).copied().unwrap_or_default());
+    }
+}
+
+// Systems
+
+pub fn load_save_file(
+    mut commands: Commands,
+    settings: Res<SaveSettings>,
+    offline_settings: Res<OfflineProgressSettings>,
+    mut wallet: ResMut<Wallet>,
+    mut prestige: ResMut<Prestige>,
+    mut autosave: ResMut<AutosaveTimer>,
+) {
+    autosave.0 = Timer::new(settings.autosave_interval, TimerMode::Repeating);
+
+    if !settings.path.exists() {
+        info!("No save file found at {:?}, starting fresh", settings.path);
+        return;
+    }
+
+    match read_save(&settings.path) {
+        Ok(data) => {
+            wallet.balances = data.wallet.balances.clone();
+            restore_prestige(&mut prestige, &data.prestige);
+            info!("Save file loaded from {:?}", settings.path);
+
+            let away_secs = unix_now().saturating_sub(data.saved_at);
+            if away_secs >= offline_settings.min_offline_secs {
+                commands.insert_resource(PendingOfflineProgress { away_secs });
+            }
+            commands.insert_resource(PendingRestore::new(data));
+        }
+        Err(err) => {
+            error!("Failed to load save file {:?}: {}", settings.path, err);
+        }
+    }
+}
+
+pub fn restore_portal_state(
+    pending: Option<ResMut<PendingRestore>>,
+    mut portal_query: Query<
+        (
+            &PortalId,
+            &mut PortalLevel,
+            &mut UpgradeCost,
+            &mut PortalSpawner,
+            &Children,
+        ),
+        With<PortalRoot>,
+    >,
+    mut upgrade_query: Query<(&UpgradeSlot, &mut UpgradeableStat)>,
+) {
+    let Some(mut pending) = pending else {
+        return;
+    };
+    if pending.portal_restored {
+        return;
+    }
+    // Portal scenes have not been spawned yet
+    if portal_query.is_empty() {
+        return;
+    }
+
+    for (id, mut level, mut cost, mut spawner, children) in portal_query.iter_mut() {
+        let Some(saved) = pending.data.portals.iter().find(|saved| saved.id == id.0) else {
+            continue;
+        };
+
+        level.max_unlocked = saved.max_unlocked;
+        level.active = saved.active_level.min(saved.max_unlocked);
+
+        cost.current_price = cost.strategy.calculate(level.max_unlocked as f32);
+
+        let new_time = spawner.interval_strategy.calculate(level.active as f32);
+        spawner
+            .timer
+            .set_duration(Duration::from_secs_f32(new_time));
+
+        for child in children.iter() {
+            let Ok((slot, mut stat)) = upgrade_query.get_mut(child) else {
+                continue;
+            };
+            if let Some(saved_level) = saved.upgrades.get(&slot.name) {
+                stat.set_level(*saved_level);
+            }
+        }
+
+        info!(
+            "Portal {} restored to level {} / {}",
+            id.0, level.active, level.max_unlocked
+        );
+    }
+
+    pending.portal_restored = true;
+}
+
+pub fn restore_player_npc_state(
+    pending: Option<ResMut<PendingRestore>>,
+    mut player_npc_query: Query<
+        (&PlayerNpcId, &mut WeaponExpertise, &mut TargetingPolicy),
+        With<PlayerNpc>,
+    >,
+) {
+    let Some(mut pending) = pending else {
+        return;
+    };
+    if pending.player_npcs_restored || player_npc_query.is_empty() {
+        return;
+    }
+
+    for (id, mut expertise, mut policy) in player_npc_query.iter_mut() {
+        let Some(saved) = pending.data.player_npcs.iter().find(|saved| saved.id == id.0) else {
+            continue;
+        };
+        expertise.melee = track_from_save(&saved.melee);
+        expertise.ranged = track_from_save(&saved.ranged);
+        *policy = saved.targeting;
+    }
+
+    pending.player_npcs_restored = true;
+    info!("Player NPC expertise and targeting restored");
+}
+
+pub fn finish_restore(mut commands: Commands, pending: Option<Res<PendingRestore>>) {
+    if let Some(pending) = pending {
+        if pending.portal_restored && pending.player_npcs_restored {
+            commands.remove_resource::<PendingRestore>();
+        }
+    }
+}
+
+/// Pays out offline earnings once the saved progression is applied, so the estimate
+/// uses the restored portal level and upgrades.
+pub fn grant_offline_progress(
+    mut commands: Commands,
+    pending: Option<Res<PendingOfflineProgress>>,
+    restore: Option<Res<PendingRestore>>,
+    settings: Res<OfflineProgressSettings>,
+    sources: OfflineSources,
+    mut wallet: ResMut<Wallet>,
+    mut reports: MessageWriter<OfflineProgressReport>,
+) {
+    let Some(pending) = pending else {
+        return;
+    };
+    if restore.is_some() {
+        return;
+    }
+    let Some(simulation) = sources.simulation() else {
+        return;
+    };
+
+    let counted_secs = counted_offline_secs(pending.away_secs, &settings);
+    let earnings = simulation.earnings(counted_secs);
+    wallet.balances += &Currencies::from(earnings.void_shards);
+
+    reports.write(OfflineProgressReport {
+        away_secs: pending.away_secs,
+        counted_secs,
+        monsters_killed: earnings.monsters_killed,
+        void_shards: earnings.void_shards,
+    });
+    commands.remove_resource::<PendingOfflineProgress>();
+    info!(
+        "Offline progress: {}s away, {} monsters killed, {} void shards",
+        counted_secs, earnings.monsters_killed, earnings.void_shards
+    );
+}
+
+pub fn autosave(
+    time: Res<Time>,
+    settings: Res<SaveSettings>,
+    mut timer: ResMut<AutosaveTimer>,
+    pending: Option<Res<PendingRestore>>,
+    snapshot: SaveSnapshot,
+) {
+    timer.0.tick(time.delta());
+    if !timer.0.just_finished() || pending.is_some() {
+        return;
+    }
+
+    match write_save(&settings.path, &snapshot.capture()) {
+        Ok(()) => debug!("Autosaved to {:?}", settings.path),
+        Err(err) => error!("Autosave failed: {}", err),
+    }
+}
+
+pub fn save_on_exit(
+    mut exit_events: MessageReader<AppExit>,
+    settings: Res<SaveSettings>,
+    pending: Option<Res<PendingRestore>>,
+    snapshot: SaveSnapshot,
+) {
+    if exit_events.is_empty() {
+        return;
+    }
+    exit_events.clear();
+
+    if pending.is_some() {
+        warn!("Exiting before the save was restored, keeping the previous save file");
+        return;
+    }
+
+    match write_save(&settings.path, &snapshot.capture()) {
+        Ok(()) => info!("Game saved to {:?}", settings.path),
+        Err(err) => error!("Saving on exit failed: {}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,187 @@
+use {crate::data::SaveData, serde::Deserialize, std::fmt};
+
+/// Version written by this build. Bump it together with a new arm in [`migrate`]
+/// whenever the layout of [`SaveData`] changes.
+pub const CURRENT_VERSION: u32 = 6;
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+    /// The file was written by a newer build (or is corrupted).
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "io error: {err}"),
+            Self::Parse(err) => write!(f, "parse error: {err}"),
+            Self::Serialize(err) => write!(f, "serialize error: {err}"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported save version {version} (current is {CURRENT_VERSION})"
+            ),
+        }
+    }
+}
+
+impl From<std::io::Error> for SaveError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for SaveError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+impl From<ron::Error> for SaveError {
+    fn from(err: ron::Error) -> Self {
+        Self::Serialize(err)
+    }
+}
+
+/// Only the version is read first, every other field is ignored.
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+/// Parses a save file of any known version and upgrades it step by step
+/// to [`CURRENT_VERSION`].
+pub fn migrate(text: &str) -> Result<SaveData, SaveError> {
+    let header: SaveHeader = ron::from_str(text)?;
+
+    let mut data: SaveData = match header.version {
+        CURRENT_VERSION => return Ok(ron::from_str(text)?),
+        // Older versions are parsed into their own layout and converted forward
+        4 | 5 => SaveData {
+            version: CURRENT_VERSION,
+            ..ron::from_str(text)?
+        },
+        2 | 3 => v3::SaveData::parse(text)?.into(),
+        1 => v3::SaveData::from(v1::SaveData::parse(text)?).into(),
+        version => return Err(SaveError::UnsupportedVersion(version)),
+    };
+    number_in_spawn_order(&mut data);
+    Ok(data)
+}
+
+/// Saves before version 6 have no ids, their entries were written in spawn
+/// order and are numbered the same way.
+fn number_in_spawn_order(data: &mut SaveData) {
+    for (id, portal) in (0..).zip(data.portals.iter_mut()) {
+        portal.id = id;
+    }
+    for (id, player_npc) in (0..).zip(data.player_npcs.iter_mut()) {
+        player_npc.id = id;
+    }
+}
+
+pub fn serialize(data: &SaveData) -> Result<String, SaveError> {
+    Ok(ron::ser::to_string_pretty(
+        data,
+        ron::ser::PrettyConfig::default(),
+    )?)
+}
+
+/// Layout before multiple portals, with at most one portal.
+mod v1 {
+    use {
+        super::v3::{self, WalletSave},
+        crate::data::{PlayerNpcSave, PortalSave},
+        serde::Deserialize,
+    };
+
+    #[derive(Deserialize)]
+    pub struct SaveData {
+        pub saved_at: u64,
+        pub wallet: WalletSave,
+        pub portal: Option<PortalSave>,
+        pub player_npcs: Vec<PlayerNpcSave>,
+    }
+
+    impl SaveData {
+        pub fn parse(text: &str) -> Result<Self, ron::error::SpannedError> {
+            ron::from_str(text)
+        }
+    }
+
+    impl From<SaveData> for v3::SaveData {
+        fn from(old: SaveData) -> Self {
+            Self {
+                saved_at: old.saved_at,
+                wallet: old.wallet,
+                portals: old.portal.into_iter().collect(),
+                player_npcs: old.player_npcs,
+                prestige: Default::default(),
+            }
+        }
+    }
+}
+
+/// Layout with a single void shard balance and prestige points kept apart from
+/// the wallet. Version 2 is the same without `prestige`.
+mod v3 {
+    use {
+        crate::{
+            data::{self, PlayerNpcSave, PortalSave},
+            CURRENT_VERSION,
+        },
+        common::{BigNumber, Currencies, Currency},
+        prestige::PrestigeBonus,
+        serde::Deserialize,
+        std::collections::BTreeMap,
+    };
+
+    #[derive(Deserialize)]
+    pub struct SaveData {
+        pub saved_at: u64,
+        pub wallet: WalletSave,
+        pub portals: Vec<PortalSave>,
+        pub player_npcs: Vec<PlayerNpcSave>,
+        #[serde(default)]
+        pub prestige: PrestigeSave,
+    }
+
+    #[derive(Deserialize)]
+    pub struct WalletSave {
+        pub void_shards: BigNumber,
+    }
+
+    #[derive(Deserialize, Default)]
+    pub struct PrestigeSave {
+        pub points: BigNumber,
+        pub resets: u32,
+        pub upgrades: BTreeMap<PrestigeBonus, f32>,
+    }
+
+    impl SaveData {
+        pub fn parse(text: &str) -> Result<Self, ron::error::SpannedError> {
+            ron::from_str(text)
+        }
+    }
+
+    impl From<SaveData> for data::SaveData {
+        fn from(old: SaveData) -> Self {
+            Self {
+                version: CURRENT_VERSION,
+                saved_at: old.saved_at,
+                wallet: data::WalletSave {
+                    balances: Currencies::from(old.wallet.void_shards)
+                        .with(Currency::PrestigePoints, old.prestige.points),
+                },
+                portals: old.portals,
+                player_npcs: old.player_npcs,
+                prestige: data::PrestigeSave {
+                    resets: old.prestige.resets,
+                    upgrades: old.prestige.upgrades,
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use {
+    bevy::{ecs::system::SystemParam, prelude::*},
+    common::{
+        components::{
+            BaseMonsterHealth, BaseMonsterReward, PortalLevel, PortalRoot, PortalSpawner,
+        },
+        BaseMonsterArmor, BigNumber, DamageType, PrestigeMultipliers, UpgradeSlot,
+        UpgradeableStat, WeaponKind,
+    },
+    items::{BaseDamage, Ranged},
+    monster_factory::ArmorCoef,
+    monsters::Armor,
+    player_npcs::{PlayerNpc, Weapon, WeaponCooldown, WeaponExpertise},
+};
+
+#[derive(Resource, Debug, Clone)]
+pub struct OfflineProgressSettings {
+    /// Time away beyond this is not paid out.
+    pub max_offline_hours: f32,
+    /// Shorter absences (e.g. a quick restart) are ignored.
+    pub min_offline_secs: u64,
+}
+
+impl Default for OfflineProgressSettings {
+    fn default() -> Self {
+        Self {
+            max_offline_hours: 8.0,
+            min_offline_secs: 60,
+        }
+    }
+}
+
+/// Time away read from the save file, paid out once the restored world is ready.
+#[derive(Resource, Debug)]
+pub struct PendingOfflineProgress {
+    pub away_secs: u64,
+}
+
+/// Damage and attack interval of a single weapon, its wielder's mastery included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeaponRate {
+    pub damage: f32,
+    pub damage_type: DamageType,
+    pub cooldown_secs: f32,
+}
+
+/// Monsters one portal sends out at its active level.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortalRate {
+    pub spawn_interval_secs: f32,
+    pub capacity: f32,
+    pub monster_health: f32,
+    pub monster_armor: Armor,
+    pub monster_reward: BigNumber,
+}
+
+impl PortalRate {
+    /// Monsters the portal lets through per second. A portal without capacity spawns nothing.
+    pub fn spawn_rate(&self) -> f32 {
+        if self.capacity < 1.0 || self.spawn_interval_secs <= 0.0 {
+            return 0.0;
+        }
+        1.0 / self.spawn_interval_secs
+    }
+
+    /// Monsters of this portal the soldiers could finish per second if they fought
+    /// nowhere else, each weapon attacking on its own cooldown through the armor.
+    pub fn kill_rate(&self, weapons: &[WeaponRate]) -> f32 {
+        weapons
+            .iter()
+            .map(|weapon| {
+                let damage = self
+                    .monster_armor
+                    .mitigate(weapon.damage, weapon.damage_type);
+                (damage, weapon.cooldown_secs)
+            })
+            .filter(|(damage, cooldown_secs)| *damage > 0.0 && *cooldown_secs > 0.0)
+            .map(|(damage, cooldown_secs)| {
+                let hits = (self.monster_health / damage).ceil().max(1.0);
+                1.0 / (hits * cooldown_secs)
+            })
+            .sum()
+    }
+}
+
+/// Snapshot of everything the offline estimate depends on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OfflineSimulation {
+    pub portals: Vec<PortalRate>,
+    pub weapons: Vec<WeaponRate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct OfflineEarnings {
+    pub monsters_killed: u32,
+    pub void_shards: BigNumber,
+}
+
+impl OfflineSimulation {
+    /// Whichever side is slower limits the income: unkilled monsters fill the
+    /// portal capacity and stop the spawner, idle soldiers have nothing to hit.
+    /// Soldiers split their time between portals, when they can't keep up with
+    /// all of them every portal gets the same share of its monsters killed.
+    pub fn earnings(&self, secs: u64) -> OfflineEarnings {
+        let rates: Vec<(f32, f32)> = self
+            .portals
+            .iter()
+            .map(|portal| (portal.spawn_rate(), portal.kill_rate(&self.weapons)))
+            .collect();
+        let busy: f32 = rates
+            .iter()
+            .filter(|(spawn_rate, kill_rate)| *spawn_rate > 0.0 && *kill_rate > 0.0)
+            .map(|(spawn_rate, kill_rate)| spawn_rate / kill_rate)
+            .sum();
+        let share = if busy > 1.0 { 1.0 / busy } else { 1.0 };
+
+        let mut earnings = OfflineEarnings::default();
+        for (portal, (spawn_rate, kill_rate)) in self.portals.iter().zip(rates) {
+            if kill_rate <= 0.0 {
+                continue;
+            }
+            let killed = (spawn_rate * share * secs as f32).floor() as u32;
+            earnings.monsters_killed += killed;
+            earnings.void_shards += portal.monster_reward * killed as f64;
+        }
+        earnings
+    }
+}
+
+/// Seconds of `away_secs` that count towards offline earnings.
+pub fn counted_offline_secs(away_secs: u64, settings: &OfflineProgressSettings) -> u64 {
+    let cap = (settings.max_offline_hours.max(0.0) * 3600.0) as u64;
+    away_secs.min(cap)
+}
+
+/// Live portal and soldier state the estimate is built from.
+#[derive(SystemParam)]
+pub struct OfflineSources<'w, 's> {
+    portal_query: Query<
+        'w,
+        's,
+        (
+            &'static PortalLevel,
+            &'static PortalSpawner,
+            &'static BaseMonsterHealth,
+            &'static BaseMonsterArmor,
+            &'static BaseMonsterReward,
+            &'static Children,
+        ),
+        With<PortalRoot>,
+    >,
+    upgrade_query: Query<'w, 's, (&'static UpgradeSlot, &'static UpgradeableStat)>,
+    player_npc_query:
+        Query<'w, 's, (&'static Children, Option<&'static WeaponExpertise>), With<PlayerNpc>>,
+    weapon_query: Query<
+        'w,
+        's,
+        (
+            &'static BaseDamage,
+            &'static WeaponCooldown,
+            Option<&'static DamageType>,
+            Has<Ranged>,
+        ),
+        With<Weapon>,
+    >,
+    multipliers: Option<Res<'w, PrestigeMultipliers>>,
+}
+
+impl OfflineSources<'_, '_> {
+    /// `None` until a portal and an armed soldier are spawned.
+    pub fn simulation(&self) -> Option<OfflineSimulation> {
+        let weapons: Vec<WeaponRate> = self
+            .player_npc_query
+            .iter()
+            .flat_map(|(children, expertise)| {
+                children.iter().filter_map(move |child| {
+                    let (damage, cooldown, damage_type, ranged) =
+                        self.weapon_query.get(child).ok()?;
+                    let kind = if ranged {
+                        WeaponKind::Ranged
+                    } else {
+                        WeaponKind::Melee
+                    };
+                    let mastery = expertise
+                        .map(|expertise| expertise.track(kind).get_damage_bonus())
+                        .unwrap_or(1.0);
+                    Some(WeaponRate {
+                        damage: damage.0 * mastery,
+                        damage_type: damage_type.copied().unwrap_or_default(),
+                        cooldown_secs: cooldown.timer.duration().as_secs_f32(),
+                    })
+                })
+            })
+            .collect();
+        if weapons.is_empty() {
+            return None;
+        }
+
+        let prestige = self.multipliers.as_deref().copied().unwrap_or_default();
+        // Monsters the estimate fights carry no armor coefficients of their own
+        let armor_coef = ArmorCoef::default();
+        let portals: Vec<PortalRate> = self
+            .portal_query
+            .iter()
+            .map(|(level, spawner, health, armor, reward, children)| {
+                let capacity = children
+                    .iter()
+                    .filter_map(|child| self.upgrade_query.get(child).ok())
+                    .find_map(|(slot, stat)| (slot.name == "Capacity").then_some(stat.value))
+                    .unwrap_or_default();
+
+                let active = level.active as f32;
+                let armor = armor.calculate(active);
+                PortalRate {
+                    spawn_interval_secs: spawner.interval_strategy.calculate(active),
+                    capacity,
+                    monster_health: health.0.calculate(active) * prestige.monster_health,
+                    monster_armor: Armor {
+                        physical: armor * armor_coef.physical,
+                        magic: armor * armor_coef.magic,
+                        void: armor * armor_coef.void,
+                    },
+                    monster_reward: reward.0.calculate_big(active) * prestige.void_shards as f64,
+                }
+            })
+            .collect();
+        if portals.is_empty() {
+            return None;
+        }
+
+        Some(OfflineSimulation { portals, weapons })
+    }
+}
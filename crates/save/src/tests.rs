@@ -0,0 +1,681 @@
+use {
+    crate::*,
+    bevy::{ecs::system::RunSystemOnce, state::app::StatesPlugin},
+    common::{
+        components::{BaseMonsterHealth, BaseMonsterReward, PortalId},
+        BaseMonsterArmor, Currency, DamageType, GrowthStrategy,
+    },
+    items::BaseDamage,
+    monsters::Armor,
+    player_npcs::{PlayerNpcId, TargetingPolicy, Weapon, WeaponCooldown},
+    prestige::PrestigeBonus,
+    std::collections::BTreeMap,
+};
+
+fn sample_save() -> SaveData {
+    SaveData {
+        version: CURRENT_VERSION,
+        saved_at: 1_700_000_000,
+        wallet: WalletSave {
+            balances: 1234.0.into(),
+        },
+        portals: vec![PortalSave {
+            id: 0,
+            active_level: 2,
+            max_unlocked: 3,
+            upgrades: BTreeMap::from([("Capacity".to_string(), 4.0)]),
+        }],
+        player_npcs: vec![PlayerNpcSave {
+            id: 0,
+            melee: MasteryTrackSave {
+                level: 2,
+                current_xp: 15.0,
+            },
+            ranged: MasteryTrackSave::default(),
+            targeting: TargetingPolicy::default(),
+        }],
+        prestige: PrestigeSave::default(),
+    }
+}
+
+fn setup_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins).add_plugins(StatesPlugin);
+    app.init_state::<GameState>();
+    app.insert_state(GameState::Playing);
+    app.insert_resource(Wallet {
+        balances: 0.0.into(),
+    });
+    app.init_resource::<OfflineProgressSettings>();
+    app.init_resource::<Prestige>();
+    app.add_message::<OfflineProgressReport>();
+    app.add_systems(
+        Update,
+        (
+            (
+                restore_portal_state,
+                restore_player_npc_state,
+                finish_restore,
+            )
+                .chain(),
+            grant_offline_progress,
+        )
+            .chain(),
+    );
+    app
+}
+
+fn spawn_portal(app: &mut App) -> (Entity, Entity) {
+    let portal = app
+        .world_mut()
+        .spawn((
+            PortalRoot,
+            PortalLevel::default(),
+            UpgradeCost {
+                strategy: GrowthStrategy::Linear {
+                    base: 100.0,
+                    coefficient: 50.0,
+                }
+                .into(),
+                current_price: 100.0.into(),
+            },
+            PortalSpawner {
+                timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+                interval_strategy: GrowthStrategy::Linear {
+                    base: 1.0,
+                    coefficient: 1.0,
+                },
+            },
+            BaseMonsterHealth(GrowthStrategy::Static(10.0)),
+            BaseMonsterArmor(GrowthStrategy::Static(0.0)),
+            BaseMonsterReward(GrowthStrategy::Linear {
+                base: 5.0,
+                coefficient: 5.0,
+            }),
+        ))
+        .id();
+
+    let capacity = app
+        .world_mut()
+        .spawn((
+            UpgradeSlot {
+                name: "Capacity".to_string(),
+            },
+            UpgradeableStat::new(
+                GrowthStrategy::Incremental {
+                    base: 5.0,
+                    step: 1.0,
+                },
+                GrowthStrategy::Static(10.0),
+            ),
+        ))
+        .id();
+    app.world_mut().entity_mut(portal).add_child(capacity);
+
+    (portal, capacity)
+}
+
+fn spawn_soldier(app: &mut App, damage: f32, cooldown_secs: f32) -> Entity {
+    let soldier = app
+        .world_mut()
+        .spawn((PlayerNpc, WeaponExpertise::default()))
+        .id();
+    let weapon = app
+        .world_mut()
+        .spawn((
+            Weapon,
+            BaseDamage(damage),
+            WeaponCooldown {
+                timer: Timer::from_seconds(cooldown_secs, TimerMode::Once),
+            },
+        ))
+        .id();
+    app.world_mut().entity_mut(soldier).add_child(weapon);
+    soldier
+}
+
+fn portal_rate() -> PortalRate {
+    PortalRate {
+        spawn_interval_secs: 2.0,
+        capacity: 5.0,
+        monster_health: 10.0,
+        monster_armor: Armor::default(),
+        monster_reward: 4.0.into(),
+    }
+}
+
+fn weapon_rate(damage: f32, cooldown_secs: f32) -> WeaponRate {
+    WeaponRate {
+        damage,
+        damage_type: DamageType::Physical,
+        cooldown_secs,
+    }
+}
+
+fn simulation() -> OfflineSimulation {
+    OfflineSimulation {
+        portals: vec![portal_rate()],
+        weapons: vec![weapon_rate(3.0, 1.0)],
+    }
+}
+
+#[test]
+fn test_save_roundtrip() {
+    let mut data = sample_save();
+    data.wallet.balances = Currencies::from(1234.0)
+        .with(Currency::Essence, 5.0)
+        .with(Currency::PrestigePoints, 12.0);
+    data.prestige = PrestigeSave {
+        resets: 1,
+        upgrades: BTreeMap::from([(PrestigeBonus::VoidShardGain, 2.0)]),
+    };
+    let text = serialize(&data).unwrap();
+    assert_eq!(migrate(&text).unwrap(), data);
+}
+
+#[test]
+fn test_rejects_newer_version() {
+    let mut data = sample_save();
+    data.version = CURRENT_VERSION + 1;
+    let text = serialize(&data).unwrap();
+
+    assert!(matches!(
+        migrate(&text),
+        Err(SaveError::UnsupportedVersion(v)) if v == CURRENT_VERSION + 1
+    ));
+}
+
+#[test]
+fn test_migrates_single_portal_save() {
+    let text = r#"(
+        version: 1,
+        saved_at: 1700000000,
+        wallet: (void_shards: 1234.0),
+        portal: Some((
+            active_level: 2,
+            max_unlocked: 3,
+            upgrades: {"Capacity": 4.0},
+        )),
+        player_npcs: [(
+            melee: (level: 2, current_xp: 15.0),
+            ranged: (level: 0, current_xp: 0.0),
+        )],
+    )"#;
+
+    assert_eq!(migrate(text).unwrap(), sample_save());
+}
+
+#[test]
+fn test_migrates_save_without_prestige() {
+    let text = r#"(
+        version: 2,
+        saved_at: 1700000000,
+        wallet: (void_shards: 1234.0),
+        portals: [(
+            active_level: 2,
+            max_unlocked: 3,
+            upgrades: {"Capacity": 4.0},
+        )],
+        player_npcs: [(
+            melee: (level: 2, current_xp: 15.0),
+            ranged: (level: 0, current_xp: 0.0),
+        )],
+    )"#;
+
+    assert_eq!(migrate(text).unwrap(), sample_save());
+}
+
+#[test]
+fn test_migrates_prestige_points_into_wallet() {
+    let text = r#"(
+        version: 3,
+        saved_at: 1700000000,
+        wallet: (void_shards: 1234.0),
+        portals: [(
+            active_level: 2,
+            max_unlocked: 3,
+            upgrades: {"Capacity": 4.0},
+        )],
+        player_npcs: [(
+            melee: (level: 2, current_xp: 15.0),
+            ranged: (level: 0, current_xp: 0.0),
+        )],
+        prestige: (
+            points: 7.0,
+            resets: 2,
+            upgrades: {VoidShardGain: 3.0},
+        ),
+    )"#;
+
+    let data = migrate(text).unwrap();
+    assert_eq!(
+        data.wallet.balances,
+        Currencies::from(1234.0).with(Currency::PrestigePoints, 7.0)
+    );
+    assert_eq!(
+        data.prestige,
+        PrestigeSave {
+            resets: 2,
+            upgrades: BTreeMap::from([(PrestigeBonus::VoidShardGain, 3.0)]),
+        }
+    );
+}
+
+#[test]
+fn test_migrates_save_without_targeting() {
+    let text = r#"(
+        version: 4,
+        saved_at: 1700000000,
+        wallet: (balances: {VoidShards: 1234.0}),
+        portals: [(
+            active_level: 2,
+            max_unlocked: 3,
+            upgrades: {"Capacity": 4.0},
+        )],
+        player_npcs: [(
+            melee: (level: 2, current_xp: 15.0),
+            ranged: (level: 0, current_xp: 0.0),
+        )],
+        prestige: (resets: 0, upgrades: {}),
+    )"#;
+
+    assert_eq!(migrate(text).unwrap(), sample_save());
+}
+
+#[test]
+fn test_migrates_save_without_ids_in_spawn_order() {
+    let text = r#"(
+        version: 5,
+        saved_at: 1700000000,
+        wallet: (balances: {VoidShards: 1234.0}),
+        portals: [
+            (active_level: 2, max_unlocked: 3, upgrades: {}),
+            (active_level: 0, max_unlocked: 7, upgrades: {}),
+        ],
+        player_npcs: [
+            (
+                melee: (level: 2, current_xp: 15.0),
+                ranged: (level: 0, current_xp: 0.0),
+                targeting: Nearest,
+            ),
+            (
+                melee: (level: 0, current_xp: 0.0),
+                ranged: (level: 4, current_xp: 0.0),
+                targeting: LowestHealth,
+            ),
+        ],
+        prestige: (resets: 0, upgrades: {}),
+    )"#;
+
+    let data = migrate(text).unwrap();
+    assert_eq!(data.version, CURRENT_VERSION);
+    let portals: Vec<_> = data.portals.iter().map(|p| (p.id, p.max_unlocked)).collect();
+    assert_eq!(portals, vec![(0, 3), (1, 7)]);
+    let player_npcs: Vec<_> = data
+        .player_npcs
+        .iter()
+        .map(|npc| (npc.id, npc.ranged.level))
+        .collect();
+    assert_eq!(player_npcs, vec![(0, 0), (1, 4)]);
+}
+
+#[test]
+fn test_restore_prestige() {
+    let mut prestige = Prestige::default();
+    prestige
+        .upgrades
+        .get_mut(&PrestigeBonus::MonsterFrailty)
+        .unwrap()
+        .set_level(4.0);
+
+    let saved = PrestigeSave {
+        resets: 2,
+        upgrades: BTreeMap::from([(PrestigeBonus::VoidShardGain, 3.0)]),
+    };
+    restore_prestige(&mut prestige, &saved);
+
+    assert_eq!(prestige.resets, 2);
+    let shard_gain = &prestige.upgrades[&PrestigeBonus::VoidShardGain];
+    assert_eq!(shard_gain.level, 3.0);
+    assert_eq!(shard_gain.value, 1.75);
+    // Upgrades missing from the save start over
+    assert_eq!(prestige.upgrades[&PrestigeBonus::MonsterFrailty].level, 0.0);
+}
+
+#[test]
+fn test_write_and_read_file() {
+    let path = std::env::temp_dir()
+        .join(format!("void_portal_save_test_{}", std::process::id()))
+        .join("savegame.ron");
+    let data = sample_save();
+
+    write_save(&path, &data).unwrap();
+    assert_eq!(read_save(&path).unwrap(), data);
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn test_restore_applies_progression_and_recalculates_prices() {
+    let mut app = setup_app();
+    let (portal, capacity) = spawn_portal(&mut app);
+    app.world_mut()
+        .spawn((PlayerNpc, WeaponExpertise::default()));
+    let mut data = sample_save();
+    data.player_npcs[0].targeting = TargetingPolicy::LowestHealth;
+    app.insert_resource(PendingRestore::new(data));
+
+    app.update();
+
+    let level = app.world().get::<PortalLevel>(portal).unwrap();
+    assert_eq!(level.active, 2);
+    assert_eq!(level.max_unlocked, 3);
+
+    // Price is recalculated from the strategy, not read from the file: 100 + 3 * 50
+    let cost = app.world().get::<UpgradeCost>(portal).unwrap();
+    assert_eq!(cost.current_price, 250.0.into());
+
+    // Spawn interval follows the active level: 1 + 2 * 1
+    let spawner = app.world().get::<PortalSpawner>(portal).unwrap();
+    assert_eq!(spawner.timer.duration().as_secs_f32(), 3.0);
+
+    let stat = app.world().get::<UpgradeableStat>(capacity).unwrap();
+    assert_eq!(stat.level, 4.0);
+    assert_eq!(stat.value, 9.0);
+
+    let mut npc_query = app
+        .world_mut()
+        .query::<(&WeaponExpertise, &TargetingPolicy)>();
+    let (expertise, policy) = npc_query.single(app.world()).unwrap();
+    assert_eq!(expertise.melee.level, 2);
+    assert_eq!(expertise.melee.current_xp, 15.0);
+    assert_eq!(*policy, TargetingPolicy::LowestHealth);
+
+    app.update();
+    assert!(
+        !app.world().contains_resource::<PendingRestore>(),
+        "PendingRestore should be removed once everything is applied"
+    );
+}
+
+#[test]
+fn test_restore_waits_for_spawned_entities() {
+    let mut app = setup_app();
+    app.insert_resource(PendingRestore::new(sample_save()));
+
+    app.update();
+    assert!(app.world().contains_resource::<PendingRestore>());
+
+    let (portal, _) = spawn_portal(&mut app);
+    app.world_mut()
+        .spawn((PlayerNpc, WeaponExpertise::default()));
+    app.update();
+    app.update();
+
+    assert_eq!(
+        app.world().get::<PortalLevel>(portal).unwrap().max_unlocked,
+        3
+    );
+    assert!(!app.world().contains_resource::<PendingRestore>());
+}
+
+#[test]
+fn test_restore_matches_portals_by_id() {
+    let mut app = setup_app();
+    // Spawned in the opposite order of their ids
+    let (first, _) = spawn_portal(&mut app);
+    app.world_mut().entity_mut(first).insert(PortalId(1));
+    let (second, _) = spawn_portal(&mut app);
+
+    let mut data = sample_save();
+    data.player_npcs.clear();
+    data.portals.push(PortalSave {
+        id: 1,
+        active_level: 0,
+        max_unlocked: 7,
+        upgrades: BTreeMap::new(),
+    });
+    app.insert_resource(PendingRestore::new(data));
+
+    app.update();
+
+    let max_unlocked = |e| app.world().get::<PortalLevel>(e).unwrap().max_unlocked;
+    assert_eq!(max_unlocked(first), 7);
+    assert_eq!(max_unlocked(second), 3);
+}
+
+#[test]
+fn test_restore_matches_player_npcs_by_id() {
+    let mut app = setup_app();
+    let first = app
+        .world_mut()
+        .spawn((PlayerNpc, PlayerNpcId(1), WeaponExpertise::default()))
+        .id();
+    let second = app
+        .world_mut()
+        .spawn((PlayerNpc, PlayerNpcId(0), WeaponExpertise::default()))
+        .id();
+
+    let mut data = sample_save();
+    data.portals.clear();
+    data.player_npcs.push(PlayerNpcSave {
+        id: 1,
+        ranged: MasteryTrackSave {
+            level: 6,
+            current_xp: 0.0,
+        },
+        targeting: TargetingPolicy::AboutToExpire,
+        ..default()
+    });
+    app.insert_resource(PendingRestore::new(data));
+
+    app.update();
+
+    let expertise = |e| app.world().get::<WeaponExpertise>(e).unwrap();
+    assert_eq!(expertise(first).ranged.level, 6);
+    assert_eq!(expertise(first).melee.level, 0);
+    assert_eq!(expertise(second).melee.level, 2);
+    assert_eq!(
+        *app.world().get::<TargetingPolicy>(first).unwrap(),
+        TargetingPolicy::AboutToExpire
+    );
+}
+
+#[test]
+fn test_snapshot_captures_state() {
+    let mut app = setup_app();
+    let (portal, capacity) = spawn_portal(&mut app);
+    app.world_mut().spawn((
+        PlayerNpc,
+        PlayerNpcId(3),
+        WeaponExpertise::default(),
+        TargetingPolicy::AboutToExpire,
+    ));
+
+    app.world_mut().resource_mut::<Wallet>().balances =
+        Currencies::from(42.0).with(Currency::PrestigePoints, 6.0);
+    app.world_mut()
+        .get_mut::<PortalLevel>(portal)
+        .unwrap()
+        .max_unlocked = 5;
+    app.world_mut()
+        .get_mut::<UpgradeableStat>(capacity)
+        .unwrap()
+        .set_level(2.0);
+
+    let data = app
+        .world_mut()
+        .run_system_once(|snapshot: SaveSnapshot| snapshot.capture())
+        .unwrap();
+
+    assert_eq!(data.version, CURRENT_VERSION);
+    assert_eq!(
+        data.wallet.balances,
+        Currencies::from(42.0).with(Currency::PrestigePoints, 6.0)
+    );
+    assert_eq!(data.portals.len(), 1);
+    let portal_save = &data.portals[0];
+    assert_eq!(portal_save.max_unlocked, 5);
+    assert_eq!(portal_save.upgrades.get("Capacity"), Some(&2.0));
+    assert_eq!(data.player_npcs.len(), 1);
+    assert_eq!(data.player_npcs[0].id, 3);
+    assert_eq!(data.player_npcs[0].targeting, TargetingPolicy::AboutToExpire);
+    assert_eq!(
+        data.prestige.upgrades.get(&PrestigeBonus::VoidShardGain),
+        Some(&0.0)
+    );
+}
+
+#[test]
+fn test_two_portals_and_npcs_roundtrip_by_id() {
+    let mut app = setup_app();
+    let mut portals = Vec::new();
+    let mut npcs = Vec::new();
+    for id in 0..2 {
+        let (portal, _) = spawn_portal(&mut app);
+        app.world_mut()
+            .entity_mut(portal)
+            .insert(PortalId(id))
+            .get_mut::<PortalLevel>()
+            .unwrap()
+            .max_unlocked = 3 + id;
+        portals.push(portal);
+        let mut expertise = WeaponExpertise::default();
+        expertise.ranged.level = 5 + id;
+        npcs.push(
+            app.world_mut()
+                .spawn((PlayerNpc, PlayerNpcId(id), expertise))
+                .id(),
+        );
+    }
+    let data = app
+        .world_mut()
+        .run_system_once(|snapshot: SaveSnapshot| snapshot.capture())
+        .unwrap();
+    let portal_ids: Vec<_> = data.portals.iter().map(|portal| portal.id).collect();
+    let npc_ids: Vec<_> = data.player_npcs.iter().map(|npc| npc.id).collect();
+    assert_eq!((portal_ids, npc_ids), (vec![0, 1], vec![0, 1]));
+
+    // Spawned again in the opposite order, each gets its own entry back
+    let mut app = setup_app();
+    let mut restored = Vec::new();
+    for id in (0..2).rev() {
+        let (portal, _) = spawn_portal(&mut app);
+        app.world_mut().entity_mut(portal).insert(PortalId(id));
+        let npc = app
+            .world_mut()
+            .spawn((PlayerNpc, PlayerNpcId(id), WeaponExpertise::default()))
+            .id();
+        restored.push((id, portal, npc));
+    }
+    app.insert_resource(PendingRestore::new(
+        migrate(&serialize(&data).unwrap()).unwrap(),
+    ));
+    app.update();
+
+    for (id, portal, npc) in restored {
+        let level = app.world().get::<PortalLevel>(portal).unwrap();
+        assert_eq!(level.max_unlocked, 3 + id);
+        let expertise = app.world().get::<WeaponExpertise>(npc).unwrap();
+        assert_eq!(expertise.ranged.level, 5 + id);
+    }
+}
+
+#[test]
+fn test_offline_earnings_limited_by_soldiers() {
+    let sim = simulation();
+    // 4 hits of 1s per kill, portal spawns every 2s
+    assert_eq!(sim.portals[0].kill_rate(&sim.weapons), 0.25);
+    assert_eq!(sim.portals[0].spawn_rate(), 0.5);
+
+    let earnings = sim.earnings(100);
+    assert_eq!(earnings.monsters_killed, 25);
+    assert_eq!(earnings.void_shards, 100.0);
+}
+
+#[test]
+fn test_offline_earnings_limited_by_portal() {
+    let mut sim = simulation();
+    sim.weapons.push(weapon_rate(20.0, 0.5));
+
+    assert_eq!(sim.earnings(100).monsters_killed, 50);
+
+    sim.portals[0].capacity = 0.0;
+    assert_eq!(sim.earnings(100), OfflineEarnings::default());
+}
+
+#[test]
+fn test_offline_earnings_summed_over_portals() {
+    let mut sim = simulation();
+    sim.weapons = vec![weapon_rate(20.0, 0.5)];
+    sim.portals.push(PortalRate {
+        spawn_interval_secs: 4.0,
+        monster_reward: 10.0.into(),
+        ..portal_rate()
+    });
+
+    // Soldiers keep up with both: 50 + 25 kills
+    let earnings = sim.earnings(100);
+    assert_eq!(earnings.monsters_killed, 75);
+    assert_eq!(earnings.void_shards, 450.0);
+
+    // One hit per 2s kill, 0.5 + 0.25 spawns/s need 1.5x the soldier's time
+    sim.weapons = vec![weapon_rate(20.0, 2.0)];
+    let earnings = sim.earnings(120);
+    assert_eq!(earnings.monsters_killed, 40 + 20);
+    assert_eq!(earnings.void_shards, 360.0);
+}
+
+#[test]
+fn test_offline_earnings_go_through_armor() {
+    let mut sim = simulation();
+    sim.weapons = vec![weapon_rate(10.0, 1.0)];
+    assert_eq!(sim.earnings(100).monsters_killed, 50);
+
+    // 100 armor halves the hits: 2 per kill, one kill every 2s
+    sim.portals[0].monster_armor.physical = 100.0;
+    assert_eq!(sim.portals[0].kill_rate(&sim.weapons), 0.5);
+
+    // Void damage ignores physical armor
+    sim.portals[0].spawn_interval_secs = 1.0;
+    sim.weapons[0].damage_type = DamageType::Void;
+    assert_eq!(sim.earnings(100).monsters_killed, 100);
+}
+
+#[test]
+fn test_counted_offline_secs_is_capped() {
+    let settings = OfflineProgressSettings {
+        max_offline_hours: 2.0,
+        min_offline_secs: 60,
+    };
+    assert_eq!(counted_offline_secs(600, &settings), 600);
+    assert_eq!(counted_offline_secs(100_000, &settings), 7200);
+}
+
+#[test]
+fn test_offline_progress_granted_once_after_restore() {
+    let mut app = setup_app();
+    app.insert_resource(PendingRestore::new(sample_save()));
+    app.insert_resource(PendingOfflineProgress { away_secs: 120 });
+    spawn_portal(&mut app);
+    spawn_soldier(&mut app, 5.0, 1.0);
+
+    app.update();
+
+    // Restored to level 2: 10 HP takes 2 hits of 6 (melee mastery 2), reward 15,
+    // spawn every 3s -> 40 kills
+    assert_eq!(app.world().resource::<Wallet>().get(Currency::VoidShards), 600.0);
+    assert!(!app.world().contains_resource::<PendingOfflineProgress>());
+
+    let reports: Vec<_> = app
+        .world_mut()
+        .resource_mut::<Messages<OfflineProgressReport>>()
+        .drain()
+        .collect();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].counted_secs, 120);
+    assert_eq!(reports[0].monsters_killed, 40);
+
+    app.update();
+    assert_eq!(app.world().resource::<Wallet>().get(Currency::VoidShards), 600.0);
+}
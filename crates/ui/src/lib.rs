@@ -1,14 +1,27 @@
 #![allow(clippy::type_complexity)]
 
-use {bevy::prelude::*, common::GameState, portal_panel::PortalPanelPlugin, wallet::Wallet};
+use {
+    bevy::prelude::*,
+    common::{Currency, GameState},
+    portal_panel::PortalPanelPlugin,
+    prestige_panel::PrestigePanelPlugin,
+    wallet::Wallet,
+    welcome_back_panel::WelcomeBackPanelPlugin,
+};
 
 mod portal_panel;
+mod prestige_panel;
+mod welcome_back_panel;
 
 pub struct VoidUiPlugin;
 
 impl Plugin for VoidUiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(PortalPanelPlugin);
+        app.add_plugins((
+            PortalPanelPlugin,
+            PrestigePanelPlugin,
+            WelcomeBackPanelPlugin,
+        ));
 
         app.add_systems(OnEnter(GameState::Playing), spawn_wallet_ui)
             .add_systems(
@@ -22,7 +35,7 @@ impl Plugin for VoidUiPlugin {
 }
 
 #[derive(Component)]
-struct WalletText;
+struct WalletText(Currency);
 
 #[derive(Component)]
 struct WalletUiRoot;
@@ -37,25 +50,28 @@ fn spawn_wallet_ui(mut commands: Commands) {
                 top: Val::Px(0.0),
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
+                column_gap: Val::Px(30.0),
                 ..default()
             },
             BackgroundColor(Color::hsla(270.0, 0.5, 0.8, 0.5)),
             WalletUiRoot,
         ))
         .with_children(|parent| {
-            parent.spawn((
-                Text::new("void shards: 0"),
-                TextFont::default(),
-                TextColor(Color::WHITE),
-                WalletText,
-            ));
+            for currency in Currency::ALL {
+                parent.spawn((
+                    Text::new(format!("{}: 0", currency.label())),
+                    TextFont::default(),
+                    TextColor(Color::WHITE),
+                    WalletText(currency),
+                ));
+            }
         });
 }
 
-fn update_wallet_ui(wallet: Res<Wallet>, mut query: Query<&mut Text, With<WalletText>>) {
+fn update_wallet_ui(wallet: Res<Wallet>, mut query: Query<(&mut Text, &WalletText)>) {
     if wallet.is_changed() {
-        for mut text in &mut query {
-            **text = format!("void shards: {}", wallet.void_shards);
+        for (mut text, WalletText(currency)) in &mut query {
+            **text = format!("{}: {}", currency.label(), wallet.get(*currency));
         }
     }
 }
@@ -112,18 +128,28 @@ mod tests {
         app.update(); // Transition
         app.update(); // OnEnter
 
-        // Verify UI spawned
-        let mut query = app.world_mut().query_filtered::<Entity, With<WalletText>>();
-        let text_entity = query.single(app.world()).unwrap();
-        let text = app.world().get::<Text>(text_entity).unwrap();
-        assert_eq!(**text, "void shards: 0");
+        // Verify UI spawned, one line per currency
+        let texts: Vec<_> = app
+            .world_mut()
+            .query::<(Entity, &WalletText)>()
+            .iter(app.world())
+            .map(|(entity, WalletText(currency))| (*currency, entity))
+            .collect();
+        assert_eq!(texts.len(), Currency::ALL.len());
+        let text_of = |app: &App, currency| {
+            let (_, entity) = texts.iter().find(|(c, _)| *c == currency).unwrap();
+            app.world().get::<Text>(*entity).unwrap().0.clone()
+        };
+        assert_eq!(text_of(&app, Currency::VoidShards), "void shards: 0");
 
         // Update wallet
-        app.world_mut().resource_mut::<Wallet>().void_shards = 100.5;
+        app.world_mut().resource_mut::<Wallet>().balances =
+            common::Currencies::from(100.5).with(Currency::Essence, 3.0);
         app.update();
 
-        let text = app.world().get::<Text>(text_entity).unwrap();
-        assert_eq!(**text, "void shards: 100.5");
+        assert_eq!(text_of(&app, Currency::VoidShards), "void shards: 100.5");
+        assert_eq!(text_of(&app, Currency::Essence), "essence: 3");
+        assert_eq!(text_of(&app, Currency::PrestigePoints), "prestige points: 0");
 
         // Transition back to Loading
         app.world_mut()
@@ -1,8 +1,12 @@
 use {
     bevy::prelude::*,
     common::{
-        components::{BaseMonsterReward, PortalLevel, UpgradeCost, UpgradeSlot},
-        ChangeActiveLevel, GameState, RequestUpgrade, UpgradePortal, UpgradeableStat,
+        components::{
+            BaseMonsterReward, LockedFeature, PortalLevel, UpgradeCost, UpgradePrerequisites,
+            UpgradeSlot,
+        },
+        ChangeActiveLevel, Currencies, GameState, PriceStrategy, PurchaseAmount, RequestUpgrade,
+        UpgradePortal, UpgradeableStat,
     },
     wallet::Wallet,
 };
@@ -11,12 +15,15 @@ pub struct PortalPanelPlugin;
 
 impl Plugin for PortalPanelPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<UpgradeMultiplier>();
+
         app.add_systems(
             Update,
             (
                 attach_portal_observer,
                 close_portal_ui_actions,
                 update_upgrade_button_state,
+                update_multiplier_button_text,
                 update_portal_ui_stats,
             )
                 .run_if(in_state(GameState::Playing)),
@@ -24,6 +31,28 @@ impl Plugin for PortalPanelPlugin {
     }
 }
 
+/// How many levels the upgrade buttons of the portal panel buy per click.
+#[derive(Resource, Default)]
+struct UpgradeMultiplier(PurchaseAmount);
+
+impl UpgradeMultiplier {
+    fn next(&self) -> PurchaseAmount {
+        match self.0 {
+            PurchaseAmount::Levels(1) => PurchaseAmount::Levels(10),
+            PurchaseAmount::Levels(10) => PurchaseAmount::Levels(100),
+            PurchaseAmount::Levels(_) => PurchaseAmount::MaxAffordable,
+            PurchaseAmount::MaxAffordable => PurchaseAmount::Levels(1),
+        }
+    }
+
+    fn label(&self) -> String {
+        match self.0 {
+            PurchaseAmount::Levels(count) => format!("x{}", count),
+            PurchaseAmount::MaxAffordable => "Max".to_string(),
+        }
+    }
+}
+
 // Marker components
 #[derive(Component)]
 struct PortalClickObserverAttached;
@@ -43,6 +72,9 @@ enum PortalUpgradeTarget {
 #[derive(Component)]
 struct PortalUiUpgradeButton(PortalUpgradeTarget);
 
+#[derive(Component)]
+struct PortalUiMultiplierButton;
+
 #[derive(Component)]
 struct PortalUiScrim;
 
@@ -54,6 +86,8 @@ enum PortalUiStat {
     Level,
     Reward,
     Generic,
+    /// Unlock conditions of a locked upgrade, empty once unlocked.
+    Requirement,
 }
 
 #[derive(Component)]
@@ -79,8 +113,14 @@ fn on_portal_click(
     trigger: On<Pointer<Click>>,
     mut commands: Commands,
     portal_query: Query<(&PortalLevel, &UpgradeCost, &BaseMonsterReward, &Children)>,
-    upgrade_query: Query<(&UpgradeSlot, &UpgradeableStat)>,
+    upgrade_query: Query<(
+        &UpgradeSlot,
+        &UpgradeableStat,
+        Option<&UpgradePrerequisites>,
+        Has<LockedFeature>,
+    )>,
     ui_query: Query<Entity, With<PortalUiRoot>>,
+    multiplier: Res<UpgradeMultiplier>,
 ) {
     // If UI is already open, don't spawn another one
     if !ui_query.is_empty() {
@@ -91,23 +131,33 @@ fn on_portal_click(
     if let Ok((level, cost, reward_scaling, children)) = portal_query.get(entity) {
         let mut upgrades = Vec::new();
         for &child in children {
-            if let Ok((slot, stat)) = upgrade_query.get(child) {
-                upgrades.push((child, slot.clone(), stat.clone()));
+            if let Ok((slot, stat, prerequisites, locked)) = upgrade_query.get(child) {
+                let requirement = requirement_text(prerequisites, locked);
+                upgrades.push((child, slot.clone(), stat.clone(), requirement));
             }
         }
-        spawn_portal_ui(&mut commands, level, cost, reward_scaling, entity, upgrades);
+        spawn_portal_ui(
+            &mut commands,
+            &multiplier,
+            level,
+            cost,
+            reward_scaling,
+            entity,
+            upgrades,
+        );
     }
 }
 
 fn spawn_portal_ui(
     commands: &mut Commands,
+    multiplier: &UpgradeMultiplier,
     level: &PortalLevel,
     cost: &UpgradeCost,
     reward_scaling: &BaseMonsterReward,
     portal_entity: Entity,
-    upgrades: Vec<(Entity, UpgradeSlot, UpgradeableStat)>,
+    upgrades: Vec<(Entity, UpgradeSlot, UpgradeableStat, String)>,
 ) {
-    let current_reward = reward_scaling.0.calculate(level.active as f32);
+    let current_reward = reward_scaling.0.calculate_big(level.active as f32);
 
     commands
         .spawn((
@@ -132,7 +182,7 @@ fn spawn_portal_ui(
                 .spawn((
                     Node {
                         width: Val::Px(400.0),
-                        height: Val::Px(350.0),
+                        height: Val::Px(400.0),
                         flex_direction: FlexDirection::Column,
                         justify_content: JustifyContent::SpaceEvenly,
                         align_items: AlignItems::Center,
@@ -154,6 +204,29 @@ fn spawn_portal_ui(
                         TextColor(Color::WHITE),
                     ));
 
+                    // Multiplier Toggle
+                    p.spawn((
+                        Button,
+                        Node {
+                            width: Val::Px(60.0),
+                            height: Val::Px(30.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        BackgroundColor(Color::hsla(270.0, 0.6, 0.4, 1.0)),
+                        BorderRadius::all(Val::Px(5.0)),
+                        PortalUiMultiplierButton,
+                    ))
+                    .observe(on_multiplier_click)
+                    .with_children(|btn| {
+                        btn.spawn((
+                            Text::new(multiplier.label()),
+                            TextFont::default(),
+                            TextColor(Color::WHITE),
+                        ));
+                    });
+
                     // --- Section 1: Portal Level Control ---
                     p.spawn((Node {
                         width: Val::Percent(100.0),
@@ -216,7 +289,7 @@ fn spawn_portal_ui(
                                             PortalUiLink(portal_entity),
                                         ));
                                         col.spawn((
-                                            Text::new(format!("Reward: {:.2}", current_reward)),
+                                            Text::new(format!("Reward: {}", current_reward)),
                                             TextFont {
                                                 font_size: 14.0,
                                                 ..default()
@@ -257,13 +330,13 @@ fn spawn_portal_ui(
                             spawn_upgrade_button(
                                 row,
                                 PortalUpgradeTarget::Level,
-                                cost.current_price,
+                                &cost.current_price,
                                 portal_entity,
                             );
                         });
 
                     // --- Dynamic Generic Sections ---
-                    for (child_entity, slot, stat) in upgrades {
+                    for (child_entity, slot, stat, requirement) in upgrades {
                         p.spawn((Node {
                             width: Val::Percent(100.0),
                             flex_direction: FlexDirection::Row,
@@ -273,18 +346,34 @@ fn spawn_portal_ui(
                             ..default()
                         },))
                             .with_children(|row| {
-                                row.spawn((
-                                    Text::new(format!("{}: {:.2}", slot.name, stat.value)),
-                                    TextFont::default(),
-                                    TextColor(Color::WHITE),
-                                    PortalUiStat::Generic,
-                                    PortalUiLink(child_entity),
-                                ));
+                                row.spawn(Node {
+                                    flex_direction: FlexDirection::Column,
+                                    ..default()
+                                })
+                                .with_children(|col| {
+                                    col.spawn((
+                                        Text::new(format!("{}: {:.2}", slot.name, stat.value)),
+                                        TextFont::default(),
+                                        TextColor(Color::WHITE),
+                                        PortalUiStat::Generic,
+                                        PortalUiLink(child_entity),
+                                    ));
+                                    col.spawn((
+                                        Text::new(requirement),
+                                        TextFont {
+                                            font_size: 12.0,
+                                            ..default()
+                                        },
+                                        TextColor(Color::srgb(1.0, 0.6, 0.3)),
+                                        PortalUiStat::Requirement,
+                                        PortalUiLink(child_entity),
+                                    ));
+                                });
 
                                 spawn_upgrade_button(
                                     row,
                                     PortalUpgradeTarget::Generic,
-                                    stat.price,
+                                    &stat.price,
                                     child_entity,
                                 );
                             });
@@ -316,10 +405,21 @@ fn spawn_portal_ui(
         });
 }
 
+/// Spells out what a locked upgrade is waiting for.
+fn requirement_text(prerequisites: Option<&UpgradePrerequisites>, locked: bool) -> String {
+    match prerequisites {
+        Some(prerequisites) if locked => {
+            let conditions: Vec<String> = prerequisites.0.iter().map(ToString::to_string).collect();
+            format!("Requires: {}", conditions.join(", "))
+        }
+        _ => String::new(),
+    }
+}
+
 fn spawn_upgrade_button(
     parent: &mut bevy::ecs::hierarchy::ChildSpawnerCommands,
     target: PortalUpgradeTarget,
-    price: f32,
+    price: &Currencies,
     link: Entity,
 ) {
     parent
@@ -340,7 +440,7 @@ fn spawn_upgrade_button(
         .observe(on_upgrade_click)
         .with_children(|btn| {
             btn.spawn((
-                Text::new(format!("Upgrade ({:.0})", price)),
+                Text::new(format!("Upgrade ({})", price)),
                 TextFont {
                     font_size: 14.0,
                     ..default()
@@ -379,6 +479,32 @@ fn on_level_control_click(
     }
 }
 
+fn on_multiplier_click(
+    _trigger: On<Pointer<Click>>,
+    mut multiplier: ResMut<UpgradeMultiplier>,
+) {
+    multiplier.0 = multiplier.next();
+}
+
+/// Levels the current multiplier buys from `from_level`, and what they cost.
+fn quote(
+    amount: PurchaseAmount,
+    strategy: &PriceStrategy,
+    from_level: f32,
+    budget: &Currencies,
+) -> (u32, Currencies) {
+    let levels = amount.levels(strategy, from_level, budget);
+    (levels, strategy.cumulative_cost(from_level, levels))
+}
+
+fn upgrade_label(levels: u32, price: &Currencies) -> String {
+    if levels == 1 {
+        format!("Upgrade ({})", price)
+    } else {
+        format!("Upgrade x{} ({})", levels, price)
+    }
+}
+
 // Handle Upgrade Click
 fn on_upgrade_click(
     trigger: On<Pointer<Click>>,
@@ -386,25 +512,42 @@ fn on_upgrade_click(
     mut msg_generic: MessageWriter<RequestUpgrade>,
     button_query: Query<(&PortalUiLink, &PortalUiUpgradeButton)>,
     portal_query: Query<(&PortalLevel, &UpgradeCost)>,
-    stat_query: Query<&UpgradeableStat>,
+    stat_query: Query<&UpgradeableStat, Without<LockedFeature>>,
     wallet: Res<Wallet>,
+    multiplier: Res<UpgradeMultiplier>,
 ) {
     let button_entity = trigger.entity;
 
     if let Ok((link, button_type)) = button_query.get(button_entity) {
         match button_type.0 {
             PortalUpgradeTarget::Level => {
-                if let Ok((_, cost)) = portal_query.get(link.0) {
-                    if wallet.void_shards >= cost.current_price {
-                        msg_level.write(UpgradePortal);
+                if let Ok((level, cost)) = portal_query.get(link.0) {
+                    let (_, price) = quote(
+                        multiplier.0,
+                        &cost.strategy,
+                        level.max_unlocked as f32,
+                        &wallet.balances,
+                    );
+                    if wallet.can_afford(&price) {
+                        msg_level.write(UpgradePortal {
+                            portal_entity: link.0,
+                            amount: multiplier.0,
+                        });
                     }
                 }
             }
             PortalUpgradeTarget::Generic => {
                 if let Ok(stat) = stat_query.get(link.0) {
-                    if wallet.void_shards >= stat.price {
+                    let (_, price) = quote(
+                        multiplier.0,
+                        &stat.price_strategy,
+                        stat.level,
+                        &wallet.balances,
+                    );
+                    if wallet.can_afford(&price) {
                         msg_generic.write(RequestUpgrade {
                             upgrade_entity: link.0,
+                            amount: multiplier.0,
                         });
                     }
                 }
@@ -422,18 +565,32 @@ fn update_upgrade_button_state(
         &Children,
     )>,
     mut text_query: Query<&mut Text>,
-    portal_query: Query<&UpgradeCost>,
-    stat_query: Query<&UpgradeableStat>,
+    portal_query: Query<(&PortalLevel, &UpgradeCost)>,
+    stat_query: Query<(&UpgradeableStat, Has<LockedFeature>)>,
     wallet: Res<Wallet>,
+    multiplier: Res<UpgradeMultiplier>,
 ) {
     for (link, button_type, mut bg_color, children) in &mut button_query {
-        let price_opt = match button_type.0 {
-            PortalUpgradeTarget::Level => portal_query.get(link.0).map(|c| c.current_price).ok(),
-            PortalUpgradeTarget::Generic => stat_query.get(link.0).map(|s| s.price).ok(),
+        let quote_opt = match button_type.0 {
+            PortalUpgradeTarget::Level => portal_query
+                .get(link.0)
+                .map(|(level, cost)| {
+                    let from_level = level.max_unlocked as f32;
+                    let budget = &wallet.balances;
+                    (quote(multiplier.0, &cost.strategy, from_level, budget), false)
+                })
+                .ok(),
+            PortalUpgradeTarget::Generic => stat_query
+                .get(link.0)
+                .map(|(s, locked)| {
+                    let budget = &wallet.balances;
+                    (quote(multiplier.0, &s.price_strategy, s.level, budget), locked)
+                })
+                .ok(),
         };
 
-        if let Some(price) = price_opt {
-            let affordable = wallet.void_shards >= price;
+        if let Some(((levels, price), locked)) = quote_opt {
+            let affordable = !locked && levels > 0 && wallet.can_afford(&price);
 
             // Update Color
             *bg_color = if affordable {
@@ -445,18 +602,45 @@ fn update_upgrade_button_state(
             // Update Text
             for &child in children {
                 if let Ok(mut text) = text_query.get_mut(child) {
-                    **text = format!("Upgrade ({:.0})", price);
+                    **text = if locked {
+                        "Locked".to_string()
+                    } else {
+                        upgrade_label(levels, &price)
+                    };
                 }
             }
         }
     }
 }
 
+fn update_multiplier_button_text(
+    multiplier: Res<UpgradeMultiplier>,
+    button_query: Query<&Children, With<PortalUiMultiplierButton>>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !multiplier.is_changed() {
+        return;
+    }
+
+    for children in &button_query {
+        for &child in children {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                **text = multiplier.label();
+            }
+        }
+    }
+}
+
 // Update Stat Texts
 fn update_portal_ui_stats(
     mut query: Query<(&PortalUiLink, &PortalUiStat, &mut Text)>,
     portal_query: Query<(&PortalLevel, &BaseMonsterReward)>,
-    upgrade_query: Query<(&UpgradeSlot, &UpgradeableStat)>,
+    upgrade_query: Query<(
+        &UpgradeSlot,
+        &UpgradeableStat,
+        Option<&UpgradePrerequisites>,
+        Has<LockedFeature>,
+    )>,
 ) {
     for (link, stat_type, mut text) in &mut query {
         match stat_type {
@@ -467,15 +651,20 @@ fn update_portal_ui_stats(
             }
             PortalUiStat::Reward => {
                 if let Ok((level, reward_scaling)) = portal_query.get(link.0) {
-                    let current_reward = reward_scaling.0.calculate(level.active as f32);
-                    **text = format!("Reward: {:.2}", current_reward);
+                    let current_reward = reward_scaling.0.calculate_big(level.active as f32);
+                    **text = format!("Reward: {}", current_reward);
                 }
             }
             PortalUiStat::Generic => {
-                if let Ok((slot, stat)) = upgrade_query.get(link.0) {
+                if let Ok((slot, stat, ..)) = upgrade_query.get(link.0) {
                     **text = format!("{}: {:.2}", slot.name, stat.value);
                 }
             }
+            PortalUiStat::Requirement => {
+                if let Ok((.., prerequisites, locked)) = upgrade_query.get(link.0) {
+                    **text = requirement_text(prerequisites, locked);
+                }
+            }
         }
     }
 }
@@ -538,7 +727,7 @@ mod tests {
         app.init_state::<GameState>();
 
         app.insert_resource(Wallet {
-            void_shards: 1000.0,
+            balances: 1000.0.into(),
         });
         app.add_message::<UpgradePortal>();
         app.add_message::<RequestUpgrade>();
@@ -562,8 +751,9 @@ mod tests {
                     strategy: GrowthStrategy::Linear {
                         base: 100.0,
                         coefficient: 1.5,
-                    },
-                    current_price: 100.0,
+                    }
+                    .into(),
+                    current_price: 100.0.into(),
                 },
                 BaseMonsterHealth::default(),
                 BaseMonsterReward::default(),
@@ -0,0 +1,164 @@
+use {
+    bevy::prelude::*,
+    common::{components::PortalLevel, Currency, GameState, PurchaseAmount},
+    prestige::{BuyPrestigeUpgrade, Prestige, PrestigeBonus, PrestigeSettings, RequestPrestige},
+    wallet::Wallet,
+};
+
+pub struct PrestigePanelPlugin;
+
+impl Plugin for PrestigePanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Playing), spawn_prestige_ui)
+            .add_systems(
+                Update,
+                update_prestige_ui.run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), despawn_prestige_ui);
+    }
+}
+
+// Marker components
+#[derive(Component)]
+struct PrestigeUiRoot;
+
+#[derive(Component)]
+struct PrestigeCollapseButton;
+
+#[derive(Component)]
+struct PrestigeUpgradeButton(PrestigeBonus);
+
+#[derive(Component, Clone, Copy)]
+enum PrestigeUiText {
+    Points,
+    Collapse,
+    Upgrade(PrestigeBonus),
+}
+
+fn spawn_prestige_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(10.0),
+                bottom: Val::Px(10.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Stretch,
+                row_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BackgroundColor(Color::hsla(270.0, 0.5, 0.2, 0.9)),
+            BorderRadius::all(Val::Px(10.0)),
+            PrestigeUiRoot,
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Text::new("Prestige points: 0"),
+                TextFont::default(),
+                TextColor(Color::WHITE),
+                PrestigeUiText::Points,
+            ));
+
+            spawn_button(p, PrestigeCollapseButton, PrestigeUiText::Collapse)
+                .observe(on_collapse_click);
+
+            for bonus in PrestigeBonus::ALL {
+                spawn_button(p, PrestigeUpgradeButton(bonus), PrestigeUiText::Upgrade(bonus))
+                    .observe(on_upgrade_click);
+            }
+        });
+}
+
+fn spawn_button<'a>(
+    parent: &'a mut bevy::ecs::hierarchy::ChildSpawnerCommands,
+    marker: impl Bundle,
+    text: PrestigeUiText,
+) -> EntityCommands<'a> {
+    let mut button = parent.spawn((
+        Button,
+        Node {
+            height: Val::Px(30.0),
+            padding: UiRect::horizontal(Val::Px(8.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BackgroundColor(Color::hsla(270.0, 0.6, 0.4, 1.0)),
+        BorderRadius::all(Val::Px(5.0)),
+        marker,
+    ));
+    button.with_children(|btn| {
+        btn.spawn((
+            Text::new(""),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            text,
+        ));
+    });
+    button
+}
+
+fn on_collapse_click(_trigger: On<Pointer<Click>>, mut events: MessageWriter<RequestPrestige>) {
+    events.write(RequestPrestige);
+}
+
+fn on_upgrade_click(
+    trigger: On<Pointer<Click>>,
+    mut events: MessageWriter<BuyPrestigeUpgrade>,
+    button_query: Query<&PrestigeUpgradeButton>,
+) {
+    if let Ok(button) = button_query.get(trigger.entity) {
+        events.write(BuyPrestigeUpgrade {
+            bonus: button.0,
+            amount: PurchaseAmount::default(),
+        });
+    }
+}
+
+fn update_prestige_ui(
+    prestige: Res<Prestige>,
+    settings: Res<PrestigeSettings>,
+    wallet: Res<Wallet>,
+    portal_query: Query<&PortalLevel>,
+    mut text_query: Query<(&mut Text, &PrestigeUiText)>,
+) {
+    let highest_level = portal_query
+        .iter()
+        .map(|level| level.max_unlocked)
+        .max()
+        .unwrap_or_default();
+
+    for (mut text, kind) in &mut text_query {
+        let new_text = match kind {
+            PrestigeUiText::Points => {
+                format!("Prestige points: {}", wallet.get(Currency::PrestigePoints))
+            }
+            PrestigeUiText::Collapse => {
+                let points = settings.points_for(highest_level);
+                if points.is_zero() {
+                    format!("Collapse (needs level {})", settings.min_level)
+                } else {
+                    format!("Collapse portals (+{})", points)
+                }
+            }
+            PrestigeUiText::Upgrade(bonus) => match prestige.upgrades.get(bonus) {
+                Some(stat) => format!("{} x{:.2} ({})", bonus.label(), stat.value, stat.price),
+                None => bonus.label().to_string(),
+            },
+        };
+        // Only write on change so `Changed<Text>` stays meaningful
+        if text.0 != new_text {
+            text.0 = new_text;
+        }
+    }
+}
+
+fn despawn_prestige_ui(mut commands: Commands, query: Query<Entity, With<PrestigeUiRoot>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,249 @@
+use {
+    bevy::prelude::*,
+    common::{GameState, OfflineProgressReport},
+};
+
+pub struct WelcomeBackPanelPlugin;
+
+impl Plugin for WelcomeBackPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (show_welcome_back_panel, close_welcome_back_actions)
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+// Marker components
+#[derive(Component)]
+struct WelcomeBackUiRoot;
+
+#[derive(Component)]
+struct WelcomeBackCloseButton;
+
+fn show_welcome_back_panel(
+    mut commands: Commands,
+    mut reports: MessageReader<OfflineProgressReport>,
+    ui_query: Query<Entity, With<WelcomeBackUiRoot>>,
+) {
+    // Only one report is sent per load, keep the latest if there are more
+    let Some(report) = reports.read().last() else {
+        return;
+    };
+
+    for entity in &ui_query {
+        commands.entity(entity).despawn();
+    }
+    spawn_welcome_back_ui(&mut commands, report);
+}
+
+fn spawn_welcome_back_ui(commands: &mut Commands, report: &OfflineProgressReport) {
+    let away_text = if report.counted_secs < report.away_secs {
+        format!(
+            "You were away for {} (counted {})",
+            format_duration(report.away_secs),
+            format_duration(report.counted_secs)
+        )
+    } else {
+        format!("You were away for {}", format_duration(report.away_secs))
+    };
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ZIndex(200),
+            // Dim the game behind the summary
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+            WelcomeBackUiRoot,
+        ))
+        .observe(on_scrim_click)
+        .with_children(|parent| {
+            // The Panel
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(400.0),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(10.0),
+                        padding: UiRect::all(Val::Px(20.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::hsla(270.0, 0.5, 0.2, 0.9)),
+                    BorderRadius::all(Val::Px(10.0)),
+                ))
+                .observe(block_click)
+                .with_children(|p| {
+                    p.spawn((
+                        Text::new("Welcome back!"),
+                        TextFont {
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+
+                    p.spawn((
+                        Text::new(away_text),
+                        TextFont::default(),
+                        TextColor(Color::srgb(0.8, 0.8, 1.0)),
+                    ));
+
+                    p.spawn((
+                        Text::new(format!("Monsters slain: {}", report.monsters_killed)),
+                        TextFont::default(),
+                        TextColor(Color::WHITE),
+                    ));
+
+                    p.spawn((
+                        Text::new(format!("Void shards earned: {}", report.void_shards)),
+                        TextFont::default(),
+                        TextColor(Color::WHITE),
+                    ));
+
+                    // Close Button
+                    p.spawn((
+                        Button,
+                        Node {
+                            width: Val::Px(100.0),
+                            height: Val::Px(40.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            margin: UiRect::top(Val::Px(10.0)),
+                            ..default()
+                        },
+                        BackgroundColor(Color::hsla(270.0, 0.6, 0.4, 1.0)),
+                        BorderRadius::all(Val::Px(5.0)),
+                        WelcomeBackCloseButton,
+                    ))
+                    .with_children(|btn| {
+                        btn.spawn((
+                            Text::new("Collect"),
+                            TextFont::default(),
+                            TextColor(Color::WHITE),
+                        ));
+                    });
+                });
+        });
+}
+
+/// `3h 12m`, `5m 3s`, `42s`
+fn format_duration(secs: u64) -> String {
+    let hours = secs / 3600;
+    let minutes = (secs % 3600) / 60;
+    let seconds = secs % 60;
+
+    if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+fn on_scrim_click(
+    trigger: On<Pointer<Click>>,
+    mut commands: Commands,
+    query: Query<Entity, With<WelcomeBackUiRoot>>,
+) {
+    if let Ok(entity) = query.get(trigger.entity) {
+        commands.entity(entity).despawn();
+    }
+}
+
+// Block clicks from propagating
+fn block_click(mut trigger: On<Pointer<Click>>) {
+    trigger.propagate(false);
+}
+
+fn close_welcome_back_actions(
+    mut commands: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<WelcomeBackCloseButton>)>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    root_query: Query<Entity, With<WelcomeBackUiRoot>>,
+) {
+    let pressed = interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed);
+
+    if pressed || keyboard_input.just_pressed(KeyCode::Escape) {
+        for entity in &root_query {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, bevy::state::app::StatesPlugin};
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(42), "42s");
+        assert_eq!(format_duration(303), "5m 3s");
+        assert_eq!(format_duration(11_520), "3h 12m");
+    }
+
+    #[test]
+    fn test_welcome_back_panel_lifecycle() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(StatesPlugin)
+            .add_plugins(AssetPlugin::default());
+
+        app.init_resource::<ButtonInput<KeyCode>>();
+        app.init_state::<GameState>();
+        app.insert_state(GameState::Playing);
+        app.add_message::<OfflineProgressReport>();
+        app.add_plugins(WelcomeBackPanelPlugin);
+
+        app.update();
+        assert!(app
+            .world_mut()
+            .query::<&WelcomeBackUiRoot>()
+            .iter(app.world())
+            .next()
+            .is_none());
+
+        app.world_mut().write_message(OfflineProgressReport {
+            away_secs: 40_000,
+            counted_secs: 28_800,
+            monsters_killed: 12,
+            void_shards: 340.0.into(),
+        });
+        app.update();
+
+        let texts: Vec<String> = app
+            .world_mut()
+            .query::<&Text>()
+            .iter(app.world())
+            .map(|text| text.0.clone())
+            .collect();
+        assert!(texts.contains(&"You were away for 11h 6m (counted 8h 0m)".to_string()));
+        assert!(texts.contains(&"Void shards earned: 340".to_string()));
+
+        // Simulate Esc
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::Escape);
+        app.update();
+
+        assert!(
+            app.world_mut()
+                .query::<&WelcomeBackUiRoot>()
+                .iter(app.world())
+                .next()
+                .is_none(),
+            "Panel should be gone after Esc"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use {
     bevy::prelude::*,
-    common::{GameState, SpawnFloatingText, VoidGameStage},
+    common::{GameState, SpawnFloatingText, VoidGameStage, WeaponMasteryLeveledUp},
 };
 
 pub struct VfxPlugin;
@@ -13,7 +13,9 @@ impl Plugin for VfxPlugin {
         app.add_systems(
             Update,
             (
-                spawn_floating_text.in_set(VoidGameStage::Effect),
+                (show_mastery_level_up, spawn_floating_text)
+                    .chain()
+                    .in_set(VoidGameStage::Effect),
                 animate_floating_text.in_set(VoidGameStage::Actions),
                 cleanup_floating_text.in_set(VoidGameStage::FrameEnd),
             )
@@ -35,6 +37,19 @@ pub struct FloatingTextAnim {
 }
 
 // Systems
+fn show_mastery_level_up(
+    mut messages: MessageReader<WeaponMasteryLeveledUp>,
+    mut vfx_events: MessageWriter<SpawnFloatingText>,
+) {
+    for msg in messages.read() {
+        vfx_events.write(SpawnFloatingText::mastery_level_up(
+            msg.kind,
+            msg.level,
+            msg.location + Vec3::new(0.0, 30.0, 0.0),
+        ));
+    }
+}
+
 fn spawn_floating_text(mut commands: Commands, mut messages: MessageReader<SpawnFloatingText>) {
     for msg in messages.read() {
         // random x/y jitter could be nice, but not strictly requested.
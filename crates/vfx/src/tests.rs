@@ -1,7 +1,7 @@
 use {
     crate::*,
     bevy::state::app::StatesPlugin,
-    common::{SpawnFloatingText, VoidGameStage},
+    common::{SpawnFloatingText, VoidGameStage, WeaponKind, WeaponMasteryLeveledUp},
     std::{thread, time::Duration},
 };
 
@@ -11,6 +11,8 @@ fn test_spawn_floating_text() {
     app.add_plugins(MinimalPlugins);
     app.add_plugins(StatesPlugin); // Required for init_state
     app.add_plugins(VfxPlugin);
+    app.add_message::<SpawnFloatingText>();
+    app.add_message::<WeaponMasteryLeveledUp>();
 
     // We need to initialize state and schedule
     app.init_state::<GameState>();
@@ -55,6 +57,8 @@ fn test_animate_and_cleanup() {
     app.add_plugins(MinimalPlugins);
     app.add_plugins(StatesPlugin);
     app.add_plugins(VfxPlugin);
+    app.add_message::<SpawnFloatingText>();
+    app.add_message::<WeaponMasteryLeveledUp>();
 
     app.init_state::<GameState>();
     app.configure_sets(
@@ -119,3 +123,29 @@ fn test_animate_and_cleanup() {
     // Check despawned
     assert!(app.world().get_entity(entity).is_err());
 }
+
+#[test]
+fn test_mastery_level_up_spawns_floating_text() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(StatesPlugin);
+    app.add_plugins(VfxPlugin);
+    app.add_message::<SpawnFloatingText>();
+    app.add_message::<WeaponMasteryLeveledUp>();
+
+    app.init_state::<GameState>();
+    app.insert_state(GameState::Playing);
+    app.update();
+
+    app.world_mut().write_message(WeaponMasteryLeveledUp {
+        entity: Entity::PLACEHOLDER,
+        kind: WeaponKind::Ranged,
+        level: 3,
+        location: Vec3::ZERO,
+    });
+    app.update();
+
+    let mut query = app.world_mut().query::<(&Text2d, &FloatingText)>();
+    let (text, _) = query.single(app.world()).unwrap();
+    assert_eq!(text.0, "Ranged mastery 3!");
+}
@@ -2,33 +2,52 @@
 
 use {
     bevy::prelude::*,
-    common::{MonsterKilled, MonsterScavenged, Reward, SpawnFloatingText},
+    common::{
+        BigNumber, Currencies, Currency, GrantCurrency, MonsterKilled, MonsterScavenged,
+        PrestigeMultipliers, Reward, SpawnFloatingText,
+    },
 };
 
 pub struct VoidWalletPlugin;
 
 impl Plugin for VoidWalletPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<Wallet>().add_systems(
-            Update,
-            (
-                update_wallet_from_monster_killed,
-                update_wallet_from_scavenge,
-            ),
-        );
+        app.init_resource::<Wallet>()
+            .add_message::<MonsterScavenged>()
+            .add_message::<GrantCurrency>()
+            .add_systems(
+                Update,
+                (
+                    update_wallet_from_monster_killed,
+                    update_wallet_from_scavenge,
+                    grant_currency,
+                ),
+            );
     }
 }
 
-#[derive(Resource, Debug)]
+/// Balance of every currency the player holds.
+#[derive(Resource, Debug, Default)]
 pub struct Wallet {
-    pub void_shards: f32,
+    pub balances: Currencies,
 }
 
-impl Default for Wallet {
-    fn default() -> Self {
-        Self {
-            void_shards: 999_999_999_000.0,
+impl Wallet {
+    pub fn get(&self, currency: Currency) -> BigNumber {
+        self.balances.get(currency)
+    }
+
+    pub fn can_afford(&self, price: &Currencies) -> bool {
+        self.balances.covers(price)
+    }
+
+    /// Pays `price` if every currency of it is covered, returns whether it was paid.
+    pub fn spend(&mut self, price: &Currencies) -> bool {
+        if !self.can_afford(price) {
+            return false;
         }
+        self.balances -= price;
+        true
     }
 }
 
@@ -36,21 +55,17 @@ fn update_wallet_from_monster_killed(
     mut events: MessageReader<MonsterKilled>,
     mut wallet: ResMut<Wallet>,
     reward_query: Query<(&Reward, &Transform)>,
+    multipliers: Option<Res<PrestigeMultipliers>>,
     mut vfx_events: MessageWriter<SpawnFloatingText>,
 ) {
     for event in events.read() {
         if let Ok((reward, transform)) = reward_query.get(event.entity) {
-            wallet.void_shards += reward.0;
+            let amount = with_prestige_bonus(&reward.0, multipliers.as_deref());
+            wallet.balances += &amount;
 
-            vfx_events.write(SpawnFloatingText::void_shards_reward(
-                reward.0,
-                transform.translation,
-            ));
+            vfx_events.write(SpawnFloatingText::reward(&amount, transform.translation));
 
-            info!(
-                "Wallet updated: +{} void shards. Total: {}",
-                reward.0, wallet.void_shards
-            );
+            info!("Wallet updated: +{}. Total: {}", amount, wallet.balances);
         } else {
             warn!(
                 "MonsterKilled event received for entity {:?} but no Reward/Dead component found",
@@ -63,25 +78,43 @@ fn update_wallet_from_monster_killed(
 fn update_wallet_from_scavenge(
     mut events: MessageReader<MonsterScavenged>,
     mut wallet: ResMut<Wallet>,
+    multipliers: Option<Res<PrestigeMultipliers>>,
     mut vfx_events: MessageWriter<SpawnFloatingText>,
 ) {
     for event in events.read() {
-        wallet.void_shards += event.amount;
+        let amount = with_prestige_bonus(&event.amount, multipliers.as_deref());
+        wallet.balances += &amount;
 
         vfx_events.write(SpawnFloatingText {
-            text: format!("+{:.0}", event.amount),
+            text: format!("+{}", amount),
             location: event.location,
             color: Color::srgb(0.7, 0.7, 0.7), // Scavenge color
             size: 18.0,
         });
 
-        info!(
-            "Wallet scavenge update: +{}. Total: {}",
-            event.amount, wallet.void_shards
-        );
+        info!("Wallet scavenge update: +{}. Total: {}", amount, wallet.balances);
     }
 }
 
+/// Grants are paid as they are, prestige bonuses don't apply.
+fn grant_currency(mut events: MessageReader<GrantCurrency>, mut wallet: ResMut<Wallet>) {
+    for event in events.read() {
+        wallet.balances += &event.amount;
+        info!("Wallet granted {}. Total: {}", event.amount, wallet.balances);
+    }
+}
+
+/// Scales the void shards of a payout, other currencies are paid as they are.
+/// Payouts are unchanged until the prestige plugin provides its multipliers.
+fn with_prestige_bonus(
+    amount: &Currencies,
+    multipliers: Option<&PrestigeMultipliers>,
+) -> Currencies {
+    let multiplier = multipliers.map_or(1.0, |m| m.void_shards as f64);
+    let shards = amount.get(Currency::VoidShards) * multiplier;
+    amount.clone().with(Currency::VoidShards, shards)
+}
+
 #[cfg(test)]
 mod tests {
     use {super::*, common::MarkedForCleanUp};
@@ -96,13 +129,13 @@ mod tests {
             .add_message::<SpawnFloatingText>();
 
         // Check initial state
-        assert_eq!(app.world().resource::<Wallet>().void_shards, 0.0);
+        assert_eq!(app.world().resource::<Wallet>().get(Currency::VoidShards), 0.0);
 
         // Spawn a dead monster with reward
         let entity1 = app
             .world_mut()
             .spawn((
-                Reward(10.0),
+                Reward(10.0.into()),
                 Transform::default(),
                 MarkedForCleanUp {
                     despawn_timer: Timer::from_seconds(1.0, TimerMode::Once),
@@ -118,13 +151,13 @@ mod tests {
         app.update();
 
         // Check updated state
-        assert_eq!(app.world().resource::<Wallet>().void_shards, 10.0);
+        assert_eq!(app.world().resource::<Wallet>().get(Currency::VoidShards), 10.0);
 
         // Spawn another dead monster
         let entity2 = app
             .world_mut()
             .spawn((
-                Reward(5.5),
+                Reward(5.5.into()),
                 Transform::default(),
                 MarkedForCleanUp {
                     despawn_timer: Timer::from_seconds(1.0, TimerMode::Once),
@@ -138,7 +171,7 @@ mod tests {
 
         app.update();
 
-        assert_eq!(app.world().resource::<Wallet>().void_shards, 15.5);
+        assert_eq!(app.world().resource::<Wallet>().get(Currency::VoidShards), 15.5);
     }
 
     #[test]
@@ -152,16 +185,90 @@ mod tests {
         app.add_message::<MonsterKilled>()
             .add_message::<SpawnFloatingText>();
 
-        assert_eq!(app.world().resource::<Wallet>().void_shards, 0.0);
+        assert_eq!(app.world().resource::<Wallet>().get(Currency::VoidShards), 0.0);
 
         let mut messages = app.world_mut().resource_mut::<Messages<MonsterScavenged>>();
         messages.write(MonsterScavenged {
-            amount: 12.5,
+            amount: 12.5.into(),
+            location: Vec3::ZERO,
+        });
+
+        app.update();
+
+        assert_eq!(app.world().resource::<Wallet>().get(Currency::VoidShards), 12.5);
+    }
+
+    #[test]
+    fn test_wallet_applies_prestige_multiplier() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(VoidWalletPlugin)
+            .add_message::<MonsterKilled>()
+            .add_message::<MonsterScavenged>()
+            .add_message::<SpawnFloatingText>();
+        app.insert_resource(Wallet {
+            balances: Currencies::default(),
+        });
+        app.insert_resource(PrestigeMultipliers {
+            void_shards: 2.0,
+            ..default()
+        });
+
+        let entity = app
+            .world_mut()
+            .spawn((
+                Reward(Currencies::from(10.0).with(Currency::Essence, 1.0)),
+                Transform::default(),
+            ))
+            .id();
+        app.world_mut().write_message(MonsterKilled { entity });
+        app.world_mut().write_message(MonsterScavenged {
+            amount: 3.0.into(),
             location: Vec3::ZERO,
         });
 
         app.update();
 
-        assert_eq!(app.world().resource::<Wallet>().void_shards, 12.5);
+        // Only void shards are multiplied
+        let wallet = app.world().resource::<Wallet>();
+        assert_eq!(wallet.get(Currency::VoidShards), 26.0);
+        assert_eq!(wallet.get(Currency::Essence), 1.0);
+    }
+
+    #[test]
+    fn test_grant_currency() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(VoidWalletPlugin)
+            .add_message::<MonsterKilled>()
+            .add_message::<SpawnFloatingText>();
+        app.insert_resource(PrestigeMultipliers {
+            void_shards: 2.0,
+            ..default()
+        });
+
+        app.world_mut().write_message(GrantCurrency {
+            amount: Currencies::from(50.0).with(Currency::Essence, 5.0),
+        });
+        app.update();
+
+        assert_eq!(
+            app.world().resource::<Wallet>().balances,
+            Currencies::from(50.0).with(Currency::Essence, 5.0)
+        );
+    }
+
+    #[test]
+    fn test_spend_needs_every_currency() {
+        let mut wallet = Wallet {
+            balances: Currencies::from(100.0).with(Currency::Essence, 2.0),
+        };
+        let price = Currencies::from(40.0).with(Currency::Essence, 3.0);
+
+        assert!(!wallet.spend(&price));
+        assert_eq!(wallet.get(Currency::VoidShards), 100.0);
+
+        assert!(wallet.spend(&Currencies::from(40.0).with(Currency::Essence, 2.0)));
+        assert_eq!(wallet.balances, Currencies::from(60.0));
     }
 }